@@ -0,0 +1,80 @@
+/// Playback speed factors inside the loop, in the order `L` cycles through them
+pub const LOOP_SLOWDOWNS: &[f32] = &[1.0, 0.9, 0.75, 0.5];
+
+/// A section of the playing episode repeated until cleared
+#[derive(Debug, Clone, PartialEq)]
+pub struct AbLoop {
+    pub audio_url: String,
+    pub a: f64,
+    /// End of the loop, once it has been set
+    pub b: Option<f64>,
+    /// Speed outside the loop
+    pub base_speed: f32,
+}
+
+impl AbLoop {
+    pub fn new(audio_url: String, a: f64, base_speed: f32) -> Self {
+        Self { audio_url, a, b: None, base_speed }
+    }
+
+    pub fn contains(&self, position_secs: f64) -> bool {
+        self.b.is_some_and(|b| position_secs >= self.a && position_secs < b)
+    }
+
+    /// Where to seek back to once playback reaches the end of the loop
+    pub fn restart_at(&self, position_secs: f64) -> Option<f64> {
+        let b = self.b?;
+        (position_secs >= b).then_some(self.a)
+    }
+
+    /// Speed to play at this position, slowed down by `slowdown` inside the loop
+    pub fn speed_at(&self, position_secs: f64, slowdown: f32) -> f32 {
+        if self.contains(position_secs) {
+            self.base_speed * slowdown
+        } else {
+            self.base_speed
+        }
+    }
+}
+
+/// The next loop slowdown after `current`, wrapping around to normal speed
+pub fn next_slowdown(current: f32) -> f32 {
+    LOOP_SLOWDOWNS
+        .iter()
+        .copied()
+        .find(|&s| s < current - f32::EPSILON)
+        .unwrap_or(LOOP_SLOWDOWNS[0])
+}
+
+/// First and last columns of the loop on a progress bar `width` cells wide.
+/// Before B is set, both are A's column.
+pub fn loop_columns(ab_loop: &AbLoop, duration_secs: u64, width: u16) -> Option<(u16, u16)> {
+    if duration_secs == 0 || width == 0 {
+        return None;
+    }
+    let col = |secs: f64| ((secs / duration_secs as f64 * width as f64) as u16).min(width - 1);
+    let a = col(ab_loop.a);
+    Some((a, ab_loop.b.map(col).unwrap_or(a)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loop_restarts_and_slows_down() {
+        let mut ab = AbLoop::new("a.mp3".to_string(), 10.0, 1.5);
+        assert_eq!(ab.restart_at(30.0), None);
+        assert_eq!(loop_columns(&ab, 100, 50), Some((5, 5)));
+
+        ab.b = Some(20.0);
+        assert_eq!(ab.restart_at(19.9), None);
+        assert_eq!(ab.restart_at(20.0), Some(10.0));
+        assert_eq!(ab.speed_at(15.0, 0.5), 0.75);
+        assert_eq!(ab.speed_at(25.0, 0.5), 1.5);
+        assert_eq!(loop_columns(&ab, 100, 50), Some((5, 10)));
+
+        assert_eq!(next_slowdown(1.0), 0.9);
+        assert_eq!(next_slowdown(0.5), 1.0);
+    }
+}
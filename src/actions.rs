@@ -1,5 +1,4 @@
 use crate::app::App;
-use crate::playback::Player;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Action {
@@ -16,9 +15,21 @@ pub enum Action {
     AddFeed,
     DeletePodcast,
     RefreshFeed,
+    RefreshAll,
+    Sync,
 
     // Episode Management
     TogglePlayed,
+    ToggleStarred,
+    Enqueue,
+    MarkOlderThan,
+
+    // Episode Filters
+    FilterUnplayed,
+    FilterInProgress,
+    FilterDownloaded,
+    FilterStarred,
+    ClearFilters,
 
     // Playback
     PlayPause,
@@ -27,13 +38,37 @@ pub enum Action {
     SeekBackward,
     SpeedUp,
     SpeedDown,
+    NextChapter,
+    PrevChapter,
+    VolumeUp,
+    VolumeDown,
+    CycleSleepTimer,
+    UndoChapterSkip,
+
+    // Bookmarks
+    AddBookmark,
+    ShowBookmarks,
+    ExportBookmarks,
+
+    // Clips
+    MarkClipStart,
+    ExportClip,
+
+    // A-B loop
+    SetLoopPoint,
+    CycleLoopSlowdown,
 
     // Screen/Mode
     SwitchToBrowse,
     SwitchToPodcasts,
+    SwitchToInbox,
+    SwitchToContinueListening,
+    SwitchToHistory,
+    SwitchToStats,
+    SwitchToNowPlaying,
+    SwitchToBookmarks,
 
-    // Browse
-    #[allow(dead_code)]
+    // Browse (or episode text filter on the Podcasts screen)
     StartSearch,
     #[allow(dead_code)]
     SubscribeFromBrowse,
@@ -46,7 +81,7 @@ pub enum Action {
 }
 
 impl Action {
-    pub fn execute(&self, app: &mut App, player: &Player) {
+    pub fn execute(&self, app: &mut App) {
         match self {
             Action::Quit => {
                 // Handled in main loop
@@ -54,6 +89,12 @@ impl Action {
             Action::MoveUp => {
                 match app.screen {
                     crate::app::AppScreen::Browse => app.browse.move_up(),
+                    crate::app::AppScreen::Inbox => app.inbox.move_up(),
+                    crate::app::AppScreen::ContinueListening => app.continue_listening.move_up(),
+                    crate::app::AppScreen::History => app.history.move_up(),
+                    crate::app::AppScreen::Stats => {}
+                    crate::app::AppScreen::NowPlaying => app.now_playing.scroll_up(),
+                    crate::app::AppScreen::Bookmarks => app.bookmarks.move_up(),
                     crate::app::AppScreen::Podcasts => {
                         match app.focus {
                             crate::app::PaneFocus::Left => app.move_podcast_up(),
@@ -65,6 +106,12 @@ impl Action {
             Action::MoveDown => {
                 match app.screen {
                     crate::app::AppScreen::Browse => app.browse.move_down(),
+                    crate::app::AppScreen::Inbox => app.inbox.move_down(),
+                    crate::app::AppScreen::ContinueListening => app.continue_listening.move_down(),
+                    crate::app::AppScreen::History => app.history.move_down(),
+                    crate::app::AppScreen::Stats => {}
+                    crate::app::AppScreen::NowPlaying => app.now_playing.scroll_down(),
+                    crate::app::AppScreen::Bookmarks => app.bookmarks.move_down(),
                     crate::app::AppScreen::Podcasts => {
                         match app.focus {
                             crate::app::PaneFocus::Left => app.move_podcast_down(),
@@ -73,6 +120,35 @@ impl Action {
                     }
                 }
             }
+            Action::GoToTop if app.screen == crate::app::AppScreen::Inbox => {
+                app.inbox.selected_index = 0;
+            }
+            Action::GoToBottom if app.screen == crate::app::AppScreen::Inbox => {
+                app.inbox.selected_index = app.inbox.entries.len().saturating_sub(1);
+            }
+            Action::GoToTop if app.screen == crate::app::AppScreen::ContinueListening => {
+                app.continue_listening.selected_index = 0;
+            }
+            Action::GoToBottom if app.screen == crate::app::AppScreen::ContinueListening => {
+                app.continue_listening.selected_index =
+                    app.continue_listening.entries.len().saturating_sub(1);
+            }
+            Action::GoToTop if app.screen == crate::app::AppScreen::History => {
+                app.history.selected_index = 0;
+            }
+            Action::GoToBottom if app.screen == crate::app::AppScreen::History => {
+                app.history.selected_index = app.history.entries.len().saturating_sub(1);
+            }
+            Action::GoToTop if app.screen == crate::app::AppScreen::Bookmarks => {
+                app.bookmarks.selected_index = 0;
+            }
+            Action::GoToBottom if app.screen == crate::app::AppScreen::Bookmarks => {
+                app.bookmarks.selected_index = app.bookmarks.bookmarks.len().saturating_sub(1);
+            }
+            Action::GoToTop if app.screen == crate::app::AppScreen::NowPlaying => {
+                app.now_playing.notes_scroll = 0;
+            }
+            Action::GoToBottom if app.screen == crate::app::AppScreen::NowPlaying => {}
             Action::GoToTop => {
                 match app.focus {
                     crate::app::PaneFocus::Left => app.selected_podcast_index = 0,
@@ -85,26 +161,79 @@ impl Action {
                         app.selected_podcast_index = app.podcasts.len().saturating_sub(1);
                     }
                     crate::app::PaneFocus::Right => {
-                        if let Some(podcast) = app.selected_podcast() {
-                            app.selected_episode_index = podcast.episodes.len().saturating_sub(1);
-                        }
+                        app.selected_episode_index = app.visible_episode_indices().len().saturating_sub(1);
                     }
                 }
             }
             Action::SwitchFocus => {
-                app.switch_focus();
+                if app.is_browse_screen() {
+                    app.browse.switch_focus();
+                } else {
+                    app.switch_focus();
+                }
             }
             Action::AddFeed => {
                 app.start_add_feed();
             }
+            Action::DeletePodcast if app.screen == crate::app::AppScreen::Bookmarks => {
+                if let Some(bookmark) = app.bookmarks.selected().cloned() {
+                    app.delete_bookmark(&bookmark);
+                }
+            }
             Action::DeletePodcast => {
                 app.delete_podcast();
             }
-            Action::RefreshFeed => {
+            Action::RefreshFeed | Action::RefreshAll | Action::Sync => {
                 // Handled in main loop (needs feed fetching)
             }
             Action::TogglePlayed => {
-                app.toggle_played();
+                if app.is_aggregate_screen() {
+                    // Aggregated views only list unfinished episodes, so this always marks played
+                    if let Some((p, e)) = app.target_episode_indices() {
+                        if let Some(episode) = app.podcasts.get_mut(p)
+                            .and_then(|p| p.episodes.get_mut(e))
+                        {
+                            episode.played = true;
+                            episode.touch();
+                            app.needs_save = true;
+                        }
+                        app.rebuild_views();
+                    }
+                } else if matches!(app.screen, crate::app::AppScreen::Podcasts | crate::app::AppScreen::Browse) {
+                    app.toggle_played();
+                }
+            }
+            Action::Enqueue => {
+                let target = match app.screen {
+                    crate::app::AppScreen::Browse => None,
+                    _ => app.target_episode_indices(),
+                };
+                if let Some((p, e)) = target {
+                    app.enqueue(p, e);
+                }
+            }
+            Action::MarkOlderThan => {
+                if app.screen == crate::app::AppScreen::Inbox {
+                    app.start_mark_older_than();
+                }
+            }
+            Action::ToggleStarred => {
+                app.toggle_starred();
+            }
+            Action::FilterUnplayed => {
+                app.update_filter(|f| f.unplayed = !f.unplayed);
+            }
+            Action::FilterInProgress => {
+                app.update_filter(|f| f.in_progress = !f.in_progress);
+            }
+            Action::FilterDownloaded => {
+                app.update_filter(|f| f.downloaded = !f.downloaded);
+            }
+            Action::FilterStarred => {
+                app.update_filter(|f| f.starred = !f.starred);
+            }
+            Action::ClearFilters => {
+                app.update_filter(|f| f.clear());
             }
             Action::PlayPause => {
                 // Handled in main loop (needs player reference)
@@ -124,14 +253,93 @@ impl Action {
             Action::SpeedDown => {
                 // Handled in main loop (needs player reference)
             }
+            Action::NextChapter | Action::PrevChapter => {
+                // Handled in main loop (needs player reference)
+            }
+            Action::UndoChapterSkip => {
+                // Handled in main loop (needs player reference)
+            }
+            Action::AddBookmark => {
+                // Handled in main loop (needs player reference)
+            }
+            Action::MarkClipStart | Action::ExportClip => {
+                // Handled in main loop (needs player reference)
+            }
+            Action::SetLoopPoint => {
+                // Handled in main loop (needs player reference)
+            }
+            Action::CycleLoopSlowdown => {
+                app.loop_slowdown = crate::ab_loop::next_slowdown(app.loop_slowdown);
+                app.status_message = Some(format!("Loop speed: {:.0}%", app.loop_slowdown * 100.0));
+            }
+            Action::ShowBookmarks => {
+                let episode = app
+                    .target_episode_indices()
+                    .and_then(|(p, e)| app.podcasts.get(p)?.episodes.get(e))
+                    .or_else(|| app.playing_episode())
+                    .cloned();
+                match episode {
+                    Some(episode) => app.open_bookmarks_popup(&episode),
+                    None => app.status_message = Some("No episode selected".to_string()),
+                }
+            }
+            Action::ExportBookmarks => {
+                let markdown = crate::bookmarks::to_markdown(&app.bookmarks.bookmarks);
+                let result = crate::persistence::get_data_file("bookmarks.md")
+                    .and_then(|path| {
+                        std::fs::write(&path, markdown)
+                            .map(|_| path)
+                            .map_err(|e| format!("Failed to write bookmarks export: {}", e))
+                    });
+                app.status_message = Some(match result {
+                    Ok(path) => format!("Exported {} bookmark(s) to {}", app.bookmarks.bookmarks.len(), path.display()),
+                    Err(e) => format!("Export error: {}", e),
+                });
+            }
+            Action::VolumeUp | Action::VolumeDown => {
+                // Handled in main loop (needs mutable player)
+            }
+            Action::CycleSleepTimer => {
+                app.status_message = Some(match app.now_playing.cycle_sleep_timer(std::time::Instant::now()) {
+                    Some(minutes) => format!("Sleep timer: {} min", minutes),
+                    None => "Sleep timer off".to_string(),
+                });
+            }
             Action::SwitchToBrowse => {
                 app.screen = crate::app::AppScreen::Browse;
             }
             Action::SwitchToPodcasts => {
                 app.screen = crate::app::AppScreen::Podcasts;
             }
+            Action::SwitchToInbox => {
+                app.show_screen(crate::app::AppScreen::Inbox);
+            }
+            Action::SwitchToContinueListening => {
+                app.show_screen(crate::app::AppScreen::ContinueListening);
+            }
+            Action::SwitchToHistory => {
+                app.show_screen(crate::app::AppScreen::History);
+            }
+            Action::SwitchToStats => {
+                app.show_screen(crate::app::AppScreen::Stats);
+            }
+            Action::SwitchToNowPlaying => {
+                app.show_screen(crate::app::AppScreen::NowPlaying);
+            }
+            Action::SwitchToBookmarks => {
+                app.show_screen(crate::app::AppScreen::Bookmarks);
+            }
             Action::StartSearch => {
-                app.start_search();
+                match app.screen {
+                    crate::app::AppScreen::Browse => app.start_search(),
+                    crate::app::AppScreen::Podcasts => app.start_episode_filter(),
+                    crate::app::AppScreen::Inbox
+                    | crate::app::AppScreen::ContinueListening
+                    | crate::app::AppScreen::History
+                    | crate::app::AppScreen::Stats
+                    | crate::app::AppScreen::NowPlaying
+                    | crate::app::AppScreen::Bookmarks => {}
+                }
             }
             Action::SubscribeFromBrowse => {
                 // Handled in main loop (needs feed fetching)
@@ -143,8 +351,7 @@ impl Action {
                 app.show_info = !app.show_info;
             }
             Action::ShowChapters => {
-                let audio_path = player.get_temp_file_path();
-                app.toggle_chapters(audio_path.as_deref());
+                // Handled in main loop (loads chapters in the background)
             }
         }
     }
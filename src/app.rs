@@ -1,14 +1,48 @@
 use serde::{Deserialize, Serialize};
 use std::time::{Duration, Instant};
-use crate::browse::{BrowseState, SearchResult};
-use crate::chapters::ChapterList;
+use crate::ab_loop::AbLoop;
+use crate::bookmarks::{Bookmark, BookmarksState};
+use crate::browse::{self, BrowseListing, BrowseState, SearchResult};
+use crate::artwork::{ArtworkRenderer, Protocol};
+use crate::chapters::skip::{SkipRules, SkipState};
+use crate::chapters::{ChapterList, ChapterSource};
+use crate::continue_listening::ContinueListeningState;
+use crate::filter::EpisodeFilter;
+use crate::history::{self, HistoryState};
+use crate::stats::{self, ListeningStats};
+use crate::inbox::InboxState;
+use crate::library::Snapshot;
+use crate::now_playing::NowPlayingState;
+use crate::config::{ClipsConfig, SyncConfig};
+use crate::directory::{Category, Directory};
+use std::sync::Arc;
+use crate::sync::{EpisodeUpdate, SyncOutcome};
 
 /// Events sent from background threads to the main UI thread
 #[derive(Debug)]
 pub enum AppEvent {
-    SearchComplete(Result<Vec<SearchResult>, String>),
+    /// Results for one page of a search or category listing
+    BrowseResults {
+        listing: BrowseListing,
+        page: usize,
+        result: Result<Vec<SearchResult>, String>,
+    },
+    CategoriesLoaded(Result<Vec<Category>, String>),
+    ToplistLoaded(Result<Vec<SearchResult>, String>),
     FeedLoaded(Result<Podcast, String>),
-    PlaybackReady,
+    /// A feed fetched for the Browse preview, keyed by its URL
+    PreviewLoaded(String, Result<Podcast, String>),
+    /// Directory details looked up for the feed at the given URL
+    DetailsLoaded(String, Result<Option<SearchResult>, String>),
+    /// A background refresh finished for the feed at the given URL
+    FeedRefreshed(String, Result<Podcast, String>),
+    SyncComplete(Result<SyncOutcome, String>),
+    /// Artwork downloaded (or read from the cache) for the given URL
+    ArtworkLoaded(String, Result<image::DynamicImage, String>),
+    /// Chapters fetched for the episode with the given audio URL
+    ChaptersLoaded(String, Result<ChapterList, String>),
+    /// A clip finished exporting to the given path
+    ClipExported(Result<std::path::PathBuf, String>),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,9 +51,17 @@ pub struct Podcast {
     pub description: String,
     pub url: String,
     pub episodes: Vec<Episode>,
+    #[serde(default)]
+    pub filter: EpisodeFilter,
+    /// Categories or tags declared by the feed
+    #[serde(default)]
+    pub categories: Vec<String>,
+    /// Cover art URL (`itunes:image` or the channel image)
+    #[serde(default)]
+    pub image_url: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Episode {
     pub title: String,
     pub description: String,
@@ -31,6 +73,38 @@ pub struct Episode {
     pub chapters_url: Option<String>,
     #[serde(default)]
     pub position_secs: u64,
+    #[serde(default)]
+    pub starred: bool,
+    /// Local file path once the episode has been downloaded
+    #[serde(default)]
+    pub download_path: Option<String>,
+    /// Unix timestamp of the last time this episode was listened to
+    #[serde(default)]
+    pub last_played: Option<i64>,
+    /// Unix timestamp of the last local change to `played` or `position_secs`,
+    /// used to resolve conflicts when syncing
+    #[serde(default)]
+    pub state_changed: Option<i64>,
+    /// Episode artwork (`itunes:image`), if different from the podcast cover
+    #[serde(default)]
+    pub image_url: Option<String>,
+    /// Podlove Simple Chapters listed inline in the feed item
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feed_chapters: Option<ChapterList>,
+}
+
+impl Episode {
+    /// Record that `played` or `position_secs` was just changed locally
+    pub fn touch(&mut self) {
+        self.state_changed = Some(chrono::Utc::now().timestamp());
+    }
+}
+
+/// Stable reference to an episode that survives reordering and feed refreshes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EpisodeRef {
+    pub podcast_url: String,
+    pub audio_url: String,
 }
 
 // Custom serialization for Option<Duration>
@@ -60,7 +134,13 @@ mod option_duration {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AppScreen {
     Podcasts,
+    Inbox,
+    ContinueListening,
+    History,
+    Stats,
     Browse,
+    NowPlaying,
+    Bookmarks,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -74,6 +154,10 @@ pub enum InputMode {
     Normal,
     AddingFeed,
     Searching,
+    FilteringEpisodes,
+    MarkingOlderThan,
+    /// Typing the note for `App::pending_bookmark`
+    BookmarkNote,
 }
 
 pub struct PlaybackState {
@@ -82,6 +166,8 @@ pub struct PlaybackState {
     pub start: Option<Instant>,
     pub paused_at: Option<Instant>,
     pub paused_duration: Duration,
+    /// Chapters of the playing episode, loaded in the background
+    pub chapters: Option<ChapterList>,
 }
 
 impl PlaybackState {
@@ -92,14 +178,53 @@ impl PlaybackState {
             start: None,
             paused_at: None,
             paused_duration: Duration::ZERO,
+            chapters: None,
+        }
+    }
+
+    /// Whether the playing episode's chapters should be looked up again, because
+    /// none were found yet or they were only guessed from the show notes
+    pub fn needs_chapters(&self) -> bool {
+        self.chapters.as_ref().is_none_or(|list| list.source == Some(ChapterSource::Description))
+    }
+}
+
+/// The chapters popup and the episode it lists
+pub struct ChaptersPopup {
+    pub audio_url: String,
+    pub episode_title: String,
+    /// `None` while the chapters are loading
+    pub chapters: Option<Result<ChapterList, String>>,
+    pub selected: usize,
+    /// Playback position when the popup was opened for the playing episode
+    pub position_secs: Option<f64>,
+}
+
+impl ChaptersPopup {
+    /// Table of contents chapters, once loaded
+    pub fn toc(&self) -> Vec<&crate::chapters::Chapter> {
+        match &self.chapters {
+            Some(Ok(list)) => list.toc(),
+            _ => Vec::new(),
         }
     }
 }
 
+/// The bookmarks popup for one episode
+pub struct BookmarksPopup {
+    pub audio_url: String,
+    pub episode_title: String,
+    pub selected: usize,
+}
+
 pub struct App {
     pub screen: AppScreen,
     pub podcasts: Vec<Podcast>,
     pub needs_save: bool,
+    /// When the podcasts or queue files were last loaded or saved by this process
+    pub library_modified: Option<std::time::SystemTime>,
+    /// The library as last loaded from or sent to the daemon, when attached to one
+    pub library_snapshot: Option<Snapshot>,
     pub selected_podcast_index: usize,
     pub selected_episode_index: usize,
     pub focus: PaneFocus,
@@ -109,16 +234,55 @@ pub struct App {
 
     // Playback state
     pub playback: PlaybackState,
+    pub now_playing: NowPlayingState,
+
+    // Episode queue, played in order after the current episode finishes
+    pub queue: Vec<EpisodeRef>,
+
+    // Inbox state
+    pub inbox: InboxState,
+    pub pending_refreshes: usize,
+    pub refreshed_new: usize,
+
+    // Continue-listening state
+    pub continue_listening: ContinueListeningState,
+
+    // Listening history
+    pub history: HistoryState,
+    pub bookmarks: BookmarksState,
+    /// Bookmark waiting for its note to be typed
+    pub pending_bookmark: Option<Bookmark>,
+    /// Audio URL and position of the clip start marked in the playing episode
+    pub clip_start: Option<(String, f64)>,
+    pub clips_config: ClipsConfig,
+    /// A-B loop in the playing episode
+    pub ab_loop: Option<AbLoop>,
+    /// Speed factor inside the A-B loop
+    pub loop_slowdown: f32,
+    pub stats: ListeningStats,
+
+    // Sync state
+    pub sync_config: Option<SyncConfig>,
+    pub is_syncing: bool,
+    /// Synced episode state waiting for its podcast to finish subscribing
+    pub pending_sync_updates: Vec<EpisodeUpdate>,
 
     // Browse state
     pub browse: BrowseState,
+    pub directory: Arc<Directory>,
+
+    // Artwork drawn over the frame
+    pub artwork: ArtworkRenderer,
+
+    // Chapters skipped by title
+    pub skip_rules: SkipRules,
+    pub chapter_skips: SkipState,
 
     // UI state
     pub show_help: bool,
     pub show_info: bool,
-    pub show_chapters: bool,
-    pub selected_chapter_index: usize,
-    pub cached_chapters: Option<ChapterList>,
+    pub chapters_popup: Option<ChaptersPopup>,
+    pub bookmarks_popup: Option<BookmarksPopup>,
 }
 
 impl App {
@@ -127,6 +291,8 @@ impl App {
             screen: AppScreen::Podcasts,
             podcasts: Vec::new(),
             needs_save: false,
+            library_modified: None,
+            library_snapshot: None,
             selected_podcast_index: 0,
             selected_episode_index: 0,
             focus: PaneFocus::Left,
@@ -134,12 +300,32 @@ impl App {
             input_buffer: String::new(),
             status_message: None,
             playback: PlaybackState::new(),
+            now_playing: NowPlayingState::new(),
+            queue: Vec::new(),
+            inbox: InboxState::new(),
+            pending_refreshes: 0,
+            refreshed_new: 0,
+            continue_listening: ContinueListeningState::new(),
+            history: HistoryState::new(),
+            bookmarks: BookmarksState::new(),
+            pending_bookmark: None,
+            clip_start: None,
+            clips_config: ClipsConfig::default(),
+            ab_loop: None,
+            loop_slowdown: 1.0,
+            stats: ListeningStats::default(),
+            sync_config: None,
+            is_syncing: false,
+            pending_sync_updates: Vec::new(),
             browse: BrowseState::new(),
+            directory: Arc::new(Directory::default()),
+            artwork: ArtworkRenderer::new(Protocol::Off),
+            skip_rules: SkipRules::default(),
+            chapter_skips: SkipState::default(),
             show_help: false,
             show_info: false,
-            show_chapters: false,
-            selected_chapter_index: 0,
-            cached_chapters: None,
+            chapters_popup: None,
+            bookmarks_popup: None,
         }
     }
 
@@ -163,6 +349,12 @@ impl App {
                         played: false,
                         chapters_url: None,
                         position_secs: 0,
+                        starred: false,
+                        download_path: None,
+                        last_played: None,
+                        state_changed: None,
+                        image_url: None,
+                        feed_chapters: None,
                     },
                     Episode {
                         title: "The Uncertainty Machine".to_string(),
@@ -173,6 +365,12 @@ impl App {
                         played: true,
                         chapters_url: None,
                         position_secs: 0,
+                        starred: false,
+                        download_path: None,
+                        last_played: None,
+                        state_changed: None,
+                        image_url: None,
+                        feed_chapters: None,
                     },
                     Episode {
                         title: "Numbers in the Wild".to_string(),
@@ -183,8 +381,17 @@ impl App {
                         played: true,
                         chapters_url: None,
                         position_secs: 0,
+                        starred: false,
+                        download_path: None,
+                        last_played: None,
+                        state_changed: None,
+                        image_url: None,
+                        feed_chapters: None,
                     },
                 ],
+                filter: EpisodeFilter::default(),
+                categories: Vec::new(),
+                image_url: None,
             },
             Podcast {
                 title: "99% Invisible".to_string(),
@@ -200,6 +407,12 @@ impl App {
                         played: false,
                         chapters_url: None,
                         position_secs: 0,
+                        starred: false,
+                        download_path: None,
+                        last_played: None,
+                        state_changed: None,
+                        image_url: None,
+                        feed_chapters: None,
                     },
                     Episode {
                         title: "Designed to Last".to_string(),
@@ -210,8 +423,17 @@ impl App {
                         played: false,
                         chapters_url: None,
                         position_secs: 0,
+                        starred: false,
+                        download_path: None,
+                        last_played: None,
+                        state_changed: None,
+                        image_url: None,
+                        feed_chapters: None,
                     },
                 ],
+                filter: EpisodeFilter::default(),
+                categories: Vec::new(),
+                image_url: None,
             },
             Podcast {
                 title: "The Daily".to_string(),
@@ -227,8 +449,17 @@ impl App {
                         played: false,
                         chapters_url: None,
                         position_secs: 0,
+                        starred: false,
+                        download_path: None,
+                        last_played: None,
+                        state_changed: None,
+                        image_url: None,
+                        feed_chapters: None,
                     },
                 ],
+                filter: EpisodeFilter::default(),
+                categories: Vec::new(),
+                image_url: None,
             },
         ];
         
@@ -243,15 +474,124 @@ impl App {
         self.podcasts.get(self.selected_podcast_index)
     }
 
-    pub fn selected_episode_url(&self) -> Option<String> {
+    /// Indices into the selected podcast's episodes that pass its filter
+    pub fn visible_episode_indices(&self) -> Vec<usize> {
         self.selected_podcast()
-            .and_then(|p| p.episodes.get(self.selected_episode_index))
-            .map(|e| e.audio_url.clone())
+            .map(|p| {
+                p.episodes
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, e)| p.filter.matches(e))
+                    .map(|(i, _)| i)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Resolve the episode selection (an index into the filtered list) to an episode index
+    fn selected_episode_position(&self) -> Option<usize> {
+        self.visible_episode_indices().get(self.selected_episode_index).copied()
+    }
+
+    pub fn selected_episode(&self) -> Option<&Episode> {
+        let idx = self.selected_episode_position()?;
+        self.selected_podcast().and_then(|p| p.episodes.get(idx))
+    }
+
+    /// Locate an episode by podcast and audio URL
+    pub fn find_episode(&self, episode_ref: &EpisodeRef) -> Option<(usize, usize)> {
+        let p = self.podcasts.iter().position(|p| p.url == episode_ref.podcast_url)?;
+        let e = self.podcasts[p]
+            .episodes
+            .iter()
+            .position(|e| e.audio_url == episode_ref.audio_url)?;
+        Some((p, e))
+    }
+
+    /// Build a reference to the episode at the given indices
+    pub fn episode_ref(&self, podcast_index: usize, episode_index: usize) -> Option<EpisodeRef> {
+        let podcast = self.podcasts.get(podcast_index)?;
+        let episode = podcast.episodes.get(episode_index)?;
+        Some(EpisodeRef {
+            podcast_url: podcast.url.clone(),
+            audio_url: episode.audio_url.clone(),
+        })
+    }
+
+    /// Indices of the selected episode in the Podcasts view
+    pub fn selected_episode_indices(&self) -> Option<(usize, usize)> {
+        Some((self.selected_podcast_index, self.selected_episode_position()?))
+    }
+
+    /// Indices of the episode that is currently playing, independent of the selection
+    pub fn playing_episode_indices(&self) -> Option<(usize, usize)> {
+        let url = self.playback.url.as_ref()?;
+        self.podcasts.iter().enumerate().find_map(|(p, podcast)| {
+            podcast
+                .episodes
+                .iter()
+                .position(|e| &e.audio_url == url)
+                .map(|e| (p, e))
+        })
+    }
+
+    /// Episode selected in the Browse preview, which isn't part of `podcasts`
+    pub fn preview_episode(&self) -> Option<&Episode> {
+        if self.screen != AppScreen::Browse {
+            return None;
+        }
+        self.browse.preview.as_ref()?.selected_episode()
+    }
+
+    /// The podcast of the playing episode, falling back to the Browse preview it was played from
+    pub fn playing_podcast(&self) -> Option<&Podcast> {
+        match self.playing_episode_indices() {
+            Some((p, _)) => self.podcasts.get(p),
+            None => self.playing_preview_podcast(),
+        }
+    }
+
+    /// The previewed podcast, if the playing episode is one of its unsubscribed episodes
+    fn playing_preview_podcast(&self) -> Option<&Podcast> {
+        let url = self.playback.url.as_ref()?;
+        self.browse
+            .preview
+            .as_ref()?
+            .podcast
+            .as_ref()
+            .filter(|p| p.episodes.iter().any(|e| &e.audio_url == url))
+    }
+
+    /// Artwork for the playing episode: the current chapter's image, the
+    /// episode's own artwork or the podcast cover
+    pub fn playing_artwork_url(&self, position_secs: f64) -> Option<&str> {
+        let chapter_image = self
+            .playback
+            .chapters
+            .as_ref()
+            .and_then(|list| list.chapter_at(position_secs))
+            .and_then(|(_, chapter)| chapter.image.as_deref());
+        chapter_image
+            .or_else(|| self.playing_episode()?.image_url.as_deref())
+            .or_else(|| self.playing_podcast()?.image_url.as_deref())
+    }
+
+    pub fn playing_episode(&self) -> Option<&Episode> {
+        let url = self.playback.url.as_ref()?;
+        match self.playing_episode_indices() {
+            Some((p, e)) => self.podcasts.get(p).and_then(|podcast| podcast.episodes.get(e)),
+            None => self.playing_preview_podcast()?.episodes.iter().find(|e| &e.audio_url == url),
+        }
+    }
+
+    pub fn playing_episode_mut(&mut self) -> Option<&mut Episode> {
+        let (p, e) = self.playing_episode_indices()?;
+        self.podcasts.get_mut(p).and_then(|podcast| podcast.episodes.get_mut(e))
     }
 
     pub fn selected_episode_mut(&mut self) -> Option<&mut Episode> {
         let p = self.selected_podcast_index;
-        let e = self.selected_episode_index;
+        let e = self.selected_episode_position()?;
         self.podcasts
             .get_mut(p)
             .and_then(|podcast| podcast.episodes.get_mut(e))
@@ -278,10 +618,16 @@ impl App {
     }
 
     pub fn move_episode_down(&mut self) {
-        if let Some(podcast) = self.selected_podcast() {
-            if self.selected_episode_index < podcast.episodes.len().saturating_sub(1) {
-                self.selected_episode_index += 1;
-            }
+        if self.selected_episode_index < self.visible_episode_indices().len().saturating_sub(1) {
+            self.selected_episode_index += 1;
+        }
+    }
+
+    /// Keep the episode selection inside the filtered list
+    pub fn clamp_episode_selection(&mut self) {
+        let visible = self.visible_episode_indices().len();
+        if self.selected_episode_index >= visible {
+            self.selected_episode_index = visible.saturating_sub(1);
         }
     }
 
@@ -292,6 +638,87 @@ impl App {
         };
     }
 
+    /// Add an episode to the end of the queue, ignoring duplicates
+    pub fn enqueue(&mut self, podcast_index: usize, episode_index: usize) {
+        let Some(episode_ref) = self.episode_ref(podcast_index, episode_index) else {
+            return;
+        };
+        let title = self.podcasts[podcast_index].episodes[episode_index].title.clone();
+        if self.queue.contains(&episode_ref) {
+            self.status_message = Some(format!("Already queued: {}", title));
+        } else {
+            self.queue.push(episode_ref);
+            self.needs_save = true;
+            self.status_message = Some(format!("Queued ({}): {}", self.queue.len(), title));
+        }
+    }
+
+    /// Take the next queued episode that still exists
+    pub fn pop_queue(&mut self) -> Option<(usize, usize)> {
+        while !self.queue.is_empty() {
+            let next = self.queue.remove(0);
+            self.needs_save = true;
+            if let Some(indices) = self.find_episode(&next) {
+                return Some(indices);
+            }
+        }
+        None
+    }
+
+    pub fn is_queued(&self, podcast_index: usize, episode_index: usize) -> bool {
+        self.episode_ref(podcast_index, episode_index)
+            .map(|r| self.queue.contains(&r))
+            .unwrap_or(false)
+    }
+
+    /// Switch screens, rebuilding the aggregated episode views
+    pub fn show_screen(&mut self, screen: AppScreen) {
+        self.screen = screen;
+        self.rebuild_views();
+        match screen {
+            AppScreen::History => self.history.reload(),
+            AppScreen::Bookmarks => self.bookmarks.reload(),
+            AppScreen::Stats => {
+                let events = history::load().unwrap_or_else(|e| {
+                    log::error!("Failed to load history: {}", e);
+                    Vec::new()
+                });
+                self.stats = stats::compute_local(&events, &self.podcasts);
+            }
+            _ => {}
+        }
+    }
+
+    /// Rebuild the cross-podcast views after podcasts or episodes change
+    pub fn rebuild_views(&mut self) {
+        self.inbox.rebuild(&self.podcasts);
+        self.continue_listening.rebuild(&self.podcasts);
+    }
+
+    /// Episode targeted by episode actions on the current screen
+    pub fn target_episode_indices(&self) -> Option<(usize, usize)> {
+        match self.screen {
+            AppScreen::Inbox => self.inbox.selected().map(|e| (e.podcast_index, e.episode_index)),
+            AppScreen::ContinueListening => self.continue_listening.selected(),
+            AppScreen::History => self.history.selected().and_then(|h| self.find_episode(&h.episode)),
+            AppScreen::Podcasts => self.selected_episode_indices(),
+            AppScreen::NowPlaying => self.playing_episode_indices(),
+            AppScreen::Bookmarks => self.bookmarks.selected().and_then(|b| self.find_episode(&b.episode())),
+            AppScreen::Browse | AppScreen::Stats => None,
+        }
+    }
+
+    /// Whether the current screen lists episodes aggregated from all podcasts
+    pub fn is_aggregate_screen(&self) -> bool {
+        matches!(self.screen, AppScreen::Inbox | AppScreen::ContinueListening)
+    }
+
+    pub fn start_mark_older_than(&mut self) {
+        self.input_mode = InputMode::MarkingOlderThan;
+        self.input_buffer.clear();
+        self.status_message = None;
+    }
+
     pub fn start_add_feed(&mut self) {
         self.input_mode = InputMode::AddingFeed;
         self.input_buffer.clear();
@@ -303,11 +730,35 @@ impl App {
         self.input_buffer.clear();
     }
 
-    pub fn add_podcast(&mut self, podcast: Podcast) {
-        self.podcasts.push(podcast);
-        self.selected_podcast_index = self.podcasts.len() - 1;
+    /// Index of the subscribed podcast with the same feed, compared by normalized URL
+    pub fn find_podcast_by_feed(&self, feed_url: &str) -> Option<usize> {
+        let normalized = browse::normalize_feed_url(feed_url);
+        self.podcasts
+            .iter()
+            .position(|p| browse::normalize_feed_url(&p.url) == normalized)
+    }
+
+    pub fn is_subscribed(&self, feed_url: &str) -> bool {
+        self.find_podcast_by_feed(feed_url).is_some()
+    }
+
+    /// Subscribe to a podcast and select it. Returns false, selecting the existing
+    /// podcast instead, if its feed is already subscribed.
+    pub fn add_podcast(&mut self, podcast: Podcast) -> bool {
+        let added = match self.find_podcast_by_feed(&podcast.url) {
+            Some(index) => {
+                self.selected_podcast_index = index;
+                false
+            }
+            None => {
+                self.podcasts.push(podcast);
+                self.selected_podcast_index = self.podcasts.len() - 1;
+                self.needs_save = true;
+                true
+            }
+        };
         self.selected_episode_index = 0;
-        self.needs_save = true;
+        added
     }
 
     pub fn delete_podcast(&mut self) {
@@ -319,15 +770,94 @@ impl App {
                 self.selected_podcast_index -= 1;
             }
             self.selected_episode_index = 0;
+            self.rebuild_views();
             self.needs_save = true;
         }
     }
 
+    /// Remove every podcast whose feed URL is in the list
+    pub fn remove_podcasts(&mut self, urls: &[String]) {
+        let before = self.podcasts.len();
+        self.podcasts.retain(|p| !urls.contains(&p.url));
+        if self.podcasts.len() == before {
+            return;
+        }
+
+        if self.selected_podcast_index >= self.podcasts.len() {
+            self.selected_podcast_index = self.podcasts.len().saturating_sub(1);
+        }
+        self.selected_episode_index = 0;
+        self.rebuild_views();
+        self.needs_save = true;
+    }
+
+    /// Apply episode state pulled from the sync server.
+    /// Returns the updates for episodes that don't exist locally.
+    pub fn apply_sync_updates(&mut self, updates: Vec<EpisodeUpdate>) -> Vec<EpisodeUpdate> {
+        let mut unmatched = Vec::new();
+        for update in updates {
+            let episode = self
+                .podcasts
+                .iter_mut()
+                .flat_map(|p| p.episodes.iter_mut())
+                .find(|e| e.audio_url == update.audio_url);
+            match episode {
+                // Changed locally while the sync was running
+                Some(episode) if episode.state_changed > Some(update.timestamp) => {}
+                Some(episode) => {
+                    episode.played = update.played;
+                    episode.position_secs = update.position_secs;
+                    episode.state_changed = Some(update.timestamp);
+                    self.needs_save = true;
+                }
+                None => unmatched.push(update),
+            }
+        }
+        self.rebuild_views();
+        self.clamp_episode_selection();
+        unmatched
+    }
+
     pub fn toggle_played(&mut self) {
         if let Some(episode) = self.selected_episode_mut() {
             episode.played = !episode.played;
+            episode.touch();
+            self.needs_save = true;
+        }
+        self.clamp_episode_selection();
+    }
+
+    pub fn toggle_starred(&mut self) {
+        if let Some(episode) = self.selected_episode_mut() {
+            episode.starred = !episode.starred;
+            self.needs_save = true;
+        }
+        self.clamp_episode_selection();
+    }
+
+    /// Apply a change to the selected podcast's episode filter
+    pub fn update_filter<F: FnOnce(&mut EpisodeFilter)>(&mut self, change: F) {
+        if self.screen != AppScreen::Podcasts {
+            return;
+        }
+        if let Some(podcast) = self.podcasts.get_mut(self.selected_podcast_index) {
+            change(&mut podcast.filter);
             self.needs_save = true;
         }
+        self.clamp_episode_selection();
+    }
+
+    pub fn start_episode_filter(&mut self) {
+        if self.selected_podcast().is_none() {
+            return;
+        }
+        self.input_mode = InputMode::FilteringEpisodes;
+        self.focus = PaneFocus::Right;
+    }
+
+    pub fn finish_episode_filter(&mut self) {
+        self.input_mode = InputMode::Normal;
+        self.needs_save = true;
     }
 
     pub fn start_search(&mut self) {
@@ -341,64 +871,146 @@ impl App {
         self.browse.is_searching = false;
     }
 
-    pub fn toggle_chapters(&mut self, audio_path: Option<&std::path::Path>) {
-        if self.show_chapters {
-            // Close chapters popup
-            self.show_chapters = false;
-            self.cached_chapters = None;
-        } else {
-            // Only show chapters for currently playing episode
-            if self.playback.url.is_none() {
-                self.status_message = Some("Start playback first".to_string());
-                return;
-            }
+    /// Open the chapters popup for an episode, showing the playing episode's
+    /// chapters straight away if they are loaded. Returns whether the chapters
+    /// still need loading.
+    pub fn open_chapters_popup(&mut self, episode: &Episode, position_secs: f64) -> bool {
+        let playing = self.playback.url.as_ref() == Some(&episode.audio_url);
+        let chapters = self
+            .playback
+            .chapters
+            .clone()
+            .filter(|_| playing && !self.playback.needs_chapters());
+        let needs_load = chapters.is_none();
+
+        self.chapters_popup = Some(ChaptersPopup {
+            audio_url: episode.audio_url.clone(),
+            episode_title: episode.title.clone(),
+            chapters: None,
+            selected: 0,
+            position_secs: playing.then_some(position_secs),
+        });
+        if let Some(chapters) = chapters {
+            self.chapters_loaded(&episode.audio_url, Ok(chapters));
+        }
+        needs_load
+    }
 
-            // Check if selected episode is the one currently playing
-            let selected_url = self.selected_episode_url();
-            if selected_url.as_ref() != self.playback.url.as_ref() {
-                self.status_message = Some("Chapters only available for playing episode".to_string());
-                return;
+    /// Store chapters loaded in the background for the playing episode and the popup
+    pub fn chapters_loaded(&mut self, audio_url: &str, result: Result<ChapterList, String>) {
+        if self.playback.url.as_deref() == Some(audio_url) {
+            match &result {
+                Ok(chapters) => self.playback.chapters = Some(chapters.clone()),
+                Err(e) => log::debug!("No chapters for {}: {}", audio_url, e),
             }
+        }
 
-            // Try to open chapters popup
-            self.selected_chapter_index = 0;
-            self.cached_chapters = None;
-
-            // Fetch chapters when opening popup
-            if let Some(episode) = self.selected_podcast()
-                .and_then(|p| p.episodes.get(self.selected_episode_index))
-            {
-                log::debug!("Opening chapters for episode: {}", episode.title);
-                use crate::chapters;
-                let chapters_url = episode.chapters_url.as_deref();
-
-                match chapters::get_chapters(audio_path, chapters_url) {
-                    Ok(chapters) => {
-                        log::debug!("Loaded {} chapters", chapters.chapters.len());
-                        self.cached_chapters = Some(chapters);
-                        self.show_chapters = true; // Only show if we found chapters
-                    }
-                    Err(e) => {
-                        log::debug!("No chapters available: {}", e);
-                        self.status_message = Some("No chapters available".to_string());
-                    }
-                }
-            } else {
-                log::error!("Could not find selected episode");
-                self.status_message = Some("No episode selected".to_string());
-            }
+        if let Some(popup) = self.chapters_popup.as_mut().filter(|p| p.audio_url == audio_url) {
+            // Start on the chapter that is playing
+            popup.selected = match (&result, popup.position_secs) {
+                (Ok(list), Some(position)) => list.toc_chapter_at(position).map(|(i, _)| i).unwrap_or(0),
+                _ => 0,
+            };
+            popup.chapters = Some(result);
         }
     }
 
     pub fn move_chapter_up(&mut self) {
-        if self.selected_chapter_index > 0 {
-            self.selected_chapter_index -= 1;
+        if let Some(popup) = &mut self.chapters_popup {
+            popup.selected = popup.selected.saturating_sub(1);
+        }
+    }
+
+    pub fn move_chapter_down(&mut self) {
+        if let Some(popup) = &mut self.chapters_popup {
+            if popup.selected + 1 < popup.toc().len() {
+                popup.selected += 1;
+            }
+        }
+    }
+
+    /// Bookmark the playing episode at the given position and ask for a note
+    pub fn start_bookmark(&mut self, position_secs: u64) {
+        let (Some(podcast), Some(episode)) = (self.playing_podcast(), self.playing_episode()) else {
+            self.status_message = Some("Start playback first".to_string());
+            return;
+        };
+        self.pending_bookmark = Some(Bookmark {
+            podcast_title: podcast.title.clone(),
+            podcast_url: podcast.url.clone(),
+            episode_title: episode.title.clone(),
+            audio_url: episode.audio_url.clone(),
+            position_secs,
+            note: String::new(),
+            created: chrono::Utc::now().timestamp(),
+        });
+        self.input_mode = InputMode::BookmarkNote;
+        self.input_buffer.clear();
+        self.status_message = None;
+    }
+
+    /// Save the pending bookmark with the typed note
+    pub fn finish_bookmark(&mut self) {
+        let note = self.input_buffer.trim().to_string();
+        self.cancel_input();
+        let Some(mut bookmark) = self.pending_bookmark.take() else {
+            return;
+        };
+        bookmark.note = note;
+
+        let label = bookmark.label();
+        self.status_message = Some(match self.bookmarks.add(bookmark) {
+            Ok(_) => format!("Bookmarked {}", label),
+            Err(e) => format!("Bookmark error: {}", e),
+        });
+    }
+
+    /// Open the bookmarks popup for an episode
+    pub fn open_bookmarks_popup(&mut self, episode: &Episode) {
+        self.bookmarks_popup = Some(BookmarksPopup {
+            audio_url: episode.audio_url.clone(),
+            episode_title: episode.title.clone(),
+            selected: 0,
+        });
+    }
+
+    /// Bookmarks listed in the popup, in playback order
+    pub fn popup_bookmarks(&self) -> Vec<&Bookmark> {
+        match &self.bookmarks_popup {
+            Some(popup) => self.bookmarks.for_episode(&popup.audio_url),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn selected_popup_bookmark(&self) -> Option<Bookmark> {
+        let selected = self.bookmarks_popup.as_ref()?.selected;
+        self.popup_bookmarks().get(selected).map(|&b| b.clone())
+    }
+
+    pub fn move_bookmark_up(&mut self) {
+        if let Some(popup) = &mut self.bookmarks_popup {
+            popup.selected = popup.selected.saturating_sub(1);
+        }
+    }
+
+    pub fn move_bookmark_down(&mut self) {
+        let count = self.popup_bookmarks().len();
+        if let Some(popup) = &mut self.bookmarks_popup {
+            if popup.selected + 1 < count {
+                popup.selected += 1;
+            }
         }
     }
 
-    pub fn move_chapter_down(&mut self, max_index: usize) {
-        if self.selected_chapter_index < max_index {
-            self.selected_chapter_index += 1;
+    /// Delete a bookmark, keeping the popup selection in range
+    pub fn delete_bookmark(&mut self, bookmark: &Bookmark) {
+        self.status_message = Some(match self.bookmarks.remove(bookmark) {
+            Ok(_) => format!("Deleted bookmark at {}", crate::now_playing::format_time(bookmark.position_secs)),
+            Err(e) => format!("Bookmark error: {}", e),
+        });
+        let count = self.popup_bookmarks().len();
+        if let Some(popup) = &mut self.bookmarks_popup {
+            popup.selected = popup.selected.min(count.saturating_sub(1));
         }
     }
 }
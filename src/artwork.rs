@@ -0,0 +1,442 @@
+use crate::app::AppEvent;
+use crate::persistence;
+use base64::Engine;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat, RgbImage};
+use ratatui::layout::Rect;
+use sha1::{Digest, Sha1};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Cursor, Write};
+use std::path::PathBuf;
+use std::sync::mpsc;
+
+const ARTWORK_DIR: &str = "artwork";
+
+/// Assumed size of a terminal cell in pixels, used to keep the aspect ratio
+const CELL_WIDTH: u32 = 10;
+const CELL_HEIGHT: u32 = 20;
+
+/// Artwork is often 3000px square; only a thumbnail is kept in memory
+const MAX_IMAGE_SIZE: u32 = 600;
+
+/// Image ID used for kitty placements so they can be deleted again
+const KITTY_IMAGE_ID: u32 = 7243;
+
+/// How images are drawn in the terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+    Kitty,
+    Sixel,
+    Iterm2,
+    /// Unicode half blocks with true-color foreground and background
+    HalfBlocks,
+    Off,
+}
+
+impl Protocol {
+    /// Protocol named in the config, or detected from the environment for "auto"
+    pub fn from_config(name: &str) -> Self {
+        match name.to_lowercase().as_str() {
+            "kitty" => Self::Kitty,
+            "sixel" => Self::Sixel,
+            "iterm2" => Self::Iterm2,
+            "halfblocks" => Self::HalfBlocks,
+            "off" | "none" => Self::Off,
+            other => {
+                if other != "auto" {
+                    log::warn!("Unknown artwork protocol '{}', detecting one", other);
+                }
+                Self::detect(&|name| std::env::var(name).ok())
+            }
+        }
+    }
+
+    /// Guess the graphics protocol from the terminal's environment variables
+    pub fn detect(env: &dyn Fn(&str) -> Option<String>) -> Self {
+        let term = env("TERM").unwrap_or_default();
+        let program = env("TERM_PROGRAM").unwrap_or_default();
+
+        if env("KITTY_WINDOW_ID").is_some() || term.contains("kitty") || program == "ghostty" {
+            Self::Kitty
+        } else if program == "iTerm.app"
+            || program == "WezTerm"
+            || env("LC_TERMINAL").as_deref() == Some("iTerm2")
+        {
+            Self::Iterm2
+        } else if term.contains("sixel") || term.starts_with("foot") || term.starts_with("mlterm") {
+            Self::Sixel
+        } else {
+            Self::HalfBlocks
+        }
+    }
+}
+
+/// Artwork to draw over a blank area of the frame once it has been rendered
+#[derive(Debug, Clone, PartialEq)]
+pub struct Placement {
+    pub url: String,
+    pub area: Rect,
+}
+
+/// Path of the cached copy of an image URL
+pub fn cache_path(url: &str) -> Result<PathBuf, String> {
+    let dir = persistence::get_data_file(ARTWORK_DIR)?;
+    fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create artwork cache: {}", e))?;
+
+    let digest = Sha1::digest(url.as_bytes());
+    let name: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    Ok(dir.join(name))
+}
+
+/// Download an image unless it is already cached, then decode it
+pub fn fetch(url: &str) -> Result<DynamicImage, String> {
+    let path = cache_path(url)?;
+
+    if !path.exists() {
+        let response = reqwest::blocking::get(url)
+            .map_err(|e| format!("Failed to fetch artwork: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("Artwork request returned status {}", response.status()));
+        }
+        let bytes = response
+            .bytes()
+            .map_err(|e| format!("Failed to read artwork: {}", e))?;
+        fs::write(&path, &bytes)
+            .map_err(|e| format!("Failed to cache artwork: {}", e))?;
+    }
+
+    let image = image::ImageReader::open(&path)
+        .and_then(|reader| reader.with_guessed_format())
+        .map_err(|e| format!("Failed to open artwork: {}", e))?
+        .decode()
+        .map_err(|e| format!("Failed to decode artwork: {}", e))?;
+
+    Ok(image.thumbnail(MAX_IMAGE_SIZE, MAX_IMAGE_SIZE))
+}
+
+/// The largest centered part of `area`, in cells, that has the image's aspect ratio
+pub fn fit(area: Rect, width: u32, height: u32) -> Rect {
+    if area.width == 0 || area.height == 0 || width == 0 || height == 0 {
+        return Rect::new(area.x, area.y, 0, 0);
+    }
+
+    let mut cols = area.width as u32;
+    let mut rows = (cols * CELL_WIDTH * height / (width * CELL_HEIGHT)).max(1);
+    if rows > area.height as u32 {
+        rows = area.height as u32;
+        cols = (rows * CELL_HEIGHT * width / (height * CELL_WIDTH)).clamp(1, area.width as u32);
+    }
+
+    Rect::new(
+        area.x + (area.width - cols as u16) / 2,
+        area.y + (area.height - rows as u16) / 2,
+        cols as u16,
+        rows as u16,
+    )
+}
+
+fn move_to(out: &mut Vec<u8>, x: u16, y: u16) {
+    let _ = write!(out, "\x1b[{};{}H", y + 1, x + 1);
+}
+
+fn to_png(image: &DynamicImage) -> Vec<u8> {
+    let mut png = Vec::new();
+    if let Err(e) = image.write_to(&mut Cursor::new(&mut png), ImageFormat::Png) {
+        log::error!("Failed to encode artwork: {}", e);
+    }
+    png
+}
+
+/// Escape sequences that draw the image fitted into `area`
+pub fn encode(protocol: Protocol, image: &DynamicImage, area: Rect) -> Vec<u8> {
+    let rect = fit(area, image.width(), image.height());
+    let mut out = Vec::new();
+    if rect.width == 0 || rect.height == 0 {
+        return out;
+    }
+    move_to(&mut out, rect.x, rect.y);
+
+    match protocol {
+        Protocol::Kitty => {
+            let data = base64::engine::general_purpose::STANDARD.encode(to_png(image));
+            let chunks: Vec<&[u8]> = data.as_bytes().chunks(4096).collect();
+            for (i, chunk) in chunks.iter().enumerate() {
+                let more = u8::from(i + 1 < chunks.len());
+                if i == 0 {
+                    let _ = write!(
+                        out,
+                        "\x1b_Ga=T,f=100,q=2,C=1,i={},c={},r={},m={};",
+                        KITTY_IMAGE_ID, rect.width, rect.height, more
+                    );
+                } else {
+                    let _ = write!(out, "\x1b_Gm={};", more);
+                }
+                out.extend_from_slice(chunk);
+                out.extend_from_slice(b"\x1b\\");
+            }
+        }
+        Protocol::Iterm2 => {
+            let png = to_png(image);
+            let _ = write!(
+                out,
+                "\x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=1:{}\x07",
+                png.len(),
+                rect.width,
+                rect.height,
+                base64::engine::general_purpose::STANDARD.encode(&png)
+            );
+        }
+        Protocol::Sixel => {
+            let pixels = image
+                .resize_exact(
+                    rect.width as u32 * CELL_WIDTH,
+                    rect.height as u32 * CELL_HEIGHT,
+                    FilterType::Triangle,
+                )
+                .to_rgb8();
+            encode_sixel(&pixels, &mut out);
+        }
+        Protocol::HalfBlocks => {
+            // Each cell shows two pixels: the upper half block in the foreground
+            // color and the lower half in the background color
+            let pixels = image
+                .resize_exact(rect.width as u32, rect.height as u32 * 2, FilterType::Triangle)
+                .to_rgb8();
+            for row in 0..rect.height {
+                move_to(&mut out, rect.x, rect.y + row);
+                for col in 0..rect.width as u32 {
+                    let top = pixels.get_pixel(col, row as u32 * 2);
+                    let bottom = pixels.get_pixel(col, row as u32 * 2 + 1);
+                    let _ = write!(
+                        out,
+                        "\x1b[38;2;{};{};{}m\x1b[48;2;{};{};{}m▀",
+                        top[0], top[1], top[2], bottom[0], bottom[1], bottom[2]
+                    );
+                }
+            }
+            out.extend_from_slice(b"\x1b[0m");
+        }
+        Protocol::Off => out.clear(),
+    }
+
+    out
+}
+
+/// Encode an image as sixels using a 6x6x6 color cube
+fn encode_sixel(image: &RgbImage, out: &mut Vec<u8>) {
+    let (width, height) = image.dimensions();
+    let level = |v: u8| (v as usize * 5 + 127) / 255;
+    let color_of = |x: u32, y: u32| {
+        let p = image.get_pixel(x, y);
+        level(p[0]) * 36 + level(p[1]) * 6 + level(p[2])
+    };
+
+    let _ = write!(out, "\x1bPq\"1;1;{};{}", width, height);
+    for i in 0..216 {
+        let _ = write!(out, "#{};2;{};{};{}", i, i / 36 * 20, i / 6 % 6 * 20, i % 6 * 20);
+    }
+
+    for band in (0..height).step_by(6) {
+        let rows = (height - band).min(6);
+        let colors: Vec<Vec<usize>> = (0..rows)
+            .map(|dy| (0..width).map(|x| color_of(x, band + dy)).collect())
+            .collect();
+
+        let mut used = [false; 216];
+        colors.iter().flatten().for_each(|&c| used[c] = true);
+
+        for color in (0..216).filter(|&c| used[c]) {
+            let _ = write!(out, "#{}", color);
+            let mut bits = vec![0u8; width as usize];
+            for (dy, row) in colors.iter().enumerate() {
+                for (x, &c) in row.iter().enumerate() {
+                    if c == color {
+                        bits[x] |= 1 << dy;
+                    }
+                }
+            }
+
+            let mut run: Option<(u8, usize)> = None;
+            for sixel in bits.into_iter().map(|b| 63 + b) {
+                run = match run {
+                    Some((c, n)) if c == sixel => Some((c, n + 1)),
+                    Some((c, n)) => {
+                        write_run(out, c, n);
+                        Some((sixel, 1))
+                    }
+                    None => Some((sixel, 1)),
+                };
+            }
+            if let Some((c, n)) = run {
+                write_run(out, c, n);
+            }
+            // Back to the start of the band for the next color
+            out.push(b'$');
+        }
+        out.push(b'-');
+    }
+
+    out.extend_from_slice(b"\x1b\\");
+}
+
+fn write_run(out: &mut Vec<u8>, sixel: u8, count: usize) {
+    if count > 3 {
+        let _ = write!(out, "!{}", count);
+        out.push(sixel);
+    } else {
+        out.extend(std::iter::repeat_n(sixel, count));
+    }
+}
+
+enum ImageState {
+    Loading,
+    Ready(DynamicImage),
+    Failed,
+}
+
+/// Loads artwork in the background and draws it over the rendered frame
+pub struct ArtworkRenderer {
+    protocol: Protocol,
+    images: HashMap<String, ImageState>,
+    /// What is currently drawn on screen, and the screen size it was drawn at
+    shown: Option<(Placement, Rect)>,
+}
+
+impl ArtworkRenderer {
+    pub fn new(protocol: Protocol) -> Self {
+        Self {
+            protocol,
+            images: HashMap::new(),
+            shown: None,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.protocol != Protocol::Off
+    }
+
+    pub fn image_loaded(&mut self, url: String, result: Result<DynamicImage, String>) {
+        let state = match result {
+            Ok(image) => ImageState::Ready(image),
+            Err(e) => {
+                log::warn!("Artwork {} unavailable: {}", url, e);
+                ImageState::Failed
+            }
+        };
+        self.images.insert(url, state);
+    }
+
+    /// Where the previous image has to be painted over with the frame's
+    /// cells before `placement` is drawn. Kitty images are deleted instead.
+    pub fn stale_area(&self, placement: Option<&Placement>) -> Option<Rect> {
+        match &self.shown {
+            Some((shown, _)) if self.protocol != Protocol::Kitty && Some(shown) != placement => Some(shown.area),
+            _ => None,
+        }
+    }
+
+    /// Draw the artwork requested by the latest frame, if it changed. Images
+    /// that aren't loaded yet are fetched in the background.
+    pub fn render<W: Write>(
+        &mut self,
+        out: &mut W,
+        placement: Option<Placement>,
+        screen: Rect,
+        tx: &mpsc::Sender<AppEvent>,
+    ) -> std::io::Result<()> {
+        let current = placement.clone().map(|p| (p, screen));
+        if self.shown.is_some() && self.shown == current {
+            return Ok(());
+        }
+
+        let mut bytes = Vec::new();
+        if self.protocol == Protocol::Kitty && self.shown.is_some() {
+            let _ = write!(bytes, "\x1b_Ga=d,d=I,i={},q=2\x1b\\", KITTY_IMAGE_ID);
+        }
+        self.shown = None;
+
+        if let Some(placement) = placement {
+            match self.images.get(&placement.url) {
+                Some(ImageState::Ready(image)) => {
+                    bytes.extend(encode(self.protocol, image, placement.area));
+                    self.shown = current;
+                }
+                Some(ImageState::Loading) | Some(ImageState::Failed) => {}
+                None => {
+                    self.images.insert(placement.url.clone(), ImageState::Loading);
+                    let url = placement.url;
+                    let tx = tx.clone();
+                    std::thread::spawn(move || {
+                        let result = fetch(&url);
+                        let _ = tx.send(AppEvent::ArtworkLoaded(url, result));
+                    });
+                }
+            }
+        }
+
+        if !bytes.is_empty() {
+            out.write_all(&bytes)?;
+            out.flush()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_and_fit() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| vars.iter().find(|(k, _)| *k == name).map(|(_, v)| v.to_string())
+        };
+        assert_eq!(Protocol::detect(&env(&[("KITTY_WINDOW_ID", "1")])), Protocol::Kitty);
+        assert_eq!(Protocol::detect(&env(&[("TERM_PROGRAM", "iTerm.app")])), Protocol::Iterm2);
+        assert_eq!(Protocol::detect(&env(&[("TERM", "foot")])), Protocol::Sixel);
+        assert_eq!(Protocol::detect(&env(&[("TERM", "xterm-256color")])), Protocol::HalfBlocks);
+
+        // A square image is twice as many cells wide as it is tall
+        assert_eq!(fit(Rect::new(0, 0, 40, 10), 600, 600), Rect::new(10, 0, 20, 10));
+        assert_eq!(fit(Rect::new(5, 5, 10, 20), 600, 600), Rect::new(5, 12, 10, 5));
+    }
+
+    #[test]
+    fn test_encode_half_blocks_and_sixel() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(4, 4, image::Rgb([255, 0, 0])));
+
+        let blocks = String::from_utf8(encode(Protocol::HalfBlocks, &image, Rect::new(0, 0, 4, 2))).unwrap();
+        assert_eq!(blocks.matches('▀').count(), 8);
+        assert!(blocks.starts_with("\x1b[1;1H"));
+        assert!(blocks.ends_with("\x1b[0m"));
+
+        let sixel = String::from_utf8(encode(Protocol::Sixel, &image, Rect::new(0, 0, 4, 2))).unwrap();
+        assert!(sixel.contains("\x1bPq"));
+        // Pure red is color 180 of the cube; the first band is one full run of it
+        assert!(sixel.contains("#180!40~$"));
+        assert!(sixel.ends_with("\x1b\\"));
+    }
+
+    #[test]
+    fn test_stale_area_of_a_moved_image() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(4, 4, image::Rgb([255, 0, 0])));
+        let placement = |x| Placement { url: "cover".to_string(), area: Rect::new(x, 0, 4, 2) };
+        let screen = Rect::new(0, 0, 80, 24);
+        let (tx, _rx) = mpsc::channel();
+
+        let mut renderer = ArtworkRenderer::new(Protocol::HalfBlocks);
+        renderer.image_loaded("cover".to_string(), Ok(image.clone()));
+        renderer.render(&mut Vec::new(), Some(placement(0)), screen, &tx).unwrap();
+        assert_eq!(renderer.stale_area(Some(&placement(0))), None);
+        assert_eq!(renderer.stale_area(Some(&placement(10))), Some(Rect::new(0, 0, 4, 2)));
+        assert_eq!(renderer.stale_area(None), Some(Rect::new(0, 0, 4, 2)));
+
+        // Kitty deletes its image itself
+        let mut kitty = ArtworkRenderer::new(Protocol::Kitty);
+        kitty.image_loaded("cover".to_string(), Ok(image));
+        kitty.render(&mut Vec::new(), Some(placement(0)), screen, &tx).unwrap();
+        assert_eq!(kitty.stale_area(None), None);
+    }
+}
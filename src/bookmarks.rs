@@ -0,0 +1,235 @@
+use crate::app::EpisodeRef;
+use crate::now_playing::format_time;
+use crate::persistence;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+const BOOKMARKS_FILE: &str = "bookmarks.json";
+
+/// A marked position in an episode. Titles are stored alongside the URLs so
+/// bookmarks still make sense after a podcast has been deleted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub podcast_title: String,
+    pub podcast_url: String,
+    pub episode_title: String,
+    pub audio_url: String,
+    pub position_secs: u64,
+    #[serde(default)]
+    pub note: String,
+    /// Unix timestamp (seconds)
+    pub created: i64,
+}
+
+impl Bookmark {
+    pub fn episode(&self) -> EpisodeRef {
+        EpisodeRef {
+            podcast_url: self.podcast_url.clone(),
+            audio_url: self.audio_url.clone(),
+        }
+    }
+
+    /// Position and note as listed, e.g. "41:12  Great quote"
+    pub fn label(&self) -> String {
+        if self.note.is_empty() {
+            format_time(self.position_secs)
+        } else {
+            format!("{}  {}", format_time(self.position_secs), self.note)
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BookmarkData {
+    bookmarks: Vec<Bookmark>,
+}
+
+/// Load every bookmark, most recently created first
+pub fn load() -> Result<Vec<Bookmark>, String> {
+    load_from(&persistence::get_data_file(BOOKMARKS_FILE)?)
+}
+
+fn load_from(path: &Path) -> Result<Vec<Bookmark>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let json = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read bookmarks file: {}", e))?;
+
+    let data: BookmarkData = serde_json::from_str(&json)
+        .map_err(|e| format!("Failed to deserialize bookmarks: {}", e))?;
+
+    Ok(data.bookmarks)
+}
+
+fn save_to(path: &Path, bookmarks: &[Bookmark]) -> Result<(), String> {
+    let data = BookmarkData {
+        bookmarks: bookmarks.to_vec(),
+    };
+
+    let json = serde_json::to_string_pretty(&data)
+        .map_err(|e| format!("Failed to serialize bookmarks: {}", e))?;
+
+    persistence::write_atomic(path, &json)
+        .map_err(|e| format!("Failed to write bookmarks file: {}", e))?;
+
+    Ok(())
+}
+
+/// Render bookmarks as Markdown, one section per episode with its bookmarks in
+/// playback order. Positions link to the audio with a `#t=` media fragment.
+pub fn to_markdown(bookmarks: &[Bookmark]) -> String {
+    let mut episodes: Vec<&str> = Vec::new();
+    for bookmark in bookmarks {
+        if !episodes.contains(&bookmark.audio_url.as_str()) {
+            episodes.push(&bookmark.audio_url);
+        }
+    }
+
+    let mut out = String::from("# Bookmarks\n");
+    for audio_url in episodes {
+        let mut marks: Vec<&Bookmark> = bookmarks.iter().filter(|b| b.audio_url == audio_url).collect();
+        marks.sort_by_key(|b| b.position_secs);
+
+        out.push_str(&format!("\n## {} — {}\n\n", marks[0].podcast_title, marks[0].episode_title));
+        for mark in marks {
+            let time = format!("[{}]({}#t={})", format_time(mark.position_secs), mark.audio_url, mark.position_secs);
+            if mark.note.is_empty() {
+                out.push_str(&format!("- {}\n", time));
+            } else {
+                out.push_str(&format!("- {} {}\n", time, mark.note));
+            }
+        }
+    }
+    out
+}
+
+pub struct BookmarksState {
+    /// Most recently created first
+    pub bookmarks: Vec<Bookmark>,
+    pub selected_index: usize,
+}
+
+impl BookmarksState {
+    pub fn new() -> Self {
+        Self {
+            bookmarks: Vec::new(),
+            selected_index: 0,
+        }
+    }
+
+    /// Reload the bookmarks from disk
+    pub fn reload(&mut self) {
+        self.bookmarks = load().unwrap_or_else(|e| {
+            log::error!("Failed to load bookmarks: {}", e);
+            Vec::new()
+        });
+        self.clamp_selection();
+    }
+
+    fn clamp_selection(&mut self) {
+        if self.selected_index >= self.bookmarks.len() {
+            self.selected_index = self.bookmarks.len().saturating_sub(1);
+        }
+    }
+
+    pub fn add(&mut self, bookmark: Bookmark) -> Result<(), String> {
+        self.update(|bookmarks| bookmarks.insert(0, bookmark))
+    }
+
+    pub fn remove(&mut self, bookmark: &Bookmark) -> Result<(), String> {
+        self.update(|bookmarks| bookmarks.retain(|b| b != bookmark))
+    }
+
+    fn update(&mut self, change: impl FnOnce(&mut Vec<Bookmark>)) -> Result<(), String> {
+        self.update_at(&persistence::get_data_file(BOOKMARKS_FILE)?, change)
+    }
+
+    /// Apply a change on top of the file as it is now, so bookmarks saved by
+    /// another client since this one loaded aren't lost
+    fn update_at(&mut self, path: &Path, change: impl FnOnce(&mut Vec<Bookmark>)) -> Result<(), String> {
+        self.bookmarks = load_from(path)?;
+        change(&mut self.bookmarks);
+        self.clamp_selection();
+        save_to(path, &self.bookmarks)
+    }
+
+    /// Bookmarks in one episode, in playback order
+    pub fn for_episode(&self, audio_url: &str) -> Vec<&Bookmark> {
+        let mut marks: Vec<&Bookmark> = self.bookmarks.iter().filter(|b| b.audio_url == audio_url).collect();
+        marks.sort_by_key(|b| b.position_secs);
+        marks
+    }
+
+    pub fn move_up(&mut self) {
+        if self.selected_index > 0 {
+            self.selected_index -= 1;
+        }
+    }
+
+    pub fn move_down(&mut self) {
+        if self.selected_index < self.bookmarks.len().saturating_sub(1) {
+            self.selected_index += 1;
+        }
+    }
+
+    pub fn selected(&self) -> Option<&Bookmark> {
+        self.bookmarks.get(self.selected_index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bookmark(audio_url: &str, episode_title: &str, position_secs: u64, note: &str) -> Bookmark {
+        Bookmark {
+            podcast_title: "Show".to_string(),
+            podcast_url: "https://example.com/feed".to_string(),
+            episode_title: episode_title.to_string(),
+            audio_url: audio_url.to_string(),
+            position_secs,
+            note: note.to_string(),
+            created: 0,
+        }
+    }
+
+    #[test]
+    fn test_bookmarks_markdown_groups_by_episode() {
+        let bookmarks = vec![
+            bookmark("https://example.com/b.mp3", "B", 2472, "Great quote"),
+            bookmark("https://example.com/a.mp3", "A", 90, ""),
+            bookmark("https://example.com/b.mp3", "B", 60, "Intro"),
+        ];
+
+        assert_eq!(
+            to_markdown(&bookmarks),
+            "# Bookmarks\n\n\
+             ## Show — B\n\n\
+             - [1:00](https://example.com/b.mp3#t=60) Intro\n\
+             - [41:12](https://example.com/b.mp3#t=2472) Great quote\n\
+             \n## Show — A\n\n\
+             - [1:30](https://example.com/a.mp3#t=90)\n"
+        );
+    }
+
+    #[test]
+    fn test_bookmarks_from_two_clients_are_both_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(BOOKMARKS_FILE);
+        let first = bookmark("https://example.com/a.mp3", "A", 60, "");
+        let second = bookmark("https://example.com/b.mp3", "B", 90, "");
+        let mut one = BookmarksState::new();
+        let mut other = BookmarksState::new();
+
+        one.update_at(&path, |b| b.insert(0, first.clone())).unwrap();
+        other.update_at(&path, |b| b.insert(0, second.clone())).unwrap();
+        assert_eq!(load_from(&path).unwrap(), vec![second.clone(), first.clone()]);
+
+        one.update_at(&path, |b| b.retain(|b| *b != first)).unwrap();
+        assert_eq!(one.bookmarks, vec![second.clone()]);
+        assert_eq!(load_from(&path).unwrap(), vec![second]);
+    }
+}
@@ -1,5 +1,14 @@
+use crate::app::{Episode, PaneFocus, Podcast};
+use crate::directory::Category;
+use crate::persistence;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+
+const TOPLIST_CACHE_FILE: &str = "toplist_cache.json";
+
+/// How long a cached toplist is used before it is fetched again
+pub const TOPLIST_CACHE_SECS: i64 = 24 * 60 * 60;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchResult {
@@ -9,81 +18,289 @@ pub struct SearchResult {
     pub feed_url: String,
     pub artwork_url: Option<String>,
     pub subscribers: u64,
+    /// Homepage of the podcast, if the directory provides one
+    #[serde(default)]
+    pub website: Option<String>,
+}
+
+impl SearchResult {
+    /// Fill in the fields this result is missing from another entry for the same podcast
+    pub fn fill_missing(&mut self, other: &SearchResult) {
+        if self.author.is_empty() {
+            self.author = other.author.clone();
+        }
+        if self.description.is_empty() {
+            self.description = other.description.clone();
+        }
+        if self.artwork_url.is_none() {
+            self.artwork_url = other.artwork_url.clone();
+        }
+        if self.subscribers == 0 {
+            self.subscribers = other.subscribers;
+        }
+        if self.website.is_none() {
+            self.website = other.website.clone();
+        }
+    }
+}
+
+/// Loading state of data fetched in the background
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoadState {
+    NotLoaded,
+    Loading,
+    Loaded,
+    Failed(String),
+}
+
+/// What the results pane is listing
+#[derive(Debug, Clone, PartialEq)]
+pub enum BrowseListing {
+    Featured,
+    /// Recommendations based on the account or current subscriptions
+    Suggested,
+    Search(String),
+    Category(Category),
+}
+
+/// Number of recent episodes listed in a podcast preview
+pub const PREVIEW_EPISODES: usize = 20;
+
+/// A feed fetched for preview without subscribing to it
+pub struct Preview {
+    pub result: SearchResult,
+    pub state: LoadState,
+    /// The parsed feed, reused when subscribing from the preview
+    pub podcast: Option<Podcast>,
+    pub selected_episode: usize,
+}
+
+impl Preview {
+    pub fn new(result: SearchResult) -> Self {
+        Self {
+            result,
+            state: LoadState::Loading,
+            podcast: None,
+            selected_episode: 0,
+        }
+    }
+
+    /// The most recent episodes, as listed in the preview
+    pub fn episodes(&self) -> &[Episode] {
+        match &self.podcast {
+            Some(podcast) => &podcast.episodes[..podcast.episodes.len().min(PREVIEW_EPISODES)],
+            None => &[],
+        }
+    }
+
+    pub fn selected_episode(&self) -> Option<&Episode> {
+        self.episodes().get(self.selected_episode)
+    }
+
+    /// Store the fetched feed
+    pub fn set_loaded(&mut self, result: Result<Podcast, String>) {
+        match result {
+            Ok(podcast) => {
+                self.podcast = Some(podcast);
+                self.state = LoadState::Loaded;
+            }
+            Err(e) => self.state = LoadState::Failed(e),
+        }
+        self.selected_episode = 0;
+    }
 }
 
 pub struct BrowseState {
     pub search_query: String,
+    /// Results on the current page
     pub search_results: Vec<SearchResult>,
     pub selected_index: usize,
     pub is_searching: bool,
-    pub showing_defaults: bool,
+    pub listing: BrowseListing,
+    /// Zero-based page of search or category results
+    pub page: usize,
+    /// Whether there are results after the current page
+    pub has_more: bool,
+    pub toplist: LoadState,
+    /// A search request is running in the background
+    pub search_pending: bool,
+
+    // Category pane
+    pub categories: Vec<Category>,
+    pub categories_state: LoadState,
+    pub selected_category: usize,
+    /// Left is the category pane, right the results
+    pub focus: PaneFocus,
+
+    /// Podcast being previewed in place of the results
+    pub preview: Option<Preview>,
+    /// Normalized feed URLs already looked up in the directory for their details
+    pub looked_up: HashSet<String>,
 }
 
 impl BrowseState {
     pub fn new() -> Self {
         Self {
             search_query: String::new(),
-            search_results: get_default_podcasts(),
+            search_results: Vec::new(),
             selected_index: 0,
             is_searching: false,
-            showing_defaults: true,
+            listing: BrowseListing::Featured,
+            page: 0,
+            has_more: false,
+            toplist: LoadState::NotLoaded,
+            search_pending: false,
+            categories: Vec::new(),
+            categories_state: LoadState::NotLoaded,
+            selected_category: 0,
+            focus: PaneFocus::Right,
+            preview: None,
+            looked_up: HashSet::new(),
         }
     }
 
+    pub fn is_featured(&self) -> bool {
+        self.listing == BrowseListing::Featured
+    }
+
+    /// Show a new listing starting from its first page
+    pub fn set_listing(&mut self, listing: BrowseListing) {
+        self.listing = listing;
+        self.page = 0;
+        self.has_more = false;
+        self.search_results.clear();
+        self.selected_index = 0;
+    }
+
+    /// Store the results fetched for `page`, which include every earlier page
+    /// plus one extra result to detect whether another page exists
+    pub fn set_page_results(&mut self, page: usize, results: Vec<SearchResult>) {
+        self.has_more = results.len() > (page + 1) * PAGE_SIZE;
+        self.page = page;
+        self.search_results = results.into_iter().skip(page * PAGE_SIZE).take(PAGE_SIZE).collect();
+        self.selected_index = 0;
+    }
+
     pub fn move_up(&mut self) {
-        if self.selected_index > 0 {
-            self.selected_index -= 1;
+        if let Some(preview) = &mut self.preview {
+            preview.selected_episode = preview.selected_episode.saturating_sub(1);
+            return;
+        }
+        match self.focus {
+            PaneFocus::Left => self.selected_category = self.selected_category.saturating_sub(1),
+            PaneFocus::Right => self.selected_index = self.selected_index.saturating_sub(1),
         }
     }
 
     pub fn move_down(&mut self) {
-        if self.selected_index < self.search_results.len().saturating_sub(1) {
-            self.selected_index += 1;
+        if let Some(preview) = &mut self.preview {
+            if preview.selected_episode < preview.episodes().len().saturating_sub(1) {
+                preview.selected_episode += 1;
+            }
+            return;
+        }
+        match self.focus {
+            PaneFocus::Left => {
+                if self.selected_category < self.categories.len().saturating_sub(1) {
+                    self.selected_category += 1;
+                }
+            }
+            PaneFocus::Right => {
+                if self.selected_index < self.search_results.len().saturating_sub(1) {
+                    self.selected_index += 1;
+                }
+            }
         }
     }
 
+    pub fn switch_focus(&mut self) {
+        self.focus = match self.focus {
+            PaneFocus::Left => PaneFocus::Right,
+            PaneFocus::Right => PaneFocus::Left,
+        };
+    }
+
+    pub fn selected_category(&self) -> Option<&Category> {
+        self.categories.get(self.selected_category)
+    }
+
     pub fn selected_result(&self) -> Option<&SearchResult> {
         self.search_results.get(self.selected_index)
     }
+
+    /// Fill in the results and preview for a feed with what the directory knows about it
+    pub fn details_loaded(&mut self, feed_url: &str, details: &SearchResult) {
+        let feed = normalize_feed_url(feed_url);
+        let preview = self.preview.as_mut().map(|p| &mut p.result);
+        for result in self.search_results.iter_mut().chain(preview) {
+            if normalize_feed_url(&result.feed_url) == feed {
+                result.fill_missing(details);
+            }
+        }
+    }
+}
+
+/// Toplist saved to disk so Browse opens instantly and works offline
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ToplistCache {
+    /// Unix timestamp of the fetch
+    pub fetched_at: i64,
+    /// Directory providers the list came from
+    pub providers: Vec<String>,
+    pub results: Vec<SearchResult>,
+}
+
+impl ToplistCache {
+    /// Whether the cache came from the same providers and hasn't expired
+    pub fn is_fresh(&self, providers: &[&str], now: i64) -> bool {
+        self.providers == providers
+            && now >= self.fetched_at
+            && now - self.fetched_at < TOPLIST_CACHE_SECS
+    }
 }
 
-/// Fetches popular podcasts from gpodder.net toplist
-pub fn get_default_podcasts() -> Vec<SearchResult> {
-    // Fetch top podcasts from gpodder.net
-    // Fallback to empty list if fetch fails
-    fetch_top_podcasts(10).unwrap_or_default()
+pub fn load_toplist_cache() -> Result<Option<ToplistCache>, String> {
+    let path = persistence::get_data_file(TOPLIST_CACHE_FILE)?;
+
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let json = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read toplist cache: {}", e))?;
+
+    let cache = serde_json::from_str(&json)
+        .map_err(|e| format!("Failed to deserialize toplist cache: {}", e))?;
+
+    Ok(Some(cache))
 }
 
-/// Fetch top podcasts from gpodder.net toplist API
-pub fn fetch_top_podcasts(count: u32) -> Result<Vec<SearchResult>, String> {
-    let count = count.clamp(1, 100); // API accepts 1-100
-    let url = format!("https://gpodder.net/toplist/{}.json", count);
+pub fn save_toplist_cache(cache: &ToplistCache) -> Result<(), String> {
+    let path = persistence::get_data_file(TOPLIST_CACHE_FILE)?;
 
-    let response = reqwest::blocking::get(&url)
-        .map_err(|e| format!("Failed to fetch toplist: {}", e))?;
+    let json = serde_json::to_string_pretty(cache)
+        .map_err(|e| format!("Failed to serialize toplist cache: {}", e))?;
 
-    let json: serde_json::Value = response.json()
-        .map_err(|e| format!("Failed to parse toplist: {}", e))?;
+    fs::write(&path, json)
+        .map_err(|e| format!("Failed to write toplist cache: {}", e))?;
 
-    let results_array = json.as_array()
-        .ok_or("Invalid toplist format")?;
+    Ok(())
+}
 
-    let results: Vec<SearchResult> = results_array.iter()
-        .filter_map(|item| {
-            Some(SearchResult {
-                title: item["title"].as_str()?.to_string(),
-                author: item["author"].as_str().unwrap_or("Unknown").to_string(),
-                description: item["description"].as_str().unwrap_or("").to_string(),
-                feed_url: item["url"].as_str()?.to_string(),
-                artwork_url: item["logo_url"].as_str().map(String::from),
-                subscribers: item["subscribers"].as_u64().unwrap_or(0),
-            })
-        })
-        .collect();
+/// Number of search or category results per page
+pub const PAGE_SIZE: usize = 20;
 
-    Ok(results)
+/// Number of categories listed in the category pane
+pub const CATEGORY_LIMIT: usize = 40;
+
+/// Result count to request so that `page` and the existence of a next page are known
+pub fn page_fetch_limit(page: usize) -> usize {
+    (page + 1) * PAGE_SIZE + 1
 }
 
+/// Number of podcasts shown before searching
+pub const TOPLIST_LIMIT: usize = 10;
+
 /// Normalize title for deduplication (lowercase, remove extra chars)
 fn normalize_title(title: &str) -> String {
     title.to_lowercase()
@@ -104,60 +321,176 @@ fn get_hostname(url: &str) -> Option<String> {
         .map(|s| s.to_string())
 }
 
-/// Search podcasts using gpodder.net API (free, open, no auth required)
-pub fn search_podcasts(query: &str) -> Result<Vec<SearchResult>, String> {
-    if query.is_empty() {
-        return Ok(Vec::new());
+/// Normalize a feed URL for comparing subscriptions: ignores the scheme, `www.`,
+/// host case, fragments and trailing slashes
+pub fn normalize_feed_url(url: &str) -> String {
+    let url = url.trim();
+    let url = url.split('#').next().unwrap_or(url);
+    let rest = match url.find("://") {
+        Some(i) => &url[i + 3..],
+        None => url,
+    };
+    let (host, path) = match rest.find(['/', '?']) {
+        Some(i) => rest.split_at(i),
+        None => (rest, ""),
+    };
+    let host = host.to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    format!("{}{}", host, path.trim_end_matches('/'))
+}
+
+/// Deduplicate by normalized title + hostname, keeping the one with most subscribers.
+/// Fields missing from the kept result are filled in from its duplicates.
+pub fn dedupe_results(results: Vec<SearchResult>) -> Vec<SearchResult> {
+    let mut deduped: Vec<SearchResult> = Vec::new();
+    let mut index: HashMap<(String, String), usize> = HashMap::new();
+
+    for result in results {
+        let Some(hostname) = get_hostname(&result.feed_url) else {
+            continue;
+        };
+        let key = (normalize_title(&result.title), hostname);
+
+        match index.get(&key) {
+            Some(&i) => {
+                let existing = &mut deduped[i];
+                let mut result = result;
+                if result.subscribers > existing.subscribers {
+                    std::mem::swap(existing, &mut result);
+                }
+                existing.fill_missing(&result);
+            }
+            None => {
+                index.insert(key, deduped.len());
+                deduped.push(result);
+            }
+        }
+    }
+
+    // Stable sort keeps the provider order for results without subscriber counts
+    deduped.sort_by_key(|r| std::cmp::Reverse(r.subscribers));
+    deduped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(title: &str, feed_url: &str, subscribers: u64, description: &str) -> SearchResult {
+        SearchResult {
+            title: title.to_string(),
+            author: String::new(),
+            description: description.to_string(),
+            feed_url: feed_url.to_string(),
+            artwork_url: None,
+            subscribers,
+            website: None,
+        }
     }
 
-    // gpodder.net search API - completely free and open
-    let url = format!("https://gpodder.net/search.json?q={}",
-        urlencoding::encode(query));
+    #[test]
+    fn test_normalize_feed_url() {
+        assert_eq!(
+            normalize_feed_url("https://www.Example.com/feed/"),
+            normalize_feed_url("http://example.com/feed")
+        );
+        assert_eq!(normalize_feed_url("https://example.com/rss#latest"), "example.com/rss");
+        assert_ne!(normalize_feed_url("https://example.com/a"), normalize_feed_url("https://example.com/b"));
+    }
 
-    let response = reqwest::blocking::get(&url)
-        .map_err(|e| format!("Search failed: {}", e))?;
+    #[test]
+    fn test_toplist_cache_expiry() {
+        let cache = ToplistCache {
+            fetched_at: 1_000,
+            providers: vec!["gpodder".to_string()],
+            results: Vec::new(),
+        };
 
-    let json: serde_json::Value = response.json()
-        .map_err(|e| format!("Failed to parse response: {}", e))?;
+        assert!(cache.is_fresh(&["gpodder"], 1_000 + 60));
+        assert!(!cache.is_fresh(&["gpodder"], 1_000 + TOPLIST_CACHE_SECS));
+        assert!(!cache.is_fresh(&["gpodder", "itunes"], 1_000 + 60));
+    }
 
-    let results_array = json.as_array()
-        .ok_or("Invalid response format")?;
+    #[test]
+    fn test_preview_lists_recent_episodes() {
+        let episode = |i: usize| Episode {
+            title: format!("Episode {}", i),
+            audio_url: format!("https://example.com/{}.mp3", i),
+            ..Default::default()
+        };
+        let podcast = Podcast {
+            title: "Show".to_string(),
+            description: String::new(),
+            url: "https://example.com/feed".to_string(),
+            episodes: (0..PREVIEW_EPISODES + 5).map(episode).collect(),
+            filter: Default::default(),
+            categories: Vec::new(),
+            image_url: None,
+        };
 
-    let all_results: Vec<SearchResult> = results_array.iter()
-        .filter_map(|item| {
-            Some(SearchResult {
-                title: item["title"].as_str()?.to_string(),
-                author: item["author"].as_str().unwrap_or("Unknown").to_string(),
-                description: item["description"].as_str().unwrap_or("").to_string(),
-                feed_url: item["url"].as_str()?.to_string(),
-                artwork_url: item["logo_url"].as_str().map(String::from),
-                subscribers: item["subscribers"].as_u64().unwrap_or(0),
-            })
-        })
-        .collect();
+        let mut browse = BrowseState::new();
+        browse.preview = Some(Preview::new(result("Show", "https://example.com/feed", 0, "")));
+        browse.move_down();
+        assert_eq!(browse.preview.as_ref().unwrap().selected_episode, 0);
+
+        browse.preview.as_mut().unwrap().set_loaded(Ok(podcast));
+        for _ in 0..PREVIEW_EPISODES + 5 {
+            browse.move_down();
+        }
+        let preview = browse.preview.as_ref().unwrap();
+        assert_eq!(preview.episodes().len(), PREVIEW_EPISODES);
+        assert_eq!(preview.selected_episode().unwrap().title, "Episode 19");
+        assert_eq!(preview.state, LoadState::Loaded);
+    }
 
-    // Deduplicate by normalized title + hostname, keeping the one with most subscribers
-    let mut deduped: HashMap<(String, String), SearchResult> = HashMap::new();
+    #[test]
+    fn test_details_fill_in_missing_fields() {
+        let mut browse = BrowseState::new();
+        browse.search_results = vec![result("Show", "https://example.com/feed", 0, "From iTunes")];
+        browse.preview = Some(Preview::new(result("Show", "http://www.example.com/feed/", 0, "")));
 
-    for result in all_results {
-        if let Some(hostname) = get_hostname(&result.feed_url) {
-            let key = (normalize_title(&result.title), hostname);
+        let details = SearchResult {
+            author: "Host".to_string(),
+            website: Some("https://example.com".to_string()),
+            ..result("Show", "https://example.com/feed", 1200, "From gpodder")
+        };
+        browse.details_loaded("https://example.com/feed", &details);
 
-            deduped.entry(key)
-                .and_modify(|existing| {
-                    // Keep the one with more subscribers
-                    if result.subscribers > existing.subscribers {
-                        *existing = result.clone();
-                    }
-                })
-                .or_insert(result);
+        for result in [&browse.search_results[0], &browse.preview.as_ref().unwrap().result] {
+            assert_eq!(result.author, "Host");
+            assert_eq!(result.subscribers, 1200);
+            assert_eq!(result.website.as_deref(), Some("https://example.com"));
         }
+        assert_eq!(browse.search_results[0].description, "From iTunes", "known fields are kept");
+        assert_eq!(browse.preview.as_ref().unwrap().result.description, "From gpodder");
     }
 
-    // Convert back to Vec and sort by subscriber count (descending)
-    let mut results: Vec<SearchResult> = deduped.into_values().collect();
-    results.sort_by(|a, b| b.subscribers.cmp(&a.subscribers));
-    results.truncate(20); // Limit to 20 results
+    #[test]
+    fn test_browse_paging() {
+        let results: Vec<SearchResult> = (0..page_fetch_limit(1))
+            .map(|i| result(&format!("Show {}", i), &format!("https://host{}.com/feed", i), 0, ""))
+            .collect();
+
+        let mut browse = BrowseState::new();
+        browse.set_page_results(1, results);
+        assert_eq!(browse.search_results.len(), PAGE_SIZE);
+        assert_eq!(browse.search_results[0].title, "Show 20");
+        assert!(browse.has_more);
+    }
 
-    Ok(results)
+    #[test]
+    fn test_dedupe_results_merges_duplicates() {
+        let results = vec![
+            result("Rust Podcast", "https://example.com/feed.xml", 0, "From iTunes"),
+            result("Other Show", "https://other.org/rss", 0, ""),
+            result("rust (podcast)", "https://example.com/rss", 50, ""),
+        ];
+
+        let deduped = dedupe_results(results);
+        assert_eq!(deduped.len(), 2);
+        assert_eq!(deduped[0].subscribers, 50);
+        assert_eq!(deduped[0].feed_url, "https://example.com/rss");
+        assert_eq!(deduped[0].description, "From iTunes");
+        assert_eq!(deduped[1].title, "Other Show");
+    }
 }
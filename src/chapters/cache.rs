@@ -0,0 +1,88 @@
+//! Chapter lists kept on disk per episode, so each source is only tried once
+
+use super::{get_chapters, ChapterList, ChapterSource};
+use crate::app::Episode;
+use crate::persistence;
+use sha1::{Digest, Sha1};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CHAPTERS_DIR: &str = "chapters";
+
+/// File name for an episode's chapters. The chapters URL is part of the key so
+/// a feed that starts publishing a chapters file isn't stuck with the old list.
+fn cache_name(episode: &Episode) -> String {
+    let key = format!("{}\n{}", episode.audio_url, episode.chapters_url.as_deref().unwrap_or(""));
+    let digest = Sha1::digest(key.as_bytes());
+    let name: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("{}.json", name)
+}
+
+fn read(dir: &Path, episode: &Episode) -> Option<ChapterList> {
+    let data = fs::read_to_string(dir.join(cache_name(episode))).ok()?;
+    serde_json::from_str(&data)
+        .map_err(|e| log::warn!("Ignoring corrupt chapter cache for '{}': {}", episode.title, e))
+        .ok()
+}
+
+fn write(dir: &Path, episode: &Episode, chapters: &ChapterList) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create chapter cache: {}", e))?;
+    let data = serde_json::to_string(chapters).map_err(|e| format!("Failed to serialize chapters: {}", e))?;
+    persistence::write_atomic(&dir.join(cache_name(episode)), &data)
+        .map_err(|e| format!("Failed to cache chapters: {}", e))
+}
+
+/// Whether a cached list is worth keeping, or a better source may be available now
+fn is_final(chapters: &ChapterList, audio_path: Option<&Path>) -> bool {
+    chapters.source != Some(ChapterSource::Description) || audio_path.is_none()
+}
+
+fn load_from(dir: &Path, episode: &Episode, audio_path: Option<&Path>) -> Result<ChapterList, String> {
+    if let Some(chapters) = read(dir, episode).filter(|c| is_final(c, audio_path)) {
+        return Ok(chapters);
+    }
+
+    let chapters = get_chapters(episode, audio_path)?;
+    if let Err(e) = write(dir, episode, &chapters) {
+        log::warn!("{}", e);
+    }
+    Ok(chapters)
+}
+
+/// Chapters for an episode from the cache, or from `get_chapters` on a miss.
+/// Chapters guessed from the show notes are looked up again once there is an
+/// audio file to read embedded ones from.
+pub fn load(episode: &Episode, audio_path: Option<&Path>) -> Result<ChapterList, String> {
+    load_from(&cache_dir()?, episode, audio_path)
+}
+
+fn cache_dir() -> Result<PathBuf, String> {
+    persistence::get_data_file(CHAPTERS_DIR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cached_chapters_are_reused() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut episode = Episode {
+            title: "Cached".to_string(),
+            description: "00:00 Intro\n05:00 Interview".to_string(),
+            audio_url: "https://example.com/cached.mp3".to_string(),
+            ..Default::default()
+        };
+
+        let first = load_from(dir.path(), &episode, None).unwrap();
+        assert_eq!(first.source, Some(ChapterSource::Description));
+
+        // A cache hit doesn't look at the episode again
+        episode.description.clear();
+        let cached = load_from(dir.path(), &episode, None).unwrap();
+        assert_eq!(cached.chapters.len(), 2);
+
+        episode.audio_url = "https://example.com/other.mp3".to_string();
+        assert!(load_from(dir.path(), &episode, None).is_err());
+    }
+}
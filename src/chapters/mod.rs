@@ -0,0 +1,352 @@
+pub mod cache;
+pub mod mp4;
+pub mod notes;
+pub mod ogg;
+pub mod podlove;
+pub mod skip;
+
+use crate::app::Episode;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::Path;
+
+/// Going back within this many seconds of a chapter's start goes to the previous chapter
+const RESTART_CHAPTER_SECS: f64 = 3.0;
+
+/// Slack for positions that land just before a chapter start after seeking to it
+const SEEK_TOLERANCE_SECS: f64 = 1.0;
+
+/// Where a chapter list came from, in the order `get_chapters` tries them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChapterSource {
+    /// Podcasting 2.0 `podcast:chapters` JSON file
+    Json,
+    /// Podlove Simple Chapters inline in the feed
+    Podlove,
+    /// Chapters embedded in the audio file (ID3, MP4, Vorbis comments)
+    Embedded,
+    /// Timestamps found in the show notes
+    Description,
+}
+
+impl ChapterSource {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Json => "chapters file",
+            Self::Podlove => "feed",
+            Self::Embedded => "audio file",
+            Self::Description => "show notes",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChapterList {
+    pub version: String,
+    pub chapters: Vec<Chapter>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<ChapterSource>,
+}
+
+impl ChapterList {
+    pub fn new(chapters: Vec<Chapter>) -> Self {
+        Self {
+            version: "1.0.0".to_string(),
+            chapters,
+            source: None,
+        }
+    }
+
+    /// Chapters meant for navigation. Ones marked `toc: false` only change the
+    /// artwork or link while they play and aren't listed or skipped to.
+    pub fn toc(&self) -> Vec<&Chapter> {
+        self.chapters.iter().filter(|c| c.toc != Some(false)).collect()
+    }
+
+    /// The chapter playing at the given position, with its index
+    pub fn chapter_at(&self, position_secs: f64) -> Option<(usize, &Chapter)> {
+        active_chapter(self.chapters.iter(), position_secs)
+    }
+
+    /// The table of contents chapter playing at the given position, indexed into `toc()`
+    pub fn toc_chapter_at(&self, position_secs: f64) -> Option<(usize, &Chapter)> {
+        active_chapter(self.toc().into_iter(), position_secs)
+    }
+
+    /// The table of contents chapter after the one playing at the given position
+    pub fn next_chapter(&self, position_secs: f64) -> Option<(usize, &Chapter)> {
+        self.toc()
+            .into_iter()
+            .enumerate()
+            .find(|(_, c)| c.start_time > position_secs + SEEK_TOLERANCE_SECS)
+    }
+
+    /// The start of the playing chapter, or the chapter before it when the
+    /// playing one has only just started, like a CD player's back button
+    pub fn previous_chapter(&self, position_secs: f64) -> Option<(usize, &Chapter)> {
+        let toc = self.toc();
+        let index = toc
+            .iter()
+            .rposition(|c| c.start_time <= position_secs + SEEK_TOLERANCE_SECS)?;
+        if position_secs - toc[index].start_time > RESTART_CHAPTER_SECS || index == 0 {
+            Some((index, toc[index]))
+        } else {
+            Some((index - 1, toc[index - 1]))
+        }
+    }
+
+    /// Where playback continues when skipping the table of contents chapter at
+    /// `index` along with any chapters right after it that should also be
+    /// skipped: the next chapter to play, or the end of the last skipped one
+    pub fn skip_end(&self, index: usize, skip: impl Fn(&Chapter) -> bool) -> Option<f64> {
+        let toc = self.toc();
+        let mut last = *toc.get(index)?;
+        for &chapter in &toc[index + 1..] {
+            if !skip(chapter) {
+                return Some(chapter.start_time);
+            }
+            last = chapter;
+        }
+        last.end_time
+    }
+}
+
+/// The most recently started chapter that hasn't reached its `endTime`
+fn active_chapter<'a>(chapters: impl Iterator<Item = &'a Chapter>, position_secs: f64) -> Option<(usize, &'a Chapter)> {
+    chapters
+        .enumerate()
+        .filter(|(_, c)| c.start_time <= position_secs && c.end_time.is_none_or(|end| position_secs < end))
+        .last()
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Chapter {
+    #[serde(rename = "startTime")]
+    pub start_time: f64,
+    pub title: String,
+    #[serde(rename = "img")]
+    pub image: Option<String>,
+    pub url: Option<String>,
+    /// Where the chapter ends, if not where the next one starts
+    #[serde(rename = "endTime", default, skip_serializing_if = "Option::is_none")]
+    pub end_time: Option<f64>,
+    /// `false` for chapters that are left out of the table of contents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub toc: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<ChapterLocation>,
+}
+
+/// A place the chapter is about
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChapterLocation {
+    pub name: String,
+    /// `geo:` URI with the coordinates
+    #[serde(default)]
+    pub geo: Option<String>,
+    /// OpenStreetMap object, e.g. "R113314"
+    #[serde(default)]
+    pub osm: Option<String>,
+}
+
+/// Fetch and parse chapter JSON from URL (Podcasting 2.0 format)
+pub fn fetch_chapters(url: &str) -> Result<ChapterList, String> {
+    let response = reqwest::blocking::get(url)
+        .map_err(|e| format!("Failed to fetch chapters: {}", e))?;
+
+    let chapters: ChapterList = response
+        .json()
+        .map_err(|e| format!("Failed to parse chapters JSON: {}", e))?;
+
+    Ok(chapters)
+}
+
+/// Extract chapters from ID3v2 CHAP frames in MP3 file
+pub fn extract_chapters_from_mp3<P: AsRef<Path>>(path: P) -> Result<ChapterList, String> {
+    let tag = id3::Tag::read_from_path(path)
+        .map_err(|e| format!("Failed to read ID3 tags: {}", e))?;
+
+    let chapters: Vec<Chapter> = tag.chapters()
+        .map(|c| Chapter {
+            start_time: c.start_time as f64 / 1000.0,
+            title: c.frames.iter()
+                .find_map(|f| {
+                    if f.id() == "TIT2" {
+                        if let id3::Content::Text(text) = f.content() {
+                            Some(text.clone())
+                        } else {
+                            None
+                        }
+                    } else {
+                        None
+                    }
+                })
+                .unwrap_or_else(|| format!("Chapter at {:.0}s", c.start_time as f64 / 1000.0)),
+            image: None,
+            url: None,
+            ..Default::default()
+        })
+        .collect();
+
+    if chapters.is_empty() {
+        return Err("No chapters found in MP3".to_string());
+    }
+
+    Ok(ChapterList::new(chapters))
+}
+
+/// Parse "HH:MM:SS.mmm" (hours and milliseconds optional) or plain seconds
+pub fn parse_timestamp(text: &str) -> Option<f64> {
+    let mut secs = 0.0;
+    for part in text.trim().split(':') {
+        secs = secs * 60.0 + part.parse::<f64>().ok()?;
+    }
+    Some(secs)
+}
+
+/// Audio container formats that can carry chapters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioFormat {
+    Mp3,
+    Mp4,
+    Ogg,
+}
+
+/// Identify the container from the first bytes of the file, since episode URLs
+/// and downloaded temp files often have no meaningful extension
+pub fn sniff_format(header: &[u8]) -> Option<AudioFormat> {
+    if header.starts_with(b"ID3") || matches!(header, [0xFF, second, ..] if second & 0xE0 == 0xE0) {
+        Some(AudioFormat::Mp3)
+    } else if mp4::is_mp4(header) {
+        Some(AudioFormat::Mp4)
+    } else if ogg::is_ogg(header) {
+        Some(AudioFormat::Ogg)
+    } else {
+        None
+    }
+}
+
+/// Extract embedded chapters from an MP3, MP4/M4A or Ogg/Opus file
+pub fn extract_chapters_from_file(path: &Path) -> Result<ChapterList, String> {
+    let mut header = [0u8; 12];
+    let read = std::fs::File::open(path)
+        .and_then(|mut f| f.read(&mut header))
+        .map_err(|e| format!("Failed to read audio file: {}", e))?;
+
+    match sniff_format(&header[..read]) {
+        Some(AudioFormat::Mp3) => extract_chapters_from_mp3(path),
+        Some(format) => {
+            let data = std::fs::read(path)
+                .map_err(|e| format!("Failed to read audio file: {}", e))?;
+            if format == AudioFormat::Mp4 {
+                mp4::extract_chapters(&data)
+            } else {
+                ogg::extract_chapters(&data)
+            }
+        }
+        None => Err("Unknown audio format".to_string()),
+    }
+}
+
+/// Chapters for an episode from the first source that has them: the Podcasting 2.0
+/// JSON file, Podlove chapters from the feed, the audio file (`audio_path`, or the
+/// download), then timestamps in the show notes. The list records its source.
+pub fn get_chapters(episode: &Episode, audio_path: Option<&Path>) -> Result<ChapterList, String> {
+    let (mut chapters, source) = find_chapters(episode, audio_path).ok_or("No chapters available")?;
+    log::info!(
+        "Loaded {} chapters for '{}' from the {}",
+        chapters.chapters.len(),
+        episode.title,
+        source.label()
+    );
+    chapters.source = Some(source);
+    Ok(chapters)
+}
+
+fn find_chapters(episode: &Episode, audio_path: Option<&Path>) -> Option<(ChapterList, ChapterSource)> {
+    if let Some(url) = &episode.chapters_url {
+        match fetch_chapters(url) {
+            Ok(chapters) if !chapters.chapters.is_empty() => return Some((chapters, ChapterSource::Json)),
+            Ok(_) => {}
+            Err(e) => log::debug!("Chapters file failed: {}", e),
+        }
+    }
+
+    if let Some(chapters) = episode.feed_chapters.clone().filter(|c| !c.chapters.is_empty()) {
+        return Some((chapters, ChapterSource::Podlove));
+    }
+
+    let path = audio_path.or(episode.download_path.as_deref().map(Path::new));
+    if let Some(path) = path {
+        match extract_chapters_from_file(path) {
+            Ok(chapters) => return Some((chapters, ChapterSource::Embedded)),
+            Err(e) => log::debug!("No embedded chapters in {}: {}", path.display(), e),
+        }
+    }
+
+    notes::from_description(&episode.description).map(|chapters| (chapters, ChapterSource::Description))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chapter_list(starts: &[f64]) -> ChapterList {
+        ChapterList::new(
+            starts
+                .iter()
+                .map(|&start_time| Chapter {
+                    start_time,
+                    title: format!("At {}", start_time),
+                    image: None,
+                    url: None,
+                    ..Default::default()
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_sniff_format() {
+        assert_eq!(sniff_format(b"ID3\x04\0"), Some(AudioFormat::Mp3));
+        assert_eq!(sniff_format(&[0xFF, 0xFB, 0x90]), Some(AudioFormat::Mp3));
+        assert_eq!(sniff_format(b"\0\0\0\x20ftypM4A "), Some(AudioFormat::Mp4));
+        assert_eq!(sniff_format(b"OggS\0\x02"), Some(AudioFormat::Ogg));
+        assert_eq!(sniff_format(b"<html>"), None);
+    }
+
+    #[test]
+    fn test_chapter_navigation() {
+        let list = chapter_list(&[0.0, 60.0, 300.0]);
+
+        assert_eq!(list.chapter_at(90.0).map(|(i, _)| i), Some(1));
+        assert_eq!(list.next_chapter(90.0).map(|(i, _)| i), Some(2));
+        // Just short of a chapter start after seeking to it
+        assert_eq!(list.next_chapter(59.8).map(|(i, _)| i), Some(2));
+        assert!(list.next_chapter(300.0).is_none());
+
+        assert_eq!(list.previous_chapter(90.0).map(|(i, _)| i), Some(1));
+        assert_eq!(list.previous_chapter(61.0).map(|(i, _)| i), Some(0));
+        assert_eq!(list.previous_chapter(1.0).map(|(i, _)| i), Some(0));
+    }
+
+    #[test]
+    fn test_toc_and_end_time() {
+        let json = r#"{"version":"1.2.0","chapters":[
+            {"startTime":0,"title":"Intro"},
+            {"startTime":30,"title":"Sponsor art","toc":false,"endTime":40},
+            {"startTime":60,"title":"Interview","location":{"name":"Berlin","geo":"geo:52.5,13.4"}}
+        ]}"#;
+        let list: ChapterList = serde_json::from_str(json).unwrap();
+
+        let toc: Vec<&str> = list.toc().iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(toc, vec!["Intro", "Interview"]);
+        assert_eq!(list.chapter_at(35.0).map(|(i, _)| i), Some(1));
+        // Past its endTime the hidden chapter no longer applies
+        assert_eq!(list.chapter_at(45.0).map(|(i, _)| i), Some(0));
+        assert_eq!(list.toc_chapter_at(35.0).map(|(i, _)| i), Some(0));
+        assert_eq!(list.next_chapter(10.0).map(|(i, c)| (i, c.title.as_str())), Some((1, "Interview")));
+        assert_eq!(list.chapters[2].location.as_ref().map(|l| l.name.as_str()), Some("Berlin"));
+    }
+}
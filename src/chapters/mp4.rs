@@ -0,0 +1,335 @@
+//! Chapters embedded in MP4/M4A files: Nero `chpl` atoms and QuickTime chapter text tracks
+
+use super::{Chapter, ChapterList};
+
+/// `chpl` start times are in units of 100 nanoseconds
+const CHPL_TIMESCALE: f64 = 10_000_000.0;
+
+/// Upper bound on chapter samples, so a corrupt table can't make us loop for ages
+const MAX_CHAPTERS: usize = 10_000;
+
+/// An atom's type and payload (the bytes after its header)
+struct Atom<'a> {
+    kind: [u8; 4],
+    data: &'a [u8],
+}
+
+/// The atoms directly inside `data`. Stops at the first truncated or malformed atom,
+/// which happens when the file is still being downloaded.
+fn atoms(data: &[u8]) -> Vec<Atom<'_>> {
+    let mut atoms = Vec::new();
+    let mut pos = 0;
+
+    while pos + 8 <= data.len() {
+        let size = read_u32(data, pos).unwrap_or(0) as u64;
+        let kind = [data[pos + 4], data[pos + 5], data[pos + 6], data[pos + 7]];
+        let (header, size) = match size {
+            0 => (8, (data.len() - pos) as u64), // Extends to the end of the file
+            1 => match read_u64(data, pos + 8) {
+                Some(large) => (16, large),
+                None => break,
+            },
+            _ => (8, size),
+        };
+        if size < header as u64 || pos as u64 + size > data.len() as u64 {
+            break;
+        }
+
+        let end = pos + size as usize;
+        atoms.push(Atom { kind, data: &data[pos + header..end] });
+        pos = end;
+    }
+
+    atoms
+}
+
+/// The first atom found by following `path` down from `data`
+fn find<'a>(data: &'a [u8], path: &[&[u8; 4]]) -> Option<&'a [u8]> {
+    let (first, rest) = path.split_first()?;
+    let atom = atoms(data).into_iter().find(|a| &a.kind == *first)?;
+    if rest.is_empty() {
+        Some(atom.data)
+    } else {
+        find(atom.data, rest)
+    }
+}
+
+fn read_u16(data: &[u8], pos: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(data.get(pos..pos + 2)?.try_into().ok()?))
+}
+
+fn read_u32(data: &[u8], pos: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(pos..pos + 4)?.try_into().ok()?))
+}
+
+fn read_u64(data: &[u8], pos: usize) -> Option<u64> {
+    Some(u64::from_be_bytes(data.get(pos..pos + 8)?.try_into().ok()?))
+}
+
+/// Whether the data starts like an MP4/M4A/QuickTime file
+pub fn is_mp4(data: &[u8]) -> bool {
+    matches!(data.get(4..8), Some(b"ftyp") | Some(b"moov") | Some(b"mdat") | Some(b"free") | Some(b"wide"))
+}
+
+/// Chapters from a Nero `chpl` atom, or failing that a QuickTime chapter track
+pub fn extract_chapters(data: &[u8]) -> Result<ChapterList, String> {
+    let moov = find(data, &[b"moov"]).ok_or("No moov atom (file may be incomplete)")?;
+
+    let chapters = find(moov, &[b"udta", b"chpl"])
+        .map(parse_chpl)
+        .filter(|chapters| !chapters.is_empty())
+        .or_else(|| chapter_track(data, moov))
+        .ok_or("No chapters found in MP4")?;
+
+    Ok(ChapterList::new(chapters))
+}
+
+/// Nero chapters: version, flags, (v1 only) 4 reserved bytes, a count, then
+/// for each chapter a 64-bit start time and a length-prefixed UTF-8 title
+fn parse_chpl(data: &[u8]) -> Vec<Chapter> {
+    let version = data.first().copied().unwrap_or(0);
+    let mut pos = if version > 0 { 8 } else { 4 };
+    let count = data.get(pos).copied().unwrap_or(0);
+    pos += 1;
+
+    let mut chapters = Vec::new();
+    for _ in 0..count {
+        let Some(start) = read_u64(data, pos) else { break };
+        let Some(&len) = data.get(pos + 8) else { break };
+        let Some(title) = data.get(pos + 9..pos + 9 + len as usize) else { break };
+        pos += 9 + len as usize;
+
+        chapters.push(Chapter {
+            start_time: start as f64 / CHPL_TIMESCALE,
+            title: String::from_utf8_lossy(title).trim().to_string(),
+            image: None,
+            url: None,
+            ..Default::default()
+        });
+    }
+    chapters
+}
+
+/// Track ID from a `tkhd` atom
+fn track_id(trak: &[u8]) -> Option<u32> {
+    let tkhd = find(trak, &[b"tkhd"])?;
+    // Version 1 has 64-bit creation and modification times
+    let pos = if tkhd.first() == Some(&1) { 20 } else { 12 };
+    read_u32(tkhd, pos)
+}
+
+/// The QuickTime chapter track: the track another track's `tref/chap` points at,
+/// or else the first text track
+fn chapter_track(file: &[u8], moov: &[u8]) -> Option<Vec<Chapter>> {
+    let tracks: Vec<&[u8]> = atoms(moov)
+        .into_iter()
+        .filter(|a| &a.kind == b"trak")
+        .map(|a| a.data)
+        .collect();
+
+    let referenced: Vec<u32> = tracks
+        .iter()
+        .filter_map(|trak| find(trak, &[b"tref", b"chap"]))
+        .flat_map(|chap| (0..chap.len() / 4).filter_map(move |i| read_u32(chap, i * 4)))
+        .collect();
+
+    let is_text = |trak: &&&[u8]| {
+        find(trak, &[b"mdia", b"hdlr"])
+            .and_then(|hdlr| hdlr.get(8..12))
+            .map(|handler| handler == b"text" || handler == b"sbtl")
+            .unwrap_or(false)
+    };
+
+    let trak = tracks
+        .iter()
+        .find(|trak| track_id(trak).map(|id| referenced.contains(&id)).unwrap_or(false))
+        .or_else(|| tracks.iter().find(is_text))?;
+
+    let chapters = parse_text_track(file, trak)?;
+    (!chapters.is_empty()).then_some(chapters)
+}
+
+/// Read the samples of a text track: each is a 16-bit length and the title,
+/// timed by the track's `stts` table in units of its `mdhd` timescale
+fn parse_text_track(file: &[u8], trak: &[u8]) -> Option<Vec<Chapter>> {
+    let mdia = find(trak, &[b"mdia"])?;
+    let mdhd = find(mdia, &[b"mdhd"])?;
+    let timescale = read_u32(mdhd, if mdhd.first() == Some(&1) { 20 } else { 12 })?;
+    if timescale == 0 {
+        return None;
+    }
+
+    let stbl = find(mdia, &[b"minf", b"stbl"])?;
+    let starts = sample_starts(find(stbl, &[b"stts"])?);
+    let offsets = sample_offsets(stbl)?;
+
+    let chapters = starts
+        .iter()
+        .zip(offsets)
+        .filter_map(|(&start, offset)| {
+            let len = read_u16(file, offset)? as usize;
+            let text = file.get(offset + 2..offset + 2 + len)?;
+            Some(Chapter {
+                start_time: start as f64 / timescale as f64,
+                title: decode_text(text),
+                image: None,
+                url: None,
+                ..Default::default()
+            })
+        })
+        .collect();
+    Some(chapters)
+}
+
+/// Start time of every sample from the time-to-sample table
+fn sample_starts(stts: &[u8]) -> Vec<u64> {
+    let count = read_u32(stts, 4).unwrap_or(0) as usize;
+    let mut starts = Vec::new();
+    let mut time = 0u64;
+    for i in 0..count {
+        let (Some(samples), Some(delta)) = (read_u32(stts, 8 + i * 8), read_u32(stts, 12 + i * 8)) else {
+            break;
+        };
+        for _ in 0..samples {
+            if starts.len() >= MAX_CHAPTERS {
+                return starts;
+            }
+            starts.push(time);
+            time += delta as u64;
+        }
+    }
+    starts
+}
+
+/// File offset of every sample, from the chunk offsets, sample-to-chunk and sample size tables
+fn sample_offsets(stbl: &[u8]) -> Option<Vec<usize>> {
+    let chunk_offsets: Vec<u64> = if let Some(stco) = find(stbl, &[b"stco"]) {
+        let count = read_u32(stco, 4)? as usize;
+        (0..count).filter_map(|i| read_u32(stco, 8 + i * 4).map(u64::from)).collect()
+    } else {
+        let co64 = find(stbl, &[b"co64"])?;
+        let count = read_u32(co64, 4)? as usize;
+        (0..count).filter_map(|i| read_u64(co64, 8 + i * 8)).collect()
+    };
+
+    // (first chunk, samples per chunk), first chunk numbered from 1
+    let stsc = find(stbl, &[b"stsc"])?;
+    let runs: Vec<(u32, u32)> = (0..read_u32(stsc, 4)? as usize)
+        .filter_map(|i| Some((read_u32(stsc, 8 + i * 12)?, read_u32(stsc, 12 + i * 12)?)))
+        .collect();
+
+    let stsz = find(stbl, &[b"stsz"])?;
+    let fixed_size = read_u32(stsz, 4)?;
+    let sample_count = read_u32(stsz, 8)? as usize;
+    let size_of = |sample: usize| {
+        if fixed_size > 0 {
+            Some(fixed_size)
+        } else {
+            read_u32(stsz, 12 + sample * 4)
+        }
+    };
+
+    let mut offsets = Vec::new();
+    for (chunk, &chunk_offset) in chunk_offsets.iter().enumerate() {
+        let samples = runs
+            .iter()
+            .rev()
+            .find(|(first, _)| *first as usize <= chunk + 1)
+            .map(|(_, samples)| *samples)
+            .unwrap_or(0);
+
+        let mut offset = chunk_offset as usize;
+        for _ in 0..samples {
+            if offsets.len() >= sample_count.min(MAX_CHAPTERS) {
+                return Some(offsets);
+            }
+            offsets.push(offset);
+            offset += size_of(offsets.len() - 1)? as usize;
+        }
+    }
+    Some(offsets)
+}
+
+/// Text samples are UTF-8, or UTF-16 when they start with a byte order mark
+fn decode_text(text: &[u8]) -> String {
+    let title = match text {
+        [0xFE, 0xFF, rest @ ..] => {
+            let units: Vec<u16> = rest.chunks_exact(2).map(|c| u16::from_be_bytes([c[0], c[1]])).collect();
+            String::from_utf16_lossy(&units)
+        }
+        [0xFF, 0xFE, rest @ ..] => {
+            let units: Vec<u16> = rest.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+            String::from_utf16_lossy(&units)
+        }
+        _ => String::from_utf8_lossy(text).into_owned(),
+    };
+    title.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn atom(kind: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut out = ((payload.len() + 8) as u32).to_be_bytes().to_vec();
+        out.extend_from_slice(kind);
+        out.extend_from_slice(payload);
+        out
+    }
+
+    fn table(entries: &[u32]) -> Vec<u8> {
+        entries.iter().flat_map(|v| v.to_be_bytes()).collect()
+    }
+
+    #[test]
+    fn test_chpl_chapters() {
+        let mut chpl = vec![1, 0, 0, 0, 0, 0, 0, 0, 2];
+        for (start, title) in [(0u64, "Intro"), (905_000_000, "News")] {
+            chpl.extend_from_slice(&start.to_be_bytes());
+            chpl.push(title.len() as u8);
+            chpl.extend_from_slice(title.as_bytes());
+        }
+        let mut file = atom(b"ftyp", b"M4A \0\0\0\0");
+        file.extend(atom(b"moov", &atom(b"udta", &atom(b"chpl", &chpl))));
+
+        assert!(is_mp4(&file));
+        let list = extract_chapters(&file).unwrap();
+        assert_eq!(list.chapters.len(), 2);
+        assert_eq!(list.chapters[1].title, "News");
+        assert_eq!(list.chapters[1].start_time, 90.5);
+    }
+
+    #[test]
+    fn test_quicktime_chapter_track() {
+        // Two text samples stored in the mdat before the moov
+        let samples = [b"\x00\x05Intro".to_vec(), b"\x00\x09Interview".to_vec()];
+        let mut file = atom(b"ftyp", b"M4A \0\0\0\0");
+        let mdat_offset = file.len() as u32 + 8;
+        file.extend(atom(b"mdat", &samples.concat()));
+
+        let stbl = [
+            atom(b"stts", &table(&[0, 2, 1, 1000, 1, 500])),
+            atom(b"stsc", &table(&[0, 1, 1, 2, 1])),
+            atom(b"stsz", &table(&[0, 0, 2, 7, 11])),
+            atom(b"stco", &table(&[0, 1, mdat_offset])),
+        ]
+        .concat();
+        let mdia = [
+            atom(b"mdhd", &table(&[0, 0, 0, 100, 1500, 0])),
+            atom(b"hdlr", &[&table(&[0, 0])[..], b"text", &[0; 12]].concat()),
+            atom(b"minf", &atom(b"stbl", &stbl)),
+        ]
+        .concat();
+        let text_track = [atom(b"tkhd", &table(&[0, 0, 0, 2])), atom(b"mdia", &mdia)].concat();
+        let audio_track = [
+            atom(b"tkhd", &table(&[0, 0, 0, 1])),
+            atom(b"tref", &atom(b"chap", &table(&[2]))),
+        ]
+        .concat();
+        file.extend(atom(b"moov", &[atom(b"trak", &audio_track), atom(b"trak", &text_track)].concat()));
+
+        let list = extract_chapters(&file).unwrap();
+        let chapters: Vec<(f64, &str)> = list.chapters.iter().map(|c| (c.start_time, c.title.as_str())).collect();
+        assert_eq!(chapters, vec![(0.0, "Intro"), (10.0, "Interview")]);
+    }
+}
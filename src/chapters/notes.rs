@@ -0,0 +1,74 @@
+//! Chapters guessed from timestamps in the show notes, e.g. "12:34 Topic" or "Topic (12:34)"
+
+use super::{parse_timestamp, Chapter, ChapterList};
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Fewer timestamps than this are more likely mentions than a table of contents
+const MIN_CHAPTERS: usize = 2;
+
+/// "12:34 Topic", "[1:02:03] - Topic", "* (05:00) Topic"
+fn leading() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"^\s*(?:[-*•·]\s*)?[\[(]?((?:\d{1,2}:)?\d{1,2}:\d{2})[\])]?\s*(?:[-–—:|]\s*)?(\S.*?)\s*$").unwrap()
+    })
+}
+
+/// "Topic (12:34)", "Topic - [1:02:03]"
+fn trailing() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"^\s*(?:[-*•·]\s*)?(\S.*?)\s*(?:[-–—]\s*)?[\[(]((?:\d{1,2}:)?\d{1,2}:\d{2})[\])]\s*$").unwrap()
+    })
+}
+
+/// Chapters from lines that start or end with a timestamp. Only used when there
+/// are at least two and they are in increasing order, like a real chapter list.
+pub fn from_description(description: &str) -> Option<ChapterList> {
+    let chapters: Vec<Chapter> = description
+        .lines()
+        .filter_map(|line| {
+            let (time, title) = if let Some(caps) = leading().captures(line) {
+                (caps.get(1)?.as_str(), caps.get(2)?.as_str())
+            } else {
+                let caps = trailing().captures(line)?;
+                (caps.get(2)?.as_str(), caps.get(1)?.as_str())
+            };
+            Some(Chapter {
+                start_time: parse_timestamp(time)?,
+                title: title.to_string(),
+                image: None,
+                url: None,
+                ..Default::default()
+            })
+        })
+        .collect();
+
+    let increasing = chapters.windows(2).all(|w| w[0].start_time < w[1].start_time);
+    if chapters.len() < MIN_CHAPTERS || !increasing {
+        return None;
+    }
+    Some(ChapterList::new(chapters))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chapters_from_show_notes() {
+        let notes = "This week we talk about terminals.\n\n\
+            00:00 Intro\n\
+            * [05:30] - Listener mail\n\
+            Interview with Jane (1:02:03)\n\
+            Recorded at 10:00 in the morning.";
+        let list = from_description(notes).unwrap();
+        let chapters: Vec<(f64, &str)> = list.chapters.iter().map(|c| (c.start_time, c.title.as_str())).collect();
+        assert_eq!(chapters, vec![(0.0, "Intro"), (330.0, "Listener mail"), (3723.0, "Interview with Jane")]);
+
+        // A single mention, or times out of order, isn't a chapter list
+        assert!(from_description("Live at 20:00 tonight").is_none());
+        assert!(from_description("10:00 Late\n05:00 Early").is_none());
+    }
+}
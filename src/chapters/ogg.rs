@@ -0,0 +1,166 @@
+//! Chapters in the Vorbis comments of Ogg Vorbis and Opus files
+//! (`CHAPTER001=00:00:00.000` with `CHAPTER001NAME=Intro`)
+
+use super::{parse_timestamp, Chapter, ChapterList};
+
+/// The comment header is one of the first packets, so only look this far
+const MAX_HEADER_PACKETS: usize = 4;
+
+/// Whether the data starts with an Ogg page
+pub fn is_ogg(data: &[u8]) -> bool {
+    data.starts_with(b"OggS")
+}
+
+/// Packets of the first logical stream, joined across pages
+fn packets(data: &[u8], limit: usize) -> Vec<Vec<u8>> {
+    let mut packets = Vec::new();
+    let mut current = Vec::new();
+    let mut pos = 0;
+    let mut serial = None;
+
+    while pos + 27 <= data.len() && packets.len() < limit {
+        if &data[pos..pos + 4] != b"OggS" {
+            break;
+        }
+        let page_serial = &data[pos + 14..pos + 18];
+        let segments = data[pos + 26] as usize;
+        let Some(table) = data.get(pos + 27..pos + 27 + segments) else { break };
+        let mut body = pos + 27 + segments;
+
+        let ours = *serial.get_or_insert(page_serial) == page_serial;
+        for &len in table {
+            let Some(segment) = data.get(body..body + len as usize) else { return packets };
+            body += len as usize;
+            if !ours {
+                continue;
+            }
+            current.extend_from_slice(segment);
+            // A segment shorter than 255 bytes ends the packet
+            if len < 255 {
+                packets.push(std::mem::take(&mut current));
+            }
+        }
+        pos = body;
+    }
+
+    packets
+}
+
+/// `KEY=value` comments from a Vorbis or Opus comment header packet
+fn comments(packet: &[u8]) -> Option<Vec<(String, String)>> {
+    let mut pos = if packet.starts_with(b"\x03vorbis") {
+        7
+    } else if packet.starts_with(b"OpusTags") {
+        8
+    } else {
+        return None;
+    };
+
+    let read_u32 = |pos: usize| -> Option<usize> {
+        Some(u32::from_le_bytes(packet.get(pos..pos + 4)?.try_into().ok()?) as usize)
+    };
+
+    let vendor_len = read_u32(pos)?;
+    pos += 4 + vendor_len;
+    let count = read_u32(pos)?;
+    pos += 4;
+
+    let mut comments = Vec::new();
+    for _ in 0..count {
+        let Some(len) = read_u32(pos) else { break };
+        let Some(comment) = packet.get(pos + 4..pos + 4 + len) else { break };
+        pos += 4 + len;
+
+        let comment = String::from_utf8_lossy(comment);
+        if let Some((key, value)) = comment.split_once('=') {
+            comments.push((key.to_ascii_uppercase(), value.to_string()));
+        }
+    }
+    Some(comments)
+}
+
+/// Chapters from the `CHAPTERxxx` comments, in chapter number order
+pub fn extract_chapters(data: &[u8]) -> Result<ChapterList, String> {
+    let comments = packets(data, MAX_HEADER_PACKETS)
+        .iter()
+        .find_map(|packet| comments(packet))
+        .ok_or("No Vorbis comments found")?;
+
+    let value = |key: String| comments.iter().find(|(k, _)| *k == key).map(|(_, v)| v.clone());
+
+    let mut numbered: Vec<(u32, Chapter)> = comments
+        .iter()
+        .filter_map(|(key, time)| {
+            let number = key.strip_prefix("CHAPTER")?;
+            if number.is_empty() || !number.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            Some((
+                number.parse().ok()?,
+                Chapter {
+                    start_time: parse_timestamp(time)?,
+                    title: value(format!("CHAPTER{}NAME", number))
+                        .unwrap_or_else(|| format!("Chapter {}", number.trim_start_matches('0'))),
+                    image: None,
+                    url: value(format!("CHAPTER{}URL", number)),
+                    ..Default::default()
+                },
+            ))
+        })
+        .collect();
+
+    if numbered.is_empty() {
+        return Err("No chapters found in Ogg comments".to_string());
+    }
+    numbered.sort_by_key(|(number, _)| *number);
+
+    Ok(ChapterList::new(numbered.into_iter().map(|(_, chapter)| chapter).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A single Ogg page holding one packet
+    fn page(packet: &[u8]) -> Vec<u8> {
+        let mut out = b"OggS\0\0".to_vec();
+        out.extend_from_slice(&[0; 8]); // Granule position
+        out.extend_from_slice(&[1, 0, 0, 0]); // Serial number
+        out.extend_from_slice(&[0; 8]); // Sequence number and CRC
+        let mut table = vec![255u8; packet.len() / 255];
+        table.push((packet.len() % 255) as u8);
+        out.push(table.len() as u8);
+        out.extend_from_slice(&table);
+        out.extend_from_slice(packet);
+        out
+    }
+
+    #[test]
+    fn test_opus_chapter_comments() {
+        let mut tags = b"OpusTags".to_vec();
+        tags.extend_from_slice(&4u32.to_le_bytes());
+        tags.extend_from_slice(b"test");
+        let comments = [
+            "CHAPTER002=00:12:30.500",
+            "chapter002name=Interview",
+            "CHAPTER001=00:00:00.000",
+            "CHAPTER001NAME=Intro",
+            "TITLE=Episode",
+        ];
+        tags.extend_from_slice(&(comments.len() as u32).to_le_bytes());
+        for comment in comments {
+            tags.extend_from_slice(&(comment.len() as u32).to_le_bytes());
+            tags.extend_from_slice(comment.as_bytes());
+        }
+        // Pad past one segment so the packet spans the lacing table
+        tags.extend_from_slice(&[0; 300]);
+
+        let mut file = page(b"OpusHead\x01\x02");
+        file.extend(page(&tags));
+
+        assert!(is_ogg(&file));
+        let list = extract_chapters(&file).unwrap();
+        let chapters: Vec<(f64, &str)> = list.chapters.iter().map(|c| (c.start_time, c.title.as_str())).collect();
+        assert_eq!(chapters, vec![(0.0, "Intro"), (750.5, "Interview")]);
+    }
+}
@@ -0,0 +1,63 @@
+//! Podlove Simple Chapters embedded in RSS items
+//! (`<psc:chapters><psc:chapter start="00:01:30" title="News"/></psc:chapters>`)
+
+use super::{parse_timestamp, Chapter, ChapterList};
+use rss::extension::ExtensionMap;
+
+pub const NAMESPACE: &str = "http://podlove.org/simple-chapters";
+
+/// Chapters from an item's extensions, under whichever prefix the feed bound the namespace to
+pub fn from_extensions(extensions: &ExtensionMap, prefix: &str) -> Option<ChapterList> {
+    let chapters: Vec<Chapter> = extensions
+        .get(prefix)?
+        .get("chapters")?
+        .iter()
+        .flat_map(|list| list.children().get("chapter").into_iter().flatten())
+        .filter_map(|chapter| {
+            let attrs = chapter.attrs();
+            Some(Chapter {
+                start_time: parse_timestamp(attrs.get("start")?)?,
+                title: attrs.get("title").map(|t| t.trim().to_string()).unwrap_or_default(),
+                image: attrs.get("image").cloned(),
+                url: attrs.get("href").cloned(),
+                ..Default::default()
+            })
+        })
+        .collect();
+
+    if chapters.is_empty() {
+        return None;
+    }
+    Some(ChapterList::new(chapters))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rss::Channel;
+
+    #[test]
+    fn test_podlove_chapters_with_custom_prefix() {
+        let rss = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <rss version="2.0" xmlns:sc="http://podlove.org/simple-chapters">
+              <channel>
+                <title>Test</title>
+                <item>
+                  <title>Episode</title>
+                  <sc:chapters version="1.2">
+                    <sc:chapter start="00:00:00.000" title="Welcome"/>
+                    <sc:chapter start="01:30" title="News" href="https://example.com/news"/>
+                    <sc:chapter start="1:02:03.5" title="Outro"/>
+                  </sc:chapters>
+                </item>
+              </channel>
+            </rss>"#;
+        let channel = Channel::read_from(rss.as_bytes()).unwrap();
+        assert_eq!(channel.namespaces().get("sc").map(String::as_str), Some(NAMESPACE));
+
+        let list = from_extensions(channel.items()[0].extensions(), "sc").unwrap();
+        let chapters: Vec<(f64, &str)> = list.chapters.iter().map(|c| (c.start_time, c.title.as_str())).collect();
+        assert_eq!(chapters, vec![(0.0, "Welcome"), (90.0, "News"), (3723.5, "Outro")]);
+        assert_eq!(list.chapters[1].url.as_deref(), Some("https://example.com/news"));
+    }
+}
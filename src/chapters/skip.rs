@@ -0,0 +1,182 @@
+//! Skipping chapters whose titles match configured patterns, such as ad breaks
+
+use super::{Chapter, ChapterList, SEEK_TOLERANCE_SECS};
+use crate::config::ChaptersConfig;
+use regex::Regex;
+use std::collections::HashMap;
+
+/// Chapter title patterns to skip, for all podcasts and per feed URL
+#[derive(Debug, Default)]
+pub struct SkipRules {
+    global: Vec<Regex>,
+    podcasts: HashMap<String, Vec<Regex>>,
+}
+
+fn compile(patterns: &[String]) -> Vec<Regex> {
+    patterns
+        .iter()
+        .filter_map(|pattern| match Regex::new(pattern) {
+            Ok(regex) => Some(regex),
+            Err(e) => {
+                log::error!("Ignoring chapter skip pattern '{}': {}", pattern, e);
+                None
+            }
+        })
+        .collect()
+}
+
+impl SkipRules {
+    pub fn from_config(config: &ChaptersConfig) -> Self {
+        Self {
+            global: compile(&config.skip),
+            podcasts: config
+                .podcasts
+                .iter()
+                .map(|(url, patterns)| (url.clone(), compile(patterns)))
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.global.is_empty() && self.podcasts.values().all(|rules| rules.is_empty())
+    }
+
+    /// Whether a chapter of the podcast with the given feed URL should be skipped
+    pub fn matches(&self, podcast_url: Option<&str>, title: &str) -> bool {
+        let podcast = podcast_url.and_then(|url| self.podcasts.get(url));
+        self.global
+            .iter()
+            .chain(podcast.into_iter().flatten())
+            .any(|regex| regex.is_match(title))
+    }
+}
+
+/// A skipped chapter, or run of chapters
+#[derive(Debug, Clone, PartialEq)]
+pub struct Skip {
+    pub title: String,
+    /// Start of the first skipped chapter
+    pub from: f64,
+    /// Where playback continued
+    pub to: f64,
+}
+
+/// Skips made in the playing episode
+#[derive(Debug, Default)]
+pub struct SkipState {
+    audio_url: Option<String>,
+    /// Ranges the listener chose to hear by undoing their skip
+    allowed: Vec<(f64, f64)>,
+    last: Option<Skip>,
+}
+
+impl SkipState {
+    /// The skip to make at this position of an episode, if the table of contents
+    /// chapter playing should be skipped. `end_secs` is where a skip ends when the
+    /// chapter runs to the end of the episode.
+    pub fn check(
+        &mut self,
+        audio_url: &str,
+        list: &ChapterList,
+        position_secs: f64,
+        end_secs: f64,
+        skip: impl Fn(&Chapter) -> bool,
+    ) -> Option<Skip> {
+        if self.audio_url.as_deref() != Some(audio_url) {
+            *self = Self {
+                audio_url: Some(audio_url.to_string()),
+                ..Self::default()
+            };
+        }
+
+        let in_range = |&(from, to): &(f64, f64)| position_secs >= from && position_secs < to;
+        if self.allowed.iter().any(in_range) {
+            return None;
+        }
+        // Seeking can land just short of the target, still in the skipped chapter
+        if let Some(last) = &self.last {
+            if in_range(&(last.to - SEEK_TOLERANCE_SECS, last.to)) {
+                return None;
+            }
+        }
+
+        let (index, chapter) = list.toc_chapter_at(position_secs)?;
+        if !skip(chapter) {
+            return None;
+        }
+        let to = list.skip_end(index, &skip).unwrap_or(end_secs);
+        if to <= position_secs + SEEK_TOLERANCE_SECS {
+            return None;
+        }
+
+        let skip = Skip {
+            title: chapter.title.clone(),
+            from: chapter.start_time,
+            to,
+        };
+        self.last = Some(skip.clone());
+        Some(skip)
+    }
+
+    /// Stop skipping the chapters of a skip, e.g. when seeking past them failed
+    pub fn allow(&mut self, skip: &Skip) {
+        self.allowed.push((skip.from, skip.to));
+    }
+
+    /// Take back the last skip in the episode so its chapters play. Returns it to seek back to.
+    pub fn undo(&mut self, audio_url: &str) -> Option<Skip> {
+        if self.audio_url.as_deref() != Some(audio_url) {
+            return None;
+        }
+        let skip = self.last.take()?;
+        self.allow(&skip);
+        Some(skip)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skip_matching_chapters_and_undo() {
+        let config = ChaptersConfig {
+            skip: vec!["(?i)^sponsor".to_string(), "(".to_string()],
+            podcasts: HashMap::from([("https://example.com/feed".to_string(), vec!["Ad break".to_string()])]),
+        };
+        let rules = SkipRules::from_config(&config);
+        assert!(rules.matches(None, "SPONSOR: Acme"));
+        assert!(!rules.matches(None, "Ad break"));
+        assert!(rules.matches(Some("https://example.com/feed"), "Ad break"));
+
+        let titles = ["Intro", "Sponsor", "Ad break", "Interview", "Sponsor"];
+        let list = ChapterList::new(
+            titles
+                .iter()
+                .enumerate()
+                .map(|(i, title)| Chapter {
+                    start_time: i as f64 * 60.0,
+                    title: title.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+        );
+        let skip = |c: &Chapter| rules.matches(Some("https://example.com/feed"), &c.title);
+
+        let mut state = SkipState::default();
+        assert_eq!(state.check("a.mp3", &list, 30.0, 300.0, skip), None);
+        let first = state.check("a.mp3", &list, 60.5, 300.0, skip).unwrap();
+        assert_eq!((first.from, first.to), (60.0, 180.0));
+        // Landed just short of the target
+        assert_eq!(state.check("a.mp3", &list, 179.8, 300.0, skip), None);
+        // The last chapter is skipped to the end of the episode
+        assert_eq!(state.check("a.mp3", &list, 245.0, 300.0, skip).map(|s| s.to), Some(300.0));
+
+        state.last = Some(first.clone());
+        assert_eq!(state.undo("b.mp3"), None);
+        assert_eq!(state.undo("a.mp3"), Some(first));
+        assert_eq!(state.check("a.mp3", &list, 61.0, 300.0, skip), None);
+        // Undone skips are forgotten for the next episode
+        assert!(state.check("b.mp3", &list, 61.0, 300.0, skip).is_some());
+    }
+}
@@ -0,0 +1,750 @@
+//! Commands for scripts and cron jobs, run without opening the terminal UI.
+//! They exit with 0 on success, 1 when something failed and 2 for bad arguments.
+
+use crate::app::{App, Episode, Podcast};
+use crate::daemon::protocol::Request;
+use crate::library::{self, Snapshot};
+use crate::{bookmarks, config, daemon, download, feed, history, opml, persistence, stats};
+use serde::Serialize;
+use simplelog::{LevelFilter, WriteLogger};
+use std::fs::{self, File};
+
+const USAGE: &str = "\
+Usage: shellcast [command]
+
+Without a command, shellcast opens the terminal UI.
+
+Podcasts are chosen by their number in `list`, feed URL or title, and episodes
+by their number in `episodes`, audio URL or title. Any part of a title that
+matches only one works.
+
+Commands:
+  add <url>...                          Subscribe to feeds
+  remove <podcast>...                   Unsubscribe
+  list [--json]                         List subscriptions
+  episodes <podcast> [--json]           List a podcast's episodes
+  refresh [--all | <podcast>...]        Fetch new episodes, of every feed by default
+  mark-played <podcast> (<episode>... | --all)
+                                        Mark episodes played
+  download <podcast> [<episode>... | --unplayed]
+                                        Save episodes for offline listening, the newest by default
+  import-opml <file>                    Subscribe to every feed in an OPML file
+  export-opml                           Print subscriptions as OPML
+  export-history [csv|json]             Print the listening history
+  export-stats                          Print listening statistics as JSON
+  export-bookmarks                      Print bookmarks as Markdown
+  daemon [--foreground]                 Start the background player
+  ctl <command> [<args>...]             Control the background player
+  help                                  Show this help
+
+Exit codes: 0 on success, 1 when something failed, 2 for bad arguments
+";
+
+/// Run the command in `args`, returning the process exit code, or None to open the TUI
+pub fn run(args: &[String]) -> Option<i32> {
+    let (command, args) = args.split_first()?;
+    let code = match command.as_str() {
+        "add" => add(args),
+        "remove" => remove(args),
+        "list" => list(args),
+        "episodes" => episodes(args),
+        "refresh" => refresh(args),
+        "mark-played" => mark_played(args),
+        "download" => download_episodes(args),
+        "import-opml" => import_opml(args),
+        "export-opml" => export_opml(args),
+        "export-history" => export_history(args.first().map(String::as_str).unwrap_or("json")),
+        "export-stats" => export_stats(),
+        "export-bookmarks" => export_bookmarks(),
+        "daemon" => match parse_args(args, &["--foreground"]) {
+            Ok((flags, rest)) if rest.is_empty() => start_daemon(flags.contains(&"--foreground")),
+            Ok(_) => usage_error("'daemon' takes no arguments besides --foreground"),
+            Err(e) => usage_error(&e),
+        },
+        "ctl" => daemon_ctl(args),
+        "help" | "--help" | "-h" => {
+            print!("{}", USAGE);
+            0
+        }
+        other => usage_error(&format!("Unknown command '{}'", other)),
+    };
+    Some(code)
+}
+
+fn usage_error(message: &str) -> i32 {
+    eprintln!("{}\nRun `shellcast help` for usage.", message);
+    2
+}
+
+/// Split `--flags` from the other arguments, rejecting flags the command doesn't take
+fn parse_args<'a>(args: &'a [String], flags: &[&str]) -> Result<(Vec<&'a str>, Vec<&'a str>), String> {
+    let (given, rest): (Vec<&str>, Vec<&str>) = args.iter().map(String::as_str).partition(|a| a.starts_with("--"));
+    match given.iter().find(|flag| !flags.contains(flag)) {
+        Some(flag) => Err(format!("Unknown option '{}'", flag)),
+        None => Ok((given, rest)),
+    }
+}
+
+fn load_app() -> Result<App, String> {
+    let mut app = App::new();
+    app.podcasts = persistence::load_podcasts()?;
+    app.queue = persistence::load_queue()?;
+    app.library_snapshot = Some(Snapshot::of(&app));
+    Ok(app)
+}
+
+/// Save what the command changed. Fetching feeds can take minutes, so the
+/// changes go to a running daemon, or onto the library as it is on disk now,
+/// rather than overwriting whatever was saved in the meantime.
+fn store(app: &mut App) -> Result<(), String> {
+    let Some(snapshot) = &app.library_snapshot else {
+        return crate::save_library(app);
+    };
+    let changes = library::diff(snapshot, app);
+    if !changes.is_empty() {
+        let socket = daemon::socket_path()?;
+        if daemon::is_running(&socket) {
+            let response = daemon::client::request_at(&socket, &Request::Update { changes })?;
+            if !response.ok {
+                return Err(response.error.unwrap_or_default());
+            }
+        } else {
+            let mut current = load_app()?;
+            library::apply(&mut current, changes);
+            crate::save_library(&mut current)?;
+        }
+    }
+    app.library_snapshot = Some(Snapshot::of(app));
+    app.needs_save = false;
+    Ok(())
+}
+
+/// Save any changes and turn the outcome into an exit code
+fn finish(app: &mut App, failed: bool) -> i32 {
+    if app.needs_save {
+        if let Err(e) = store(app) {
+            eprintln!("{}", e);
+            return 1;
+        }
+    }
+    if failed { 1 } else { 0 }
+}
+
+/// Index of the item whose number (counting from 1), URL or title is `query`,
+/// or whose title is the only one containing it
+fn find<'a>(items: impl Iterator<Item = (&'a str, &'a str)>, query: &str, kind: &str) -> Result<usize, String> {
+    let items: Vec<(&str, &str)> = items.collect();
+    // Out of range numbers are matched against titles, like "1984"
+    let number = query.parse::<usize>().ok();
+    if let Some(n) = number.filter(|n| (1..=items.len()).contains(n)) {
+        return Ok(n - 1);
+    }
+    if let Some(index) = items.iter().position(|&(_, url)| url == query) {
+        return Ok(index);
+    }
+
+    let query = query.to_lowercase();
+    if let Some(index) = items.iter().position(|(title, _)| title.to_lowercase() == query) {
+        return Ok(index);
+    }
+    let matches: Vec<usize> = (0..items.len())
+        .filter(|&i| items[i].0.to_lowercase().contains(&query))
+        .collect();
+    match matches[..] {
+        [index] => Ok(index),
+        [] if number.is_some() => Err(format!("There is no {} number {}", kind, query)),
+        [] => Err(format!("No {} matches '{}'", kind, query)),
+        _ => Err(format!(
+            "'{}' matches {} {}s: {}",
+            query,
+            matches.len(),
+            kind,
+            matches.iter().map(|&i| items[i].0).collect::<Vec<_>>().join(", ")
+        )),
+    }
+}
+
+fn find_podcast(app: &App, query: &str) -> Result<usize, String> {
+    match app.find_podcast_by_feed(query) {
+        Some(index) => Ok(index),
+        None => find(app.podcasts.iter().map(|p| (p.title.as_str(), p.url.as_str())), query, "podcast"),
+    }
+}
+
+fn find_episode(podcast: &Podcast, query: &str) -> Result<usize, String> {
+    find(podcast.episodes.iter().map(|e| (e.title.as_str(), e.audio_url.as_str())), query, "episode")
+}
+
+/// Load the library and find the podcast and episodes named in `args`, reporting failures
+fn select(args: &[&str]) -> Result<(App, usize, Vec<usize>), i32> {
+    let Some((podcast, episodes)) = args.split_first() else {
+        return Err(usage_error("Missing podcast"));
+    };
+    let app = load_app().map_err(|e| {
+        eprintln!("{}", e);
+        1
+    })?;
+    let selected = find_podcast(&app, podcast).and_then(|p| {
+        let episodes = episodes
+            .iter()
+            .map(|query| find_episode(&app.podcasts[p], query))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((p, episodes))
+    });
+    match selected {
+        Ok((p, episodes)) => Ok((app, p, episodes)),
+        Err(e) => {
+            eprintln!("{}", e);
+            Err(1)
+        }
+    }
+}
+
+fn add(args: &[String]) -> i32 {
+    let urls = match parse_args(args, &[]) {
+        Ok((_, urls)) if !urls.is_empty() => urls,
+        Ok(_) => return usage_error("'add' needs a feed URL"),
+        Err(e) => return usage_error(&e),
+    };
+    let mut app = match load_app() {
+        Ok(app) => app,
+        Err(e) => {
+            eprintln!("{}", e);
+            return 1;
+        }
+    };
+
+    let mut failed = false;
+    for url in urls {
+        if let Some(index) = app.find_podcast_by_feed(url) {
+            println!("Already subscribed: {}", app.podcasts[index].title);
+            continue;
+        }
+        match feed::fetch_and_parse(url) {
+            Ok(podcast) => {
+                println!("Added: {} ({} episodes)", podcast.title, podcast.episodes.len());
+                app.add_podcast(podcast);
+            }
+            Err(e) => {
+                eprintln!("{}: {}", url, e);
+                failed = true;
+            }
+        }
+    }
+    finish(&mut app, failed)
+}
+
+fn remove(args: &[String]) -> i32 {
+    let queries = match parse_args(args, &[]) {
+        Ok((_, queries)) if !queries.is_empty() => queries,
+        Ok(_) => return usage_error("'remove' needs a podcast"),
+        Err(e) => return usage_error(&e),
+    };
+    let mut app = match load_app() {
+        Ok(app) => app,
+        Err(e) => {
+            eprintln!("{}", e);
+            return 1;
+        }
+    };
+
+    // Find every podcast before removing any, so a typo changes nothing
+    let indices = match queries.iter().map(|q| find_podcast(&app, q)).collect::<Result<Vec<_>, _>>() {
+        Ok(indices) => indices,
+        Err(e) => {
+            eprintln!("{}", e);
+            return 1;
+        }
+    };
+    let urls: Vec<String> = indices.iter().map(|&i| app.podcasts[i].url.clone()).collect();
+    for &index in &indices {
+        println!("Removed: {}", app.podcasts[index].title);
+    }
+    app.remove_podcasts(&urls);
+    app.queue.retain(|episode| !urls.contains(&episode.podcast_url));
+    finish(&mut app, false)
+}
+
+#[derive(Serialize)]
+struct PodcastSummary<'a> {
+    title: &'a str,
+    url: &'a str,
+    episodes: usize,
+    unplayed: usize,
+}
+
+fn list(args: &[String]) -> i32 {
+    let json = match parse_args(args, &["--json"]) {
+        Ok((flags, rest)) if rest.is_empty() => flags.contains(&"--json"),
+        Ok(_) => return usage_error("'list' takes no arguments besides --json"),
+        Err(e) => return usage_error(&e),
+    };
+    let podcasts = match persistence::load_podcasts() {
+        Ok(podcasts) => podcasts,
+        Err(e) => {
+            eprintln!("{}", e);
+            return 1;
+        }
+    };
+
+    let summaries: Vec<PodcastSummary> = podcasts
+        .iter()
+        .map(|p| PodcastSummary {
+            title: &p.title,
+            url: &p.url,
+            episodes: p.episodes.len(),
+            unplayed: p.episodes.iter().filter(|e| !e.played).count(),
+        })
+        .collect();
+    if json {
+        return print_json(&summaries);
+    }
+    for (i, podcast) in summaries.iter().enumerate() {
+        println!("{:>3}  {}  [{} unplayed]  {}", i + 1, podcast.title, podcast.unplayed, podcast.url);
+    }
+    0
+}
+
+#[derive(Serialize)]
+struct EpisodeSummary<'a> {
+    title: &'a str,
+    published: &'a str,
+    duration_secs: Option<u64>,
+    audio_url: &'a str,
+    played: bool,
+    position_secs: u64,
+    starred: bool,
+    download_path: Option<&'a str>,
+}
+
+fn episodes(args: &[String]) -> i32 {
+    let (json, query) = match parse_args(args, &["--json"]) {
+        Ok((flags, rest)) if rest.len() == 1 => (flags.contains(&"--json"), rest[0]),
+        Ok(_) => return usage_error("'episodes' needs one podcast"),
+        Err(e) => return usage_error(&e),
+    };
+    let (app, p, _) = match select(&[query]) {
+        Ok(selected) => selected,
+        Err(code) => return code,
+    };
+    let episodes = &app.podcasts[p].episodes;
+
+    if json {
+        let summaries: Vec<EpisodeSummary> = episodes
+            .iter()
+            .map(|e| EpisodeSummary {
+                title: &e.title,
+                published: &e.published,
+                duration_secs: e.duration.map(|d| d.as_secs()),
+                audio_url: &e.audio_url,
+                played: e.played,
+                position_secs: e.position_secs,
+                starred: e.starred,
+                download_path: e.download_path.as_deref(),
+            })
+            .collect();
+        return print_json(&summaries);
+    }
+    for (i, episode) in episodes.iter().enumerate() {
+        let date = feed::parse_published(&episode.published)
+            .map(|d| d.format("%Y-%m-%d").to_string())
+            .unwrap_or_default();
+        let state = if episode.played {
+            "played".to_string()
+        } else if episode.position_secs > 0 {
+            crate::now_playing::format_time(episode.position_secs)
+        } else {
+            String::new()
+        };
+        let downloaded = if download::local_file(episode).is_some() { "  [downloaded]" } else { "" };
+        println!("{:>3}  {:<10}  {:>7}  {}{}", i + 1, date, state, episode.title, downloaded);
+    }
+    0
+}
+
+fn print_json(value: &impl Serialize) -> i32 {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => {
+            println!("{}", json);
+            0
+        }
+        Err(e) => {
+            eprintln!("Failed to serialize: {}", e);
+            1
+        }
+    }
+}
+
+fn refresh(args: &[String]) -> i32 {
+    let queries = match parse_args(args, &["--all"]) {
+        Ok((flags, queries)) if flags.is_empty() || queries.is_empty() => queries,
+        Ok(_) => return usage_error("Name podcasts to refresh or use --all, not both"),
+        Err(e) => return usage_error(&e),
+    };
+    let mut app = match load_app() {
+        Ok(app) => app,
+        Err(e) => {
+            eprintln!("{}", e);
+            return 1;
+        }
+    };
+    let indices = if queries.is_empty() {
+        (0..app.podcasts.len()).collect()
+    } else {
+        match queries.iter().map(|q| find_podcast(&app, q)).collect::<Result<Vec<_>, _>>() {
+            Ok(indices) => indices,
+            Err(e) => {
+                eprintln!("{}", e);
+                return 1;
+            }
+        }
+    };
+
+    let mut failed = false;
+    for index in indices {
+        let podcast = &mut app.podcasts[index];
+        match feed::refresh_feed(podcast) {
+            Ok(added) => {
+                println!("{}: {} new episode(s)", podcast.title, added.len());
+                app.needs_save = true;
+            }
+            Err(e) => {
+                eprintln!("{}: {}", podcast.title, e);
+                failed = true;
+            }
+        }
+    }
+    finish(&mut app, failed)
+}
+
+fn mark_played(args: &[String]) -> i32 {
+    let (all, rest) = match parse_args(args, &["--all"]) {
+        Ok((flags, rest)) => (flags.contains(&"--all"), rest),
+        Err(e) => return usage_error(&e),
+    };
+    if all == (rest.len() > 1) {
+        return usage_error("'mark-played' needs a podcast and either episodes or --all");
+    }
+    let (mut app, p, selected) = match select(&rest) {
+        Ok(selected) => selected,
+        Err(code) => return code,
+    };
+
+    let indices = if all { (0..app.podcasts[p].episodes.len()).collect() } else { selected };
+    let mut marked = 0;
+    for index in indices {
+        let episode = &mut app.podcasts[p].episodes[index];
+        if !episode.played {
+            episode.played = true;
+            episode.touch();
+            marked += 1;
+        }
+    }
+    app.needs_save = marked > 0;
+    println!("Marked {} episode(s) played", marked);
+    finish(&mut app, false)
+}
+
+/// The most recently published episode. Episodes whose date can't be read
+/// count as older than the rest, and ties go to the one listed first.
+fn newest_episode(podcast: &Podcast) -> Option<usize> {
+    (0..podcast.episodes.len())
+        .rev()
+        .max_by_key(|&i| feed::parse_published(&podcast.episodes[i].published))
+}
+
+fn download_episodes(args: &[String]) -> i32 {
+    let (unplayed, rest) = match parse_args(args, &["--unplayed"]) {
+        Ok((flags, rest)) => (flags.contains(&"--unplayed"), rest),
+        Err(e) => return usage_error(&e),
+    };
+    if unplayed && rest.len() > 1 {
+        return usage_error("Name episodes to download or use --unplayed, not both");
+    }
+    let (mut app, p, selected) = match select(&rest) {
+        Ok(selected) => selected,
+        Err(code) => return code,
+    };
+    let dir = match config::Config::load().downloads.directory() {
+        Ok(dir) => dir,
+        Err(e) => {
+            eprintln!("{}", e);
+            return 1;
+        }
+    };
+
+    let podcast = &app.podcasts[p];
+    let indices: Vec<usize> = if unplayed {
+        (0..podcast.episodes.len()).filter(|&i| !podcast.episodes[i].played).collect()
+    } else if selected.is_empty() {
+        newest_episode(podcast).into_iter().collect()
+    } else {
+        selected
+    };
+
+    // A running daemon downloads the episodes and records where itself
+    let daemon_socket = daemon::socket_path().ok().filter(|socket| daemon::is_running(socket));
+
+    let mut failed = false;
+    for index in indices {
+        let podcast = &app.podcasts[p];
+        let episode: &Episode = &podcast.episodes[index];
+        if let Some(path) = download::local_file(episode) {
+            println!("Already downloaded: {}", path.display());
+            continue;
+        }
+
+        if let Some(socket) = &daemon_socket {
+            let request = Request::Download { audio_url: episode.audio_url.clone() };
+            match daemon::client::request_at(socket, &request) {
+                Ok(response) if response.ok => println!("{}", response.message.unwrap_or_default()),
+                Ok(response) => {
+                    eprintln!("{}: {}", episode.title, response.error.unwrap_or_default());
+                    failed = true;
+                }
+                Err(e) => {
+                    eprintln!("{}: {}", episode.title, e);
+                    failed = true;
+                }
+            }
+            continue;
+        }
+
+        match download::download(&podcast.title, episode, &dir) {
+            Ok(path) => {
+                println!("Downloaded: {}", path.display());
+                app.podcasts[p].episodes[index].download_path = Some(path.to_string_lossy().into_owned());
+                // Saved after every episode, so finished downloads are kept if a later one is interrupted
+                if let Err(e) = store(&mut app) {
+                    eprintln!("{}", e);
+                    return 1;
+                }
+            }
+            Err(e) => {
+                eprintln!("{}: {}", episode.title, e);
+                failed = true;
+            }
+        }
+    }
+    finish(&mut app, failed)
+}
+
+fn import_opml(args: &[String]) -> i32 {
+    let path = match parse_args(args, &[]) {
+        Ok((_, rest)) if rest.len() == 1 => rest[0],
+        Ok(_) => return usage_error("'import-opml' needs one file"),
+        Err(e) => return usage_error(&e),
+    };
+    let feeds = match fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path, e))
+        .and_then(|xml| opml::parse(&xml))
+    {
+        Ok(feeds) => feeds,
+        Err(e) => {
+            eprintln!("{}", e);
+            return 1;
+        }
+    };
+    let mut app = match load_app() {
+        Ok(app) => app,
+        Err(e) => {
+            eprintln!("{}", e);
+            return 1;
+        }
+    };
+
+    let (mut added, mut failed) = (0, false);
+    for opml_feed in &feeds {
+        if app.is_subscribed(&opml_feed.url) {
+            println!("Already subscribed: {}", opml_feed.title);
+            continue;
+        }
+        match feed::fetch_and_parse(&opml_feed.url) {
+            Ok(podcast) => {
+                println!("Added: {}", podcast.title);
+                app.add_podcast(podcast);
+                added += 1;
+            }
+            Err(e) => {
+                eprintln!("{}: {}", opml_feed.title, e);
+                failed = true;
+            }
+        }
+    }
+    println!("Imported {} of {} feed(s)", added, feeds.len());
+    finish(&mut app, failed)
+}
+
+fn export_opml(args: &[String]) -> i32 {
+    if !args.is_empty() {
+        return usage_error("'export-opml' takes no arguments");
+    }
+    match persistence::load_podcasts() {
+        Ok(podcasts) => {
+            print!("{}", opml::to_opml(&podcasts));
+            0
+        }
+        Err(e) => {
+            eprintln!("Failed to load podcasts: {}", e);
+            1
+        }
+    }
+}
+
+/// Print the listening history to stdout as CSV or JSON, returning the process exit code
+fn export_history(format: &str) -> i32 {
+    let events = match history::load() {
+        Ok(events) => events,
+        Err(e) => {
+            eprintln!("Failed to load history: {}", e);
+            return 1;
+        }
+    };
+
+    let output = match format {
+        "csv" => Ok(history::to_csv(&events)),
+        "json" => history::to_json(&events),
+        other => {
+            eprintln!("Unknown format '{}', expected csv or json", other);
+            return 2;
+        }
+    };
+
+    match output {
+        Ok(text) => {
+            print!("{}", text);
+            0
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
+/// Print bookmarks to stdout as Markdown, returning the process exit code
+fn export_bookmarks() -> i32 {
+    match bookmarks::load() {
+        Ok(bookmarks) => {
+            print!("{}", bookmarks::to_markdown(&bookmarks));
+            0
+        }
+        Err(e) => {
+            eprintln!("Failed to load bookmarks: {}", e);
+            1
+        }
+    }
+}
+
+/// Print listening statistics to stdout as JSON, returning the process exit code
+fn export_stats() -> i32 {
+    let events = match history::load() {
+        Ok(events) => events,
+        Err(e) => {
+            eprintln!("Failed to load history: {}", e);
+            return 1;
+        }
+    };
+    let podcasts = persistence::load_podcasts().unwrap_or_default();
+
+    match serde_json::to_string_pretty(&stats::compute_local(&events, &podcasts)) {
+        Ok(json) => {
+            println!("{}", json);
+            0
+        }
+        Err(e) => {
+            eprintln!("Failed to serialize stats: {}", e);
+            1
+        }
+    }
+}
+
+/// Start the daemon in the background, or run it in this process with
+/// `--foreground`, returning the process exit code
+fn start_daemon(foreground: bool) -> i32 {
+    if !foreground {
+        return match daemon::spawn() {
+            Ok(socket) => {
+                println!("Daemon listening on {}", socket.display());
+                0
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                1
+            }
+        };
+    }
+
+    match persistence::get_data_file(daemon::LOG_FILE).and_then(|path| {
+        File::create(path).map_err(|e| format!("Failed to create daemon log: {}", e))
+    }) {
+        Ok(file) => {
+            let _ = WriteLogger::init(LevelFilter::Debug, simplelog::Config::default(), file);
+        }
+        Err(e) => eprintln!("{}", e),
+    }
+
+    match daemon::run() {
+        Ok(()) => 0,
+        Err(e) => {
+            log::error!("Daemon stopped: {}", e);
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
+/// Send a request to the daemon and print its response as JSON, returning the process exit code
+fn daemon_ctl(args: &[String]) -> i32 {
+    let request = match daemon::protocol::Request::from_args(args) {
+        Ok(request) => request,
+        Err(e) => {
+            eprintln!("{}", e);
+            return 2;
+        }
+    };
+
+    match daemon::client::request(&request) {
+        Ok(response) => {
+            match serde_json::to_string_pretty(&response) {
+                Ok(json) => println!("{}", json),
+                Err(e) => eprintln!("Failed to serialize response: {}", e),
+            }
+            if response.ok { 0 } else { 1 }
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_by_number_url_or_title() {
+        let items = [
+            ("Daily News", "https://a/feed"),
+            ("Weekly News", "https://b/feed"),
+            ("News", "https://c/feed"),
+            ("1984", "https://d/feed"),
+        ];
+        let find = |query: &str| find(items.iter().copied(), query, "podcast");
+
+        assert_eq!(find("2"), Ok(1));
+        assert_eq!(find("1984"), Ok(3), "a number past the end of the list is a title");
+        assert_eq!(find("5"), Err("There is no podcast number 5".to_string()));
+        assert_eq!(find("https://c/feed"), Ok(2));
+        // An exact title wins over titles that contain it
+        assert_eq!(find("news"), Ok(2));
+        assert_eq!(find("week"), Ok(1));
+        assert_eq!(find("ly"), Err("'ly' matches 2 podcasts: Daily News, Weekly News".to_string()));
+        assert!(find("sports").is_err());
+
+        let args: Vec<String> = ["--json", "x"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(parse_args(&args, &["--json"]), Ok((vec!["--json"], vec!["x"])));
+        assert!(parse_args(&args, &[]).is_err());
+    }
+}
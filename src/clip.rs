@@ -0,0 +1,209 @@
+//! Cutting a segment out of an episode into its own MP3 file, without re-encoding
+
+use crate::chapters::{sniff_format, AudioFormat};
+use crate::now_playing::format_time;
+use id3::TagLike;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Kbps by bitrate index for Layer III, MPEG-1 then MPEG-2/2.5
+const BITRATES: [[u32; 15]; 2] = [
+    [0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320],
+    [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160],
+];
+
+/// Hz by sample rate index for MPEG-1, MPEG-2 and MPEG-2.5
+const SAMPLE_RATES: [[u32; 3]; 3] = [[44100, 48000, 32000], [22050, 24000, 16000], [11025, 12000, 8000]];
+
+/// What a clip was cut from, for its file name and tags
+#[derive(Debug, Clone)]
+pub struct ClipInfo {
+    pub podcast_title: String,
+    pub episode_title: String,
+    pub audio_url: String,
+    pub start_secs: f64,
+    pub end_secs: f64,
+}
+
+impl ClipInfo {
+    /// Clip range as shown to the user, e.g. "12:00-12:30"
+    pub fn range(&self) -> String {
+        format!("{}-{}", format_time(self.start_secs as u64), format_time(self.end_secs as u64))
+    }
+
+    pub fn file_name(&self) -> String {
+        let name = format!(
+            "{} - {} ({}).mp3",
+            self.podcast_title,
+            self.episode_title,
+            self.range().replace(':', ".")
+        );
+        name.chars()
+            .map(|c| if c.is_control() || "/\\:*?\"<>|".contains(c) { '_' } else { c })
+            .collect()
+    }
+}
+
+/// A Layer III frame: its length in bytes and duration in seconds
+struct FrameHeader {
+    len: usize,
+    secs: f64,
+}
+
+fn parse_header(header: &[u8]) -> Option<FrameHeader> {
+    let [0xFF, b1, b2, _] = *header.get(..4)? else {
+        return None;
+    };
+    if b1 & 0xE0 != 0xE0 || (b1 >> 1) & 0x03 != 0x01 {
+        return None; // Not a frame sync, or not Layer III
+    }
+    // 3 = MPEG-1, 2 = MPEG-2, 0 = MPEG-2.5
+    let version = match (b1 >> 3) & 0x03 {
+        3 => 0,
+        2 => 1,
+        0 => 2,
+        _ => return None,
+    };
+    let kbps = *BITRATES[version.min(1)].get((b2 >> 4) as usize).filter(|&&k| k > 0)?;
+    let sample_rate = *SAMPLE_RATES[version].get(((b2 >> 2) & 0x03) as usize)?;
+    let padding = ((b2 >> 1) & 0x01) as u32;
+
+    let (samples, factor) = if version == 0 { (1152, 144) } else { (576, 72) };
+    Some(FrameHeader {
+        len: (factor * kbps * 1000 / sample_rate + padding) as usize,
+        secs: samples as f64 / sample_rate as f64,
+    })
+}
+
+/// Length of the ID3v2 tag at the start of the data, if any
+fn id3_len(data: &[u8]) -> usize {
+    match data.get(..10) {
+        Some(tag) if tag.starts_with(b"ID3") => {
+            let size = tag[6..10].iter().fold(0usize, |acc, &b| (acc << 7) | (b & 0x7F) as usize);
+            let footer = if tag[5] & 0x10 != 0 { 10 } else { 0 };
+            10 + size + footer
+        }
+        _ => 0,
+    }
+}
+
+/// Whether a frame only holds a Xing/Info/VBRI header describing the whole file
+fn is_info_frame(frame: &[u8]) -> bool {
+    let head = &frame[..frame.len().min(64)];
+    [b"Xing", b"Info", b"VBRI"].iter().any(|tag| head.windows(4).any(|w| w == *tag))
+}
+
+/// The MP3 frames that start between `start_secs` and `end_secs`
+pub fn cut_mp3(data: &[u8], start_secs: f64, end_secs: f64) -> Result<Vec<u8>, String> {
+    let mut pos = id3_len(data);
+    let mut time = 0.0;
+    let mut first = true;
+    let mut out = Vec::new();
+
+    while time < end_secs {
+        let Some(header) = data.get(pos..).and_then(parse_header) else {
+            if pos + 4 > data.len() {
+                return Err(format!(
+                    "The audio only reaches {}, not the end of the clip yet",
+                    format_time(time as u64)
+                ));
+            }
+            // Skip junk between frames
+            pos += 1;
+            continue;
+        };
+        let Some(frame) = data.get(pos..pos + header.len) else {
+            return Err("The audio file ends in the middle of a frame".to_string());
+        };
+        pos += header.len;
+
+        if std::mem::take(&mut first) && is_info_frame(frame) {
+            continue;
+        }
+        if time >= start_secs {
+            out.extend_from_slice(frame);
+        }
+        time += header.secs;
+    }
+
+    if out.is_empty() {
+        return Err("The clip is empty".to_string());
+    }
+    Ok(out)
+}
+
+/// ID3 tag naming the podcast, the episode and where in it the clip is from
+fn clip_tag(info: &ClipInfo) -> id3::Tag {
+    let mut tag = id3::Tag::new();
+    tag.set_title(format!("{} ({})", info.episode_title, info.range()));
+    tag.set_artist(info.podcast_title.clone());
+    tag.set_album(info.episode_title.clone());
+    tag.add_frame(id3::frame::Comment {
+        lang: "eng".to_string(),
+        description: String::new(),
+        text: format!("Clip from {} to {} of {}", format_time(info.start_secs as u64), format_time(info.end_secs as u64), info.audio_url),
+    });
+    tag
+}
+
+/// Write the clip from an MP3 file into `dir`, returning the new file's path
+pub fn export(source: &Path, dir: &Path, info: &ClipInfo, with_tags: bool) -> Result<PathBuf, String> {
+    let data = fs::read(source).map_err(|e| format!("Failed to read audio file: {}", e))?;
+    if sniff_format(&data) != Some(AudioFormat::Mp3) {
+        return Err("Clips can only be cut from MP3 files".to_string());
+    }
+    let frames = cut_mp3(&data, info.start_secs, info.end_secs)?;
+
+    let mut out = Vec::new();
+    if with_tags {
+        clip_tag(info)
+            .write_to(&mut out, id3::Version::Id3v24)
+            .map_err(|e| format!("Failed to write ID3 tag: {}", e))?;
+    }
+    out.extend_from_slice(&frames);
+
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create clips directory: {}", e))?;
+    let path = dir.join(info.file_name());
+    fs::write(&path, out).map_err(|e| format!("Failed to write clip: {}", e))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/silence.mp3");
+
+    fn info(start_secs: f64, end_secs: f64) -> ClipInfo {
+        ClipInfo {
+            podcast_title: "Show".to_string(),
+            episode_title: "Episode: One".to_string(),
+            audio_url: "https://example.com/one.mp3".to_string(),
+            start_secs,
+            end_secs,
+        }
+    }
+
+    #[test]
+    fn test_export_clip_from_fixture() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = export(Path::new(FIXTURE), dir.path(), &info(1.0, 2.0), true).unwrap();
+        assert_eq!(path.file_name().unwrap(), "Show - Episode_ One (0.01-0.02).mp3");
+
+        let tag = id3::Tag::read_from_path(&path).unwrap();
+        assert_eq!(tag.title(), Some("Episode: One (0:01-0:02)"));
+        assert_eq!(tag.artist(), Some("Show"));
+
+        // 36 ms frames starting from 1.008s up to 1.980s
+        let decoder = rodio::Decoder::new(std::io::BufReader::new(fs::File::open(&path).unwrap())).unwrap();
+        let secs = decoder.count() as f64 / 32_000.0;
+        assert!((secs - 28.0 * 0.036).abs() < 0.05, "decoded {}s", secs);
+    }
+
+    #[test]
+    fn test_clip_past_end_of_file() {
+        let data = fs::read(FIXTURE).unwrap();
+        assert_eq!(cut_mp3(&data, 0.0, 1.0).unwrap().len(), 28 * 144);
+        assert!(cut_mp3(&data, 3.0, 10.0).is_err());
+    }
+}
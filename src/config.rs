@@ -1,12 +1,189 @@
 use crate::theme::Theme;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Config {
     #[serde(default)]
     pub theme: ThemeConfig,
+
+    #[serde(default)]
+    pub directory: DirectoryConfig,
+
+    /// Subscription and episode state sync (disabled when absent)
+    #[serde(default)]
+    pub sync: Option<SyncConfig>,
+
+    #[serde(default)]
+    pub artwork: ArtworkConfig,
+
+    #[serde(default)]
+    pub chapters: ChaptersConfig,
+
+    #[serde(default)]
+    pub clips: ClipsConfig,
+
+    #[serde(default)]
+    pub downloads: DownloadsConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClipsConfig {
+    /// Where clips are saved (default: ~/.config/shellcast/clips)
+    #[serde(default)]
+    pub directory: Option<String>,
+
+    /// Tag clips with the podcast, episode and clip range
+    #[serde(default = "default_clip_id3")]
+    pub id3: bool,
+}
+
+fn default_clip_id3() -> bool {
+    true
+}
+
+impl Default for ClipsConfig {
+    fn default() -> Self {
+        Self {
+            directory: None,
+            id3: default_clip_id3(),
+        }
+    }
+}
+
+/// A configured directory with a leading `~` expanded, or `default` in the config directory
+fn expand_directory(dir: Option<&str>, default: &str) -> Result<PathBuf, String> {
+    match dir {
+        Some(dir) => match dir.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir()
+                .map(|home| home.join(rest))
+                .ok_or_else(|| "Could not determine home directory".to_string()),
+            None => Ok(PathBuf::from(dir)),
+        },
+        None => crate::persistence::get_data_file(default),
+    }
+}
+
+impl ClipsConfig {
+    /// The clips directory, with a leading `~` expanded
+    pub fn directory(&self) -> Result<PathBuf, String> {
+        expand_directory(self.directory.as_deref(), "clips")
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DownloadsConfig {
+    /// Where `shellcast download` saves episodes (default: ~/.config/shellcast/downloads)
+    #[serde(default)]
+    pub directory: Option<String>,
+}
+
+impl DownloadsConfig {
+    /// The downloads directory, with a leading `~` expanded
+    pub fn directory(&self) -> Result<PathBuf, String> {
+        expand_directory(self.directory.as_deref(), "downloads")
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChaptersConfig {
+    /// Regexes for chapter titles to skip in every podcast, e.g. "(?i)^sponsor"
+    #[serde(default)]
+    pub skip: Vec<String>,
+
+    /// Extra skip regexes per podcast, keyed by feed URL
+    #[serde(default)]
+    pub podcasts: HashMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtworkConfig {
+    /// Terminal graphics protocol: "auto", "kitty", "sixel", "iterm2",
+    /// "halfblocks" or "off"
+    #[serde(default = "default_artwork_protocol")]
+    pub protocol: String,
+}
+
+fn default_artwork_protocol() -> String {
+    "auto".to_string()
+}
+
+impl Default for ArtworkConfig {
+    fn default() -> Self {
+        Self {
+            protocol: default_artwork_protocol(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectoryConfig {
+    /// Directories searched in Browse, merged in this order:
+    /// "gpodder", "itunes" and/or "podcastindex"
+    #[serde(default = "default_providers")]
+    pub providers: Vec<String>,
+
+    /// Store country for iTunes search and charts
+    #[serde(default = "default_itunes_country")]
+    pub itunes_country: String,
+
+    /// Podcast Index API credentials (required for "podcastindex")
+    #[serde(default)]
+    pub podcastindex_key: Option<String>,
+    #[serde(default)]
+    pub podcastindex_secret: Option<String>,
+}
+
+fn default_providers() -> Vec<String> {
+    vec!["gpodder".to_string()]
+}
+
+fn default_itunes_country() -> String {
+    "us".to_string()
+}
+
+impl Default for DirectoryConfig {
+    fn default() -> Self {
+        Self {
+            providers: default_providers(),
+            itunes_country: default_itunes_country(),
+            podcastindex_key: None,
+            podcastindex_secret: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncConfig {
+    /// Sync backend: "gpodder" or "nextcloud" (gpoddersync app)
+    #[serde(default = "default_sync_backend")]
+    pub backend: String,
+
+    /// Base URL of the sync server, e.g. a self-hosted gpodder instance or Nextcloud
+    #[serde(default = "default_sync_server")]
+    pub server: String,
+
+    pub username: String,
+    /// Account password (for Nextcloud, preferably an app password)
+    pub password: String,
+
+    /// Device ID this client registers as
+    #[serde(default = "default_device_id")]
+    pub device_id: String,
+}
+
+fn default_sync_backend() -> String {
+    "gpodder".to_string()
+}
+
+fn default_sync_server() -> String {
+    "https://gpodder.net".to_string()
+}
+
+fn default_device_id() -> String {
+    "shellcast".to_string()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,14 +215,6 @@ impl Default for ThemeConfig {
     }
 }
 
-impl Default for Config {
-    fn default() -> Self {
-        Self {
-            theme: ThemeConfig::default(),
-        }
-    }
-}
-
 impl Config {
     /// Get the config file path
     pub fn config_path() -> PathBuf {
@@ -82,26 +251,6 @@ impl Config {
         }
     }
 
-    /// Save config to file
-    pub fn save(&self) -> Result<(), String> {
-        let path = Self::config_path();
-
-        // Create config directory if it doesn't exist
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent)
-                .map_err(|e| format!("Failed to create config directory: {}", e))?;
-        }
-
-        let toml_string = toml::to_string_pretty(self)
-            .map_err(|e| format!("Failed to serialize config: {}", e))?;
-
-        fs::write(&path, toml_string)
-            .map_err(|e| format!("Failed to write config file: {}", e))?;
-
-        log::info!("Saved config to {:?}", path);
-        Ok(())
-    }
-
     /// Get the active theme based on config
     pub fn get_theme(&self) -> Theme {
         let mut theme = match self.theme.name.as_str() {
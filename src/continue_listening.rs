@@ -0,0 +1,123 @@
+use crate::app::{Episode, Podcast};
+
+/// Episodes that were started but not finished, most recently listened first
+pub struct ContinueListeningState {
+    /// (podcast index, episode index) pairs
+    pub entries: Vec<(usize, usize)>,
+    pub selected_index: usize,
+}
+
+impl ContinueListeningState {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            selected_index: 0,
+        }
+    }
+
+    /// Rebuild the entry list from the current podcasts
+    pub fn rebuild(&mut self, podcasts: &[Podcast]) {
+        self.entries = collect_in_progress(podcasts);
+        if self.selected_index >= self.entries.len() {
+            self.selected_index = self.entries.len().saturating_sub(1);
+        }
+    }
+
+    pub fn move_up(&mut self) {
+        if self.selected_index > 0 {
+            self.selected_index -= 1;
+        }
+    }
+
+    pub fn move_down(&mut self) {
+        if self.selected_index < self.entries.len().saturating_sub(1) {
+            self.selected_index += 1;
+        }
+    }
+
+    pub fn selected(&self) -> Option<(usize, usize)> {
+        self.entries.get(self.selected_index).copied()
+    }
+}
+
+/// Whether an episode has a resume point and has not been finished
+pub fn is_in_progress(episode: &Episode) -> bool {
+    episode.position_secs > 0 && !episode.played
+}
+
+/// Collect in-progress episodes from every podcast, most recently listened first
+pub fn collect_in_progress(podcasts: &[Podcast]) -> Vec<(usize, usize)> {
+    let mut entries: Vec<(usize, usize)> = podcasts
+        .iter()
+        .enumerate()
+        .flat_map(|(p, podcast)| {
+            podcast
+                .episodes
+                .iter()
+                .enumerate()
+                .filter(|(_, e)| is_in_progress(e))
+                .map(move |(e, _)| (p, e))
+        })
+        .collect();
+
+    entries.sort_by_key(|&(p, e)| std::cmp::Reverse(podcasts[p].episodes[e].last_played));
+    entries
+}
+
+/// Fraction of the episode already listened to, if the duration is known
+pub fn progress_ratio(episode: &Episode) -> Option<f64> {
+    let total = episode.duration?.as_secs();
+    if total == 0 {
+        return None;
+    }
+    Some((episode.position_secs as f64 / total as f64).min(1.0))
+}
+
+/// Human-readable time since a unix timestamp, e.g. "5m ago" or "3d ago"
+pub fn format_ago(timestamp: i64, now: i64) -> String {
+    let secs = (now - timestamp).max(0);
+    match secs {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{}m ago", secs / 60),
+        3600..=86_399 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86_400),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn episode(position_secs: u64, played: bool, last_played: Option<i64>) -> Episode {
+        Episode {
+            title: "Episode".to_string(),
+            duration: Some(Duration::from_secs(600)),
+            played,
+            position_secs,
+            last_played,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_collect_in_progress_most_recent_first() {
+        let podcasts = vec![Podcast {
+            title: "Test".to_string(),
+            description: String::new(),
+            url: String::new(),
+            episodes: vec![
+                episode(120, false, Some(100)),
+                episode(0, false, Some(300)),
+                episode(300, true, Some(400)),
+                episode(60, false, Some(200)),
+            ],
+            filter: Default::default(),
+            categories: Vec::new(),
+            image_url: None,
+        }];
+
+        assert_eq!(collect_in_progress(&podcasts), vec![(0, 3), (0, 0)]);
+        assert_eq!(progress_ratio(&podcasts[0].episodes[0]), Some(0.2));
+    }
+}
@@ -0,0 +1,227 @@
+//! Talking to a running daemon, for `shellcast ctl` and the TUI
+
+use super::protocol::{PlayerState, Request, Response, Status};
+use crate::library::LibraryChange;
+use crate::playback::{BufferStatus, Playback};
+use std::cell::{Cell, Ref, RefCell};
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// How long a status is reused before asking the daemon again
+const STATUS_MAX_AGE: Duration = Duration::from_millis(250);
+/// How long the TUI waits before asking a daemon that stopped answering again
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The daemon answers most requests straight away. The TUI asks on every
+/// frame, so a daemon that hangs mustn't hold it up for long.
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(1);
+/// Long enough for the daemon to fetch a feed when subscribing
+const SUBSCRIBE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// `None` for downloads, which take as long as the episode is big
+fn response_timeout(request: &Request) -> Option<Duration> {
+    match request {
+        Request::Subscribe { .. } => Some(SUBSCRIBE_TIMEOUT),
+        Request::Download { .. } => None,
+        _ => Some(RESPONSE_TIMEOUT),
+    }
+}
+
+/// Send one request to the daemon listening on `socket` and wait for its response
+pub fn request_at(socket: &Path, request: &Request) -> Result<Response, String> {
+    let mut stream = UnixStream::connect(socket)
+        .map_err(|e| format!("Failed to connect to the daemon at {}: {}", socket.display(), e))?;
+    stream
+        .set_read_timeout(response_timeout(request))
+        .and_then(|_| stream.set_write_timeout(Some(RESPONSE_TIMEOUT)))
+        .map_err(|e| format!("Failed to configure daemon connection: {}", e))?;
+
+    let json = serde_json::to_string(request).map_err(|e| format!("Failed to serialize request: {}", e))?;
+    writeln!(stream, "{}", json).map_err(|e| format!("Failed to send request: {}", e))?;
+
+    let mut line = String::new();
+    BufReader::new(stream)
+        .read_line(&mut line)
+        .map_err(|e| format!("Failed to read response: {}", e))?;
+    serde_json::from_str(&line).map_err(|e| format!("Invalid response from daemon: {}", e))
+}
+
+pub fn request(request: &Request) -> Result<Response, String> {
+    request_at(&super::socket_path()?, request)
+}
+
+/// Playback in the daemon, for the TUI to use in place of its own player.
+/// The daemon moves on to the next queued episode itself, so an episode
+/// never looks finished from here.
+pub struct RemotePlayer {
+    socket: PathBuf,
+    status: RefCell<Status>,
+    fetched: Cell<Instant>,
+    connected: Cell<bool>,
+}
+
+impl RemotePlayer {
+    /// Connect to the running daemon, if there is one
+    pub fn connect() -> Result<Self, String> {
+        let socket = super::socket_path()?;
+        let response = request_at(&socket, &Request::Status)?;
+        Ok(Self {
+            socket,
+            status: RefCell::new(response.status.unwrap_or_default()),
+            fetched: Cell::new(Instant::now()),
+            connected: Cell::new(true),
+        })
+    }
+
+    /// The daemon's playback status, asked for again once it is a moment old,
+    /// or after a few seconds while the daemon isn't answering
+    pub fn status(&self) -> Ref<'_, Status> {
+        let max_age = if self.connected.get() { STATUS_MAX_AGE } else { RECONNECT_INTERVAL };
+        if self.fetched.get().elapsed() >= max_age {
+            // Failures are logged by send and leave the daemon looking stopped
+            if self.send(&Request::Status).is_err() {
+                *self.status.borrow_mut() = Status::default();
+            }
+        }
+        self.status.borrow()
+    }
+
+    /// Whether the daemon answered the last request
+    pub fn is_connected(&self) -> bool {
+        self.connected.get()
+    }
+
+    /// Whether the daemon is still listening, even if it isn't answering
+    pub fn is_running(&self) -> bool {
+        super::is_running(&self.socket)
+    }
+
+    /// Have the daemon apply and save library changes made in the TUI
+    pub fn update(&self, changes: Vec<LibraryChange>) -> Result<(), String> {
+        self.send(&Request::Update { changes })
+    }
+
+    fn send(&self, request: &Request) -> Result<(), String> {
+        self.fetched.set(Instant::now());
+        let response = match request_at(&self.socket, request) {
+            Ok(response) => response,
+            Err(e) => {
+                if self.connected.replace(false) {
+                    log::error!("Lost the daemon: {}", e);
+                }
+                return Err(e);
+            }
+        };
+        if !self.connected.replace(true) {
+            log::info!("Reconnected to the daemon");
+        }
+
+        if let Some(status) = response.status {
+            *self.status.borrow_mut() = status;
+        }
+        match response.error {
+            Some(e) if !response.ok => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// Send a request whose failure has nowhere to go but the log
+    fn send_logged(&self, request: &Request) {
+        if let Err(e) = self.send(request) {
+            log::error!("Daemon request failed: {}", e);
+        }
+    }
+
+    fn seek(&self, position_secs: Option<f64>, offset_secs: Option<f64>) -> Result<(), String> {
+        self.send(&Request::Seek { position_secs, offset_secs })
+    }
+}
+
+impl Playback for RemotePlayer {
+    fn play(&mut self, url: &str, start_secs: u64) -> Result<(), String> {
+        self.send(&Request::Play {
+            audio_url: url.to_string(),
+            position_secs: Some(start_secs),
+        })
+    }
+
+    fn pause(&self) {
+        self.send_logged(&Request::Pause);
+    }
+
+    fn resume(&self) {
+        self.send_logged(&Request::Resume);
+    }
+
+    fn stop(&mut self) {
+        self.send_logged(&Request::Stop);
+    }
+
+    fn is_playing(&self) -> bool {
+        self.status().state == PlayerState::Playing
+    }
+
+    fn is_finished(&self) -> bool {
+        false
+    }
+
+    fn is_paused(&self) -> bool {
+        self.status().state == PlayerState::Paused
+    }
+
+    fn seek_forward(&self, seconds: u64) -> Result<(), String> {
+        self.seek(None, Some(seconds as f64))
+    }
+
+    fn seek_backward(&self, seconds: u64) -> Result<(), String> {
+        self.seek(None, Some(-(seconds as f64)))
+    }
+
+    fn seek_to(&self, position: Duration) -> Result<(), String> {
+        self.seek(Some(position.as_secs_f64()), None)
+    }
+
+    /// The last reported position, moved on by the time since it was reported
+    fn get_position(&self) -> Duration {
+        let status = self.status();
+        let mut secs = status.position_secs;
+        if status.state == PlayerState::Playing {
+            secs += self.fetched.get().elapsed().as_secs_f64() * status.speed as f64;
+        }
+        Duration::from_secs_f64(secs.max(0.0))
+    }
+
+    fn get_temp_file_path(&self) -> Option<PathBuf> {
+        self.status().temp_path.clone()
+    }
+
+    fn set_speed(&self, speed: f32) {
+        self.send_logged(&Request::Speed { speed });
+    }
+
+    fn get_speed(&self) -> f32 {
+        self.status().speed
+    }
+
+    fn set_volume(&mut self, volume: f32) {
+        self.send_logged(&Request::Volume { volume });
+    }
+
+    fn get_volume(&self) -> f32 {
+        self.status().volume
+    }
+
+    fn is_buffering(&self) -> bool {
+        self.status().state == PlayerState::Buffering
+    }
+
+    fn buffer_status(&self) -> BufferStatus {
+        self.status().buffer
+    }
+
+    fn remote(&self) -> Option<&RemotePlayer> {
+        Some(self)
+    }
+}
@@ -0,0 +1,613 @@
+//! Headless playback for `shellcast daemon`. The daemon owns the player, the
+//! podcast library and the refresh and download workers, and takes requests
+//! from any number of clients over a Unix socket. See `protocol` for the
+//! messages. While it runs, clients send their library changes here rather
+//! than saving the files.
+
+pub mod client;
+pub mod protocol;
+
+use crate::app::{App, AppEvent, Episode, Podcast};
+use crate::chapters::skip::SkipRules;
+use crate::config::Config;
+use crate::download;
+use crate::history::HistoryEventKind;
+use crate::library::{self, LibraryChange};
+use crate::persistence;
+use crate::playback::{Playback, Player};
+use protocol::{PlayerState, QueueEntry, Request, Response, Status};
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+const SOCKET_FILE: &str = "shellcast.sock";
+pub const LOG_FILE: &str = "daemon.log";
+
+/// How long the daemon waits for a request before checking on playback
+const TICK: Duration = Duration::from_millis(200);
+/// How often the playing position is saved, so little is lost if the daemon is killed
+const POSITION_SAVE_INTERVAL: Duration = Duration::from_secs(30);
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+pub fn socket_path() -> Result<PathBuf, String> {
+    persistence::get_data_file(SOCKET_FILE)
+}
+
+pub fn is_running(socket: &Path) -> bool {
+    UnixStream::connect(socket).is_ok()
+}
+
+enum Message {
+    Request(Request, mpsc::Sender<Response>),
+    /// The feed fetched for a subscribe request
+    Subscribed(Result<Podcast, String>, mpsc::Sender<Response>),
+    /// Where the episode with the given audio URL was downloaded to
+    Downloaded(String, Result<PathBuf, String>, mpsc::Sender<Response>),
+}
+
+/// Listen on the socket, replacing one left behind but not a daemon that is still running
+fn bind(socket: &Path) -> Result<UnixListener, String> {
+    if socket.exists() {
+        if is_running(socket) {
+            return Err(format!("A daemon is already listening on {}", socket.display()));
+        }
+        fs::remove_file(socket).map_err(|e| format!("Failed to remove stale socket: {}", e))?;
+    }
+
+    let listener = UnixListener::bind(socket)
+        .map_err(|e| format!("Failed to listen on {}: {}", socket.display(), e))?;
+    fs::set_permissions(socket, fs::Permissions::from_mode(0o600))
+        .map_err(|e| format!("Failed to restrict socket permissions: {}", e))?;
+    Ok(listener)
+}
+
+/// Accept clients, each on its own thread, and pass their requests on to the daemon loop
+fn serve(listener: UnixListener, messages: mpsc::Sender<Message>) {
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                let messages = messages.clone();
+                std::thread::spawn(move || handle_client(stream, messages));
+            }
+            Err(e) => log::error!("Failed to accept client: {}", e),
+        }
+    }
+}
+
+fn handle_client(stream: UnixStream, messages: mpsc::Sender<Message>) {
+    let mut writer = match stream.try_clone() {
+        Ok(writer) => writer,
+        Err(e) => {
+            log::error!("Failed to set up client connection: {}", e);
+            return;
+        }
+    };
+
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let request = serde_json::from_str::<Request>(&line);
+        let shutdown = matches!(request, Ok(Request::Shutdown));
+        let response = match request {
+            Ok(request) => {
+                let (reply, response) = mpsc::channel();
+                if messages.send(Message::Request(request, reply)).is_err() {
+                    break;
+                }
+                response
+                    .recv()
+                    .unwrap_or_else(|_| Response::error("The daemon stopped before answering"))
+            }
+            Err(e) => Response::error(format!("Invalid request: {}", e)),
+        };
+
+        let json = serde_json::to_string(&response).unwrap_or_else(|e| {
+            format!(r#"{{"ok":false,"error":"Failed to serialize response: {}"}}"#, e)
+        });
+        if writeln!(writer, "{}", json).is_err() {
+            break;
+        }
+
+        // Everything has been saved by now, so exit once the client has its answer
+        if shutdown && response.ok {
+            log::info!("Daemon shut down");
+            std::process::exit(0);
+        }
+    }
+}
+
+/// Start `shellcast daemon --foreground` detached from the terminal, and wait until it is listening
+pub fn spawn() -> Result<PathBuf, String> {
+    let socket = socket_path()?;
+    if is_running(&socket) {
+        return Err(format!("A daemon is already listening on {}", socket.display()));
+    }
+
+    let exe = std::env::current_exe().map_err(|e| format!("Failed to find the shellcast binary: {}", e))?;
+    let mut child = Command::new(exe)
+        .args(["daemon", "--foreground"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        // A process group of its own, so closing the terminal doesn't hang it up
+        .process_group(0)
+        .spawn()
+        .map_err(|e| format!("Failed to start the daemon: {}", e))?;
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while Instant::now() < deadline {
+        if is_running(&socket) {
+            return Ok(socket);
+        }
+        if let Ok(Some(status)) = child.try_wait() {
+            let log = persistence::get_data_file(LOG_FILE)?;
+            return Err(format!("The daemon exited ({}), see {}", status, log.display()));
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    Err("The daemon didn't start listening in time".to_string())
+}
+
+/// Run the daemon in this process until a client asks it to shut down
+pub fn run() -> Result<(), String> {
+    // Open the audio output first, so clients only find a daemon that can play
+    let player = Player::new()?;
+    let socket = socket_path()?;
+    let listener = bind(&socket)?;
+    log::info!("Daemon listening on {}", socket.display());
+
+    let config = Config::load();
+    let mut app = App::new();
+    app.podcasts = persistence::load_podcasts()?;
+    app.queue = persistence::load_queue().unwrap_or_default();
+    app.library_modified = persistence::library_modified();
+    app.skip_rules = SkipRules::from_config(&config.chapters);
+
+    let (events, event_rx) = mpsc::channel::<AppEvent>();
+    let (messages, message_rx) = mpsc::channel::<Message>();
+    let clients = messages.clone();
+    std::thread::spawn(move || serve(listener, clients));
+
+    let mut daemon = Daemon {
+        app,
+        player,
+        socket,
+        events,
+        messages,
+        last_position_save: Instant::now(),
+        last_refresh: Instant::now(),
+    };
+
+    loop {
+        match message_rx.recv_timeout(TICK) {
+            Ok(Message::Request(request, reply)) => daemon.handle(request, reply),
+            Ok(Message::Subscribed(result, reply)) => {
+                let _ = reply.send(daemon.respond(|d| d.subscribed(result)));
+            }
+            Ok(Message::Downloaded(audio_url, result, reply)) => {
+                let _ = reply.send(daemon.respond(|d| d.downloaded(audio_url, result)));
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => return Err("Stopped accepting clients".to_string()),
+        }
+        while let Ok(event) = event_rx.try_recv() {
+            crate::handle_app_event(&mut daemon.app, event, &daemon.events);
+        }
+        daemon.tick();
+    }
+}
+
+/// The TUI's state and player, driven by requests instead of keys
+struct Daemon {
+    app: App,
+    player: Player,
+    socket: PathBuf,
+    /// Background work reporting back, as in the TUI
+    events: mpsc::Sender<AppEvent>,
+    messages: mpsc::Sender<Message>,
+    last_position_save: Instant,
+    last_refresh: Instant,
+}
+
+/// An episode that isn't in the library, e.g. one from a Browse preview
+fn unlisted_episode(audio_url: &str) -> Episode {
+    Episode {
+        title: audio_url.to_string(),
+        audio_url: audio_url.to_string(),
+        ..Default::default()
+    }
+}
+
+impl Daemon {
+    fn handle(&mut self, request: Request, reply: mpsc::Sender<Response>) {
+        let response = match request {
+            Request::Play { audio_url, position_secs } => self.respond(|d| d.play(&audio_url, position_secs)),
+            Request::Pause => self.respond(Self::pause),
+            Request::Resume => self.respond(Self::resume),
+            Request::Toggle => self.respond(|d| if d.player.is_paused() { d.resume() } else { d.pause() }),
+            Request::Stop => self.respond(|d| {
+                crate::stop_playback(&mut d.app, &mut d.player);
+                Ok(())
+            }),
+            Request::Seek { position_secs, offset_secs } => self.respond(|d| d.seek(position_secs, offset_secs)),
+            Request::Speed { speed } => self.respond(|d| d.set_speed(speed)),
+            Request::Volume { volume } => self.respond(|d| d.set_volume(volume)),
+            Request::Status | Request::Queue => self.respond(|_| Ok(())),
+            Request::Enqueue { audio_url } => self.respond(|d| d.enqueue(&audio_url)),
+            Request::Next => self.respond(Self::next),
+            Request::Subscribe { url } => {
+                // Answered once the feed has been fetched, without holding up other clients
+                let messages = self.messages.clone();
+                std::thread::spawn(move || {
+                    let result = crate::feed::fetch_and_parse(&url);
+                    let _ = messages.send(Message::Subscribed(result, reply));
+                });
+                return;
+            }
+            Request::Unsubscribe { url } => self.respond(|d| d.unsubscribe(&url)),
+            Request::MarkPlayed { audio_url, played } => self.respond(|d| d.mark_played(&audio_url, played)),
+            Request::Update { changes } => self.respond(|d| d.update(changes)),
+            Request::Download { audio_url } => {
+                if let Err(e) = self.download(audio_url, reply.clone()) {
+                    let _ = reply.send(Response::error(e));
+                }
+                return;
+            }
+            Request::Refresh => self.respond(|d| {
+                crate::refresh_all_feeds(&mut d.app, &d.events);
+                d.last_refresh = Instant::now();
+                Ok(())
+            }),
+            Request::Shutdown => self.respond(Self::shutdown),
+        };
+        let _ = reply.send(response);
+    }
+
+    /// Run a request, answering with the status message it left and the new status
+    fn respond(&mut self, f: impl FnOnce(&mut Self) -> Result<(), String>) -> Response {
+        match f(self) {
+            Ok(()) => Response {
+                message: self.app.status_message.take(),
+                queue: Some(self.queue()),
+                ..Response::ok(self.status())
+            },
+            Err(e) => {
+                self.app.status_message = None;
+                Response::error(e)
+            }
+        }
+    }
+
+    fn status(&self) -> Status {
+        let (app, player) = (&self.app, &self.player);
+        let state = if app.playback.url.is_none() {
+            PlayerState::Stopped
+        } else if player.is_buffering() {
+            PlayerState::Buffering
+        } else if player.is_paused() {
+            PlayerState::Paused
+        } else {
+            PlayerState::Playing
+        };
+
+        Status {
+            state,
+            podcast_title: app.playing_episode_indices().map(|(p, _)| app.podcasts[p].title.clone()),
+            episode_title: app.playing_episode().map(|e| e.title.clone()),
+            audio_url: app.playback.url.clone(),
+            position_secs: if app.playback.url.is_some() { player.get_position().as_secs_f64() } else { 0.0 },
+            duration_secs: app.playback.duration_secs,
+            speed: player.get_speed(),
+            volume: player.get_volume(),
+            buffer: player.buffer_status(),
+            temp_path: player.get_temp_file_path(),
+        }
+    }
+
+    fn queue(&self) -> Vec<QueueEntry> {
+        self.app
+            .queue
+            .iter()
+            .filter_map(|episode_ref| {
+                let (p, e) = self.app.find_episode(episode_ref)?;
+                let podcast = &self.app.podcasts[p];
+                Some(QueueEntry {
+                    podcast_title: podcast.title.clone(),
+                    episode_title: podcast.episodes[e].title.clone(),
+                    audio_url: episode_ref.audio_url.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Record and save where the playing episode was left before playing another
+    fn leave_episode(&mut self, next_url: &str) {
+        if self.app.playback.start.is_none() {
+            return;
+        }
+        let kind = if self.app.playback.url.as_deref() == Some(next_url) {
+            HistoryEventKind::Stop
+        } else {
+            HistoryEventKind::Skip
+        };
+        crate::record_history(&self.app, &self.player, kind, None);
+        crate::save_playing_position(&mut self.app, &self.player);
+    }
+
+    fn play(&mut self, audio_url: &str, position_secs: Option<u64>) -> Result<(), String> {
+        let mut episode = self
+            .app
+            .podcasts
+            .iter()
+            .flat_map(|p| &p.episodes)
+            .find(|e| e.audio_url == audio_url)
+            .cloned()
+            .unwrap_or_else(|| unlisted_episode(audio_url));
+        if let Some(position_secs) = position_secs {
+            episode.position_secs = position_secs;
+        }
+
+        self.leave_episode(audio_url);
+        crate::start_playback(&mut self.app, &mut self.player, &episode, &self.events);
+        if self.app.playback.url.as_deref() != Some(audio_url) {
+            return Err(self.app.status_message.take().unwrap_or_else(|| "Failed to start playback".to_string()));
+        }
+        self.last_position_save = Instant::now();
+        Ok(())
+    }
+
+    fn pause(&mut self) -> Result<(), String> {
+        if !self.player.is_playing() {
+            return Err("Nothing is playing".to_string());
+        }
+        crate::pause_playback(&mut self.app, &self.player);
+        crate::save_playing_position(&mut self.app, &self.player);
+        Ok(())
+    }
+
+    fn resume(&mut self) -> Result<(), String> {
+        if !self.player.is_paused() {
+            return Err("Nothing is paused".to_string());
+        }
+        crate::resume_playback(&mut self.app, &self.player);
+        Ok(())
+    }
+
+    fn seek(&mut self, position_secs: Option<f64>, offset_secs: Option<f64>) -> Result<(), String> {
+        if self.app.playback.start.is_none() {
+            return Err("Nothing is playing".to_string());
+        }
+        let from = self.player.get_position().as_secs_f64();
+        let target = match (position_secs, offset_secs) {
+            (Some(position), _) => position,
+            (None, Some(offset)) => from + offset,
+            (None, None) => return Err("Seek needs position_secs or offset_secs".to_string()),
+        };
+
+        self.player.seek_to(Duration::from_secs_f64(target.max(0.0)))?;
+        crate::record_history(&self.app, &self.player, HistoryEventKind::Seek, Some(from as u64));
+        Ok(())
+    }
+
+    fn set_speed(&mut self, speed: f32) -> Result<(), String> {
+        if self.app.playback.start.is_none() {
+            return Err("Nothing is playing".to_string());
+        }
+        if !(0.5..=3.0).contains(&speed) {
+            return Err("Speed must be between 0.5 and 3.0".to_string());
+        }
+        self.player.set_speed(speed);
+        Ok(())
+    }
+
+    fn set_volume(&mut self, volume: f32) -> Result<(), String> {
+        if !(0.0..=1.5).contains(&volume) {
+            return Err("Volume must be between 0.0 and 1.5".to_string());
+        }
+        self.player.set_volume(volume);
+        Ok(())
+    }
+
+    fn enqueue(&mut self, audio_url: &str) -> Result<(), String> {
+        let (p, e) = self
+            .app
+            .podcasts
+            .iter()
+            .enumerate()
+            .find_map(|(p, podcast)| podcast.episodes.iter().position(|e| e.audio_url == audio_url).map(|e| (p, e)))
+            .ok_or_else(|| format!("Not an episode of a subscribed podcast: {}", audio_url))?;
+        self.app.enqueue(p, e);
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<(), String> {
+        let (p, e) = self.app.pop_queue().ok_or_else(|| "The queue is empty".to_string())?;
+        let audio_url = self.app.podcasts[p].episodes[e].audio_url.clone();
+        self.leave_episode(&audio_url);
+        crate::start_episode(&mut self.app, &mut self.player, p, e, &self.events);
+        Ok(())
+    }
+
+    fn subscribed(&mut self, result: Result<Podcast, String>) -> Result<(), String> {
+        let podcast = result?;
+        let title = podcast.title.clone();
+        self.app.status_message = Some(if self.app.add_podcast(podcast) {
+            format!("Added: {}", title)
+        } else {
+            format!("Already subscribed: {}", title)
+        });
+        Ok(())
+    }
+
+    fn unsubscribe(&mut self, url: &str) -> Result<(), String> {
+        let index = self
+            .app
+            .find_podcast_by_feed(url)
+            .ok_or_else(|| format!("Not subscribed: {}", url))?;
+        let podcast = &self.app.podcasts[index];
+        self.app.status_message = Some(format!("Removed: {}", podcast.title));
+        let url = podcast.url.clone();
+        self.update(vec![LibraryChange::Unsubscribe { url }])
+    }
+
+    fn mark_played(&mut self, audio_url: &str, played: bool) -> Result<(), String> {
+        let mut episode = self
+            .app
+            .podcasts
+            .iter()
+            .flat_map(|p| &p.episodes)
+            .find(|e| e.audio_url == audio_url)
+            .cloned()
+            .ok_or_else(|| format!("Not an episode of a subscribed podcast: {}", audio_url))?;
+        episode.touch();
+        self.app.status_message = Some(format!(
+            "Marked {}: {}",
+            if played { "played" } else { "unplayed" },
+            episode.title
+        ));
+        self.update(vec![LibraryChange::EpisodeState {
+            audio_url: episode.audio_url,
+            played,
+            position_secs: episode.position_secs,
+            last_played: episode.last_played,
+            state_changed: episode.state_changed,
+        }])
+    }
+
+    /// Download an episode on a worker thread, answering once it is saved.
+    /// Episodes that are already downloaded are answered straight away.
+    fn download(&mut self, audio_url: String, reply: mpsc::Sender<Response>) -> Result<(), String> {
+        let (podcast, episode) = self
+            .app
+            .podcasts
+            .iter()
+            .find_map(|p| p.episodes.iter().find(|e| e.audio_url == audio_url).map(|e| (p, e)))
+            .ok_or_else(|| format!("Not an episode of a subscribed podcast: {}", audio_url))?;
+        if let Some(path) = download::local_file(episode) {
+            let message = format!("Already downloaded: {}", path.display());
+            let _ = reply.send(self.respond(|d| {
+                d.app.status_message = Some(message);
+                Ok(())
+            }));
+            return Ok(());
+        }
+
+        let dir = Config::load().downloads.directory()?;
+        let (title, episode) = (podcast.title.clone(), episode.clone());
+        let messages = self.messages.clone();
+        std::thread::spawn(move || {
+            let result = download::download(&title, &episode, &dir);
+            let _ = messages.send(Message::Downloaded(audio_url, result, reply));
+        });
+        Ok(())
+    }
+
+    fn downloaded(&mut self, audio_url: String, result: Result<PathBuf, String>) -> Result<(), String> {
+        let path = result?;
+        self.app.status_message = Some(format!("Downloaded: {}", path.display()));
+        self.update(vec![LibraryChange::Downloaded {
+            audio_url,
+            path: Some(path.to_string_lossy().into_owned()),
+        }])
+    }
+
+    /// Apply library changes and save them before answering, so the client can
+    /// count on them being on disk
+    fn update(&mut self, changes: Vec<LibraryChange>) -> Result<(), String> {
+        library::apply(&mut self.app, changes);
+        crate::save_library(&mut self.app)
+    }
+
+    fn shutdown(&mut self) -> Result<(), String> {
+        crate::stop_playback(&mut self.app, &mut self.player);
+        crate::save_library(&mut self.app)?;
+        fs::remove_file(&self.socket).map_err(|e| format!("Failed to remove socket: {}", e))
+    }
+
+    /// Keep playback going between requests: move through the queue, skip
+    /// chapters, save the position now and then and refresh the feeds hourly
+    fn tick(&mut self) {
+        if self.app.playback.start.is_some() && self.player.is_finished() {
+            crate::handle_playback_finished(&mut self.app, &mut self.player, &self.events);
+        }
+
+        if self.player.is_playing() {
+            crate::skip_matching_chapter(&mut self.app, &self.player);
+            if self.last_position_save.elapsed() >= POSITION_SAVE_INTERVAL {
+                crate::save_playing_position(&mut self.app, &self.player);
+                self.last_position_save = Instant::now();
+            }
+        }
+
+        if self.last_refresh.elapsed() >= REFRESH_INTERVAL {
+            crate::refresh_all_feeds(&mut self.app, &self.events);
+            self.last_refresh = Instant::now();
+        }
+
+        crate::reload_changed_library(&mut self.app);
+        if self.app.needs_save {
+            if let Err(e) = crate::save_library(&mut self.app) {
+                log::error!("Save error: {}", e);
+            }
+        }
+        if let Some(message) = self.app.status_message.take() {
+            log::info!("{}", message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clients_share_the_daemon_loop() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join(SOCKET_FILE);
+        let listener = bind(&socket).unwrap();
+        assert!(bind(&socket).is_err(), "a second daemon must not take over the socket");
+
+        let (messages, message_rx) = mpsc::channel();
+        std::thread::spawn(move || serve(listener, messages));
+        // Answers every request with how many it has answered so far
+        std::thread::spawn(move || {
+            for (answered, message) in message_rx.iter().enumerate() {
+                if let Message::Request(_, reply) = message {
+                    let status = Status { position_secs: answered as f64, ..Status::default() };
+                    let _ = reply.send(Response::ok(status));
+                }
+            }
+        });
+
+        let first = client::request_at(&socket, &Request::Status).unwrap();
+        let second = client::request_at(&socket, &Request::Pause).unwrap();
+        assert!(first.ok && second.ok);
+        assert_eq!(second.status.unwrap().position_secs, 1.0);
+
+        let mut raw = UnixStream::connect(&socket).unwrap();
+        writeln!(raw, r#"{{"cmd":"dance"}}"#).unwrap();
+        let mut line = String::new();
+        BufReader::new(raw).read_line(&mut line).unwrap();
+        let response: Response = serde_json::from_str(&line).unwrap();
+        assert!(!response.ok);
+        assert!(response.error.unwrap().starts_with("Invalid request"));
+
+        // A daemon that never answers doesn't hold a client up for long
+        let hung = dir.path().join("hung.sock");
+        let _listener = bind(&hung).unwrap();
+        let started = Instant::now();
+        assert!(client::request_at(&hung, &Request::Status).is_err());
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,201 @@
+//! Messages exchanged with the daemon: one JSON object per line in each direction,
+//! a response for every request, in order. Documented in the README.
+
+use crate::library::LibraryChange;
+use crate::playback::BufferStatus;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+pub enum Request {
+    /// Play an episode, from its saved position unless `position_secs` is given
+    Play {
+        audio_url: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        position_secs: Option<u64>,
+    },
+    Pause,
+    Resume,
+    /// Pause if playing, otherwise resume
+    Toggle,
+    Stop,
+    /// Seek to `position_secs`, or by `offset_secs` (negative to go back)
+    Seek {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        position_secs: Option<f64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        offset_secs: Option<f64>,
+    },
+    Speed { speed: f32 },
+    Volume { volume: f32 },
+    Status,
+    /// List the queued episodes
+    Queue,
+    /// Add a subscribed episode to the end of the queue
+    Enqueue { audio_url: String },
+    /// Skip to the next queued episode
+    Next,
+    /// Subscribe to a feed, answering once it has been fetched
+    Subscribe { url: String },
+    /// Remove a podcast and its queued episodes
+    Unsubscribe { url: String },
+    MarkPlayed { audio_url: String, played: bool },
+    /// Download an episode for offline listening, answering once it is saved
+    Download { audio_url: String },
+    /// Library changes made by a client, applied and saved by the daemon
+    Update { changes: Vec<LibraryChange> },
+    /// Refresh every feed in the background
+    Refresh,
+    /// Save the playing position and exit
+    Shutdown,
+}
+
+impl Request {
+    /// Parse `shellcast ctl` arguments, e.g. `seek -30` or `play <audio-url> 120`
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let arg = |i: usize, name: &str| {
+            args.get(i)
+                .cloned()
+                .ok_or_else(|| format!("'{}' needs {}", args[0], name))
+        };
+        let number = |i: usize, name: &str| -> Result<f64, String> {
+            let value = arg(i, name)?;
+            value.parse().map_err(|_| format!("Invalid {}: {}", name, value))
+        };
+
+        let Some(cmd) = args.first() else {
+            return Err("Missing command".to_string());
+        };
+        let request = match cmd.as_str() {
+            "play" => Self::Play {
+                audio_url: arg(1, "an audio URL")?,
+                position_secs: match args.get(2) {
+                    Some(_) => Some(number(2, "position")? as u64),
+                    None => None,
+                },
+            },
+            "pause" => Self::Pause,
+            "resume" => Self::Resume,
+            "toggle" => Self::Toggle,
+            "stop" => Self::Stop,
+            // "+30" and "-30" are relative, "90" is a position
+            "seek" => {
+                let target = arg(1, "a position or +/- offset in seconds")?;
+                let secs = number(1, "position")?;
+                if target.starts_with(['+', '-']) {
+                    Self::Seek { position_secs: None, offset_secs: Some(secs) }
+                } else {
+                    Self::Seek { position_secs: Some(secs), offset_secs: None }
+                }
+            }
+            "speed" => Self::Speed { speed: number(1, "speed")? as f32 },
+            "volume" => Self::Volume { volume: number(1, "volume")? as f32 },
+            "status" => Self::Status,
+            "queue" => Self::Queue,
+            "enqueue" => Self::Enqueue { audio_url: arg(1, "an audio URL")? },
+            "next" => Self::Next,
+            "subscribe" => Self::Subscribe { url: arg(1, "a feed URL")? },
+            "unsubscribe" => Self::Unsubscribe { url: arg(1, "a feed URL")? },
+            "mark-played" => Self::MarkPlayed { audio_url: arg(1, "an audio URL")?, played: true },
+            "mark-unplayed" => Self::MarkPlayed { audio_url: arg(1, "an audio URL")?, played: false },
+            "download" => Self::Download { audio_url: arg(1, "an audio URL")? },
+            "refresh" => Self::Refresh,
+            "shutdown" => Self::Shutdown,
+            other => return Err(format!("Unknown command '{}'", other)),
+        };
+        Ok(request)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlayerState {
+    #[default]
+    Stopped,
+    /// Started, but not buffered enough to play yet
+    Buffering,
+    Playing,
+    Paused,
+}
+
+/// What the daemon is playing
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Status {
+    pub state: PlayerState,
+    pub podcast_title: Option<String>,
+    pub episode_title: Option<String>,
+    pub audio_url: Option<String>,
+    pub position_secs: f64,
+    /// 0 when the feed doesn't give a duration
+    pub duration_secs: u64,
+    pub speed: f32,
+    pub volume: f32,
+    pub buffer: BufferStatus,
+    /// Where the episode is being downloaded to while it plays
+    pub temp_path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueueEntry {
+    pub podcast_title: String,
+    pub episode_title: String,
+    pub audio_url: String,
+}
+
+/// Answer to a request. `status` is included in every successful response.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Response {
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<Status>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queue: Option<Vec<QueueEntry>>,
+}
+
+impl Response {
+    pub fn ok(status: Status) -> Self {
+        Self {
+            ok: true,
+            status: Some(status),
+            ..Self::default()
+        }
+    }
+
+    pub fn error(error: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            error: Some(error.into()),
+            ..Self::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_ctl_args_to_json() {
+        let json = |line: &str| serde_json::to_string(&Request::from_args(&args(line)).unwrap()).unwrap();
+        assert_eq!(json("pause"), r#"{"cmd":"pause"}"#);
+        assert_eq!(json("seek -30"), r#"{"cmd":"seek","offset_secs":-30.0}"#);
+        assert_eq!(json("seek 90"), r#"{"cmd":"seek","position_secs":90.0}"#);
+        assert_eq!(json("play https://example.com/a.mp3"), r#"{"cmd":"play","audio_url":"https://example.com/a.mp3"}"#);
+        assert!(Request::from_args(&args("seek")).is_err());
+        assert!(Request::from_args(&args("speed fast")).is_err());
+
+        let request: Request = serde_json::from_str(r#"{"cmd":"play","audio_url":"a.mp3","position_secs":120}"#).unwrap();
+        assert!(matches!(request, Request::Play { audio_url, position_secs: Some(120) } if audio_url == "a.mp3"));
+        assert_eq!(json("mark-unplayed a.mp3"), r#"{"cmd":"mark_played","audio_url":"a.mp3","played":false}"#);
+        assert!(serde_json::from_str::<Request>(r#"{"cmd":"dance"}"#).is_err());
+    }
+}
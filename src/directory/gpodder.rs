@@ -0,0 +1,117 @@
+use super::{http_client, Category, DirectoryProvider};
+use crate::browse::SearchResult;
+use reqwest::blocking::Client;
+
+pub const GPODDER_URL: &str = "https://gpodder.net";
+
+/// gpodder.net directory (free, open, no auth required)
+pub struct GpodderDirectory {
+    client: Client,
+    base: String,
+}
+
+impl GpodderDirectory {
+    pub fn new(base: &str) -> Result<Self, String> {
+        Ok(Self {
+            client: http_client()?,
+            base: base.trim_end_matches('/').to_string(),
+        })
+    }
+
+    fn get_json(&self, url: &str) -> Result<serde_json::Value, String> {
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .map_err(|e| format!("Request failed: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("gpodder.net returned status {}", response.status()));
+        }
+
+        response
+            .json()
+            .map_err(|e| format!("Failed to parse response: {}", e))
+    }
+
+    fn get_podcasts(&self, url: &str) -> Result<Vec<SearchResult>, String> {
+        let json = self.get_json(url)?;
+        let items = json.as_array().ok_or("Invalid response format")?;
+        Ok(items.iter().filter_map(parse_podcast).collect())
+    }
+}
+
+/// Parse a podcast object as returned by search, toplist, tag and data endpoints
+pub fn parse_podcast(item: &serde_json::Value) -> Option<SearchResult> {
+    Some(SearchResult {
+        title: item["title"].as_str()?.to_string(),
+        author: item["author"].as_str().unwrap_or("Unknown").to_string(),
+        description: item["description"].as_str().unwrap_or("").to_string(),
+        feed_url: item["url"].as_str()?.to_string(),
+        artwork_url: item["logo_url"].as_str().map(String::from),
+        subscribers: item["subscribers"].as_u64().unwrap_or(0),
+        website: item["website"].as_str().filter(|s| !s.is_empty()).map(String::from),
+    })
+}
+
+impl DirectoryProvider for GpodderDirectory {
+    fn name(&self) -> &'static str {
+        "gpodder"
+    }
+
+    fn search(&self, query: &str, _limit: usize) -> Result<Vec<SearchResult>, String> {
+        self.get_podcasts(&format!("{}/search.json?q={}", self.base, urlencoding::encode(query)))
+    }
+
+    fn toplist(&self, limit: usize) -> Result<Vec<SearchResult>, String> {
+        let count = limit.clamp(1, 100); // API accepts 1-100
+        self.get_podcasts(&format!("{}/toplist/{}.json", self.base, count))
+    }
+
+    fn categories(&self, limit: usize) -> Result<Vec<Category>, String> {
+        let json = self.get_json(&format!("{}/api/2/tags/{}.json", self.base, limit.max(1)))?;
+        let items = json.as_array().ok_or("Invalid tags format")?;
+        Ok(items
+            .iter()
+            .filter_map(|item| {
+                let tag = item["tag"].as_str()?;
+                let title = item["title"].as_str().unwrap_or(tag);
+                Some(Category::new(self.name(), tag, title))
+            })
+            .collect())
+    }
+
+    fn category_podcasts(&self, id: &str, limit: usize) -> Result<Vec<SearchResult>, String> {
+        self.get_podcasts(&format!(
+            "{}/api/2/tag/{}/{}.json",
+            self.base,
+            urlencoding::encode(id),
+            limit.max(1)
+        ))
+    }
+
+    fn lookup(&self, feed_url: &str) -> Result<Option<SearchResult>, String> {
+        let url = format!(
+            "{}/api/2/data/podcast.json?url={}",
+            self.base,
+            urlencoding::encode(feed_url)
+        );
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .map_err(|e| format!("Request failed: {}", e))?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(format!("gpodder.net returned status {}", response.status()));
+        }
+
+        let json: serde_json::Value = response
+            .json()
+            .map_err(|e| format!("Failed to parse response: {}", e))?;
+        Ok(parse_podcast(&json))
+    }
+}
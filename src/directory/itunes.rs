@@ -0,0 +1,164 @@
+use super::{http_client, Category, DirectoryProvider};
+use crate::browse::SearchResult;
+use reqwest::blocking::Client;
+
+const SEARCH_URL: &str = "https://itunes.apple.com/search";
+const LOOKUP_URL: &str = "https://itunes.apple.com/lookup";
+const CHARTS_URL: &str = "https://rss.applemarketingtools.com/api/v2";
+
+/// Top-level Apple Podcasts genres as (genre ID, name)
+const GENRES: &[(&str, &str)] = &[
+    ("1301", "Arts"),
+    ("1321", "Business"),
+    ("1303", "Comedy"),
+    ("1304", "Education"),
+    ("1483", "Fiction"),
+    ("1511", "Government"),
+    ("1512", "Health & Fitness"),
+    ("1487", "History"),
+    ("1305", "Kids & Family"),
+    ("1502", "Leisure"),
+    ("1310", "Music"),
+    ("1489", "News"),
+    ("1314", "Religion & Spirituality"),
+    ("1533", "Science"),
+    ("1324", "Society & Culture"),
+    ("1545", "Sports"),
+    ("1318", "Technology"),
+    ("1488", "True Crime"),
+    ("1309", "TV & Film"),
+];
+
+/// iTunes Search API (no auth required)
+pub struct ItunesDirectory {
+    client: Client,
+    country: String,
+}
+
+impl ItunesDirectory {
+    pub fn new(country: &str) -> Result<Self, String> {
+        Ok(Self {
+            client: http_client()?,
+            country: country.to_lowercase(),
+        })
+    }
+
+    fn get_json(&self, url: &str) -> Result<serde_json::Value, String> {
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .map_err(|e| format!("Request failed: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("iTunes returned status {}", response.status()));
+        }
+
+        response
+            .json()
+            .map_err(|e| format!("Failed to parse response: {}", e))
+    }
+
+    /// Fetch `results` from a search or lookup URL
+    fn get_podcasts(&self, url: &str) -> Result<Vec<SearchResult>, String> {
+        let json = self.get_json(url)?;
+        let items = json["results"].as_array().ok_or("Invalid response format")?;
+        Ok(items.iter().filter_map(parse_podcast).collect())
+    }
+}
+
+/// Parse a search/lookup result. iTunes has no descriptions or subscriber counts.
+fn parse_podcast(item: &serde_json::Value) -> Option<SearchResult> {
+    Some(SearchResult {
+        title: item["collectionName"].as_str()?.to_string(),
+        author: item["artistName"].as_str().unwrap_or("Unknown").to_string(),
+        description: String::new(),
+        feed_url: item["feedUrl"].as_str()?.to_string(),
+        artwork_url: item["artworkUrl600"]
+            .as_str()
+            .or_else(|| item["artworkUrl100"].as_str())
+            .map(String::from),
+        subscribers: 0,
+        // No homepage in the API, link to the Apple Podcasts page instead
+        website: item["collectionViewUrl"].as_str().map(String::from),
+    })
+}
+
+impl DirectoryProvider for ItunesDirectory {
+    fn name(&self) -> &'static str {
+        "itunes"
+    }
+
+    fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>, String> {
+        self.get_podcasts(&format!(
+            "{}?media=podcast&entity=podcast&country={}&limit={}&term={}",
+            SEARCH_URL,
+            self.country,
+            limit.clamp(1, 200),
+            urlencoding::encode(query)
+        ))
+    }
+
+    fn toplist(&self, limit: usize) -> Result<Vec<SearchResult>, String> {
+        // The charts feed has no feed URLs, so look the chart entries up by ID
+        let json = self.get_json(&format!(
+            "{}/{}/podcasts/top/{}/podcasts.json",
+            CHARTS_URL,
+            self.country,
+            limit.clamp(1, 100)
+        ))?;
+        let ids: Vec<&str> = json["feed"]["results"]
+            .as_array()
+            .ok_or("Invalid charts format")?
+            .iter()
+            .filter_map(|item| item["id"].as_str())
+            .collect();
+
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let lookup = self.get_json(&format!(
+            "{}?country={}&id={}",
+            LOOKUP_URL,
+            self.country,
+            ids.join(",")
+        ))?;
+        let items = lookup["results"].as_array().ok_or("Invalid response format")?;
+
+        // Keep chart order
+        let mut ranked: Vec<(usize, SearchResult)> = items
+            .iter()
+            .filter_map(|item| {
+                let id = item["collectionId"].as_u64()?.to_string();
+                let rank = ids.iter().position(|chart_id| *chart_id == id)?;
+                Some((rank, parse_podcast(item)?))
+            })
+            .collect();
+        ranked.sort_by_key(|(rank, _)| *rank);
+        Ok(ranked.into_iter().map(|(_, result)| result).collect())
+    }
+
+    fn categories(&self, limit: usize) -> Result<Vec<Category>, String> {
+        Ok(GENRES
+            .iter()
+            .take(limit)
+            .map(|(id, name)| Category::new(self.name(), id, name))
+            .collect())
+    }
+
+    fn category_podcasts(&self, id: &str, limit: usize) -> Result<Vec<SearchResult>, String> {
+        self.get_podcasts(&format!(
+            "{}?media=podcast&entity=podcast&country={}&limit={}&genreId={}&term=podcast",
+            SEARCH_URL,
+            self.country,
+            limit.clamp(1, 200),
+            urlencoding::encode(id)
+        ))
+    }
+
+    fn lookup(&self, _feed_url: &str) -> Result<Option<SearchResult>, String> {
+        // The iTunes API can only look podcasts up by their iTunes ID
+        Ok(None)
+    }
+}
@@ -0,0 +1,289 @@
+pub mod gpodder;
+pub mod itunes;
+pub mod podcastindex;
+
+use crate::browse::{self, SearchResult};
+use crate::config::DirectoryConfig;
+use reqwest::blocking::Client;
+use std::time::Duration;
+
+/// A podcast category (or tag) as listed by one or more providers
+#[derive(Debug, Clone, PartialEq)]
+pub struct Category {
+    pub title: String,
+    /// Provider-specific IDs as (provider name, id) pairs
+    pub ids: Vec<(String, String)>,
+}
+
+impl Category {
+    pub fn new(provider: &str, id: &str, title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            ids: vec![(provider.to_string(), id.to_string())],
+        }
+    }
+
+    fn id_for(&self, provider: &str) -> Option<&str> {
+        self.ids.iter().find(|(p, _)| p == provider).map(|(_, id)| id.as_str())
+    }
+}
+
+/// A podcast directory that Browse can search
+pub trait DirectoryProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>, String>;
+
+    /// Most popular podcasts
+    fn toplist(&self, limit: usize) -> Result<Vec<SearchResult>, String>;
+
+    fn categories(&self, limit: usize) -> Result<Vec<Category>, String>;
+
+    /// Podcasts in the category with the given provider-specific ID
+    fn category_podcasts(&self, id: &str, limit: usize) -> Result<Vec<SearchResult>, String>;
+
+    /// Directory entry for a feed URL, if the provider knows it
+    fn lookup(&self, feed_url: &str) -> Result<Option<SearchResult>, String>;
+}
+
+/// HTTP client shared by the providers
+fn http_client() -> Result<Client, String> {
+    Client::builder()
+        .timeout(Duration::from_secs(15))
+        .user_agent(concat!("shellcast/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+/// The providers selected in the config, queried together and merged
+pub struct Directory {
+    providers: Vec<Box<dyn DirectoryProvider>>,
+}
+
+impl Default for Directory {
+    fn default() -> Self {
+        Self::from_config(&DirectoryConfig::default())
+    }
+}
+
+impl Directory {
+    #[cfg(test)]
+    pub fn new(providers: Vec<Box<dyn DirectoryProvider>>) -> Self {
+        Self { providers }
+    }
+
+    /// Build the providers listed in the config, skipping unknown or unconfigured ones
+    pub fn from_config(config: &DirectoryConfig) -> Self {
+        let mut providers: Vec<Box<dyn DirectoryProvider>> = Vec::new();
+
+        for name in &config.providers {
+            let provider: Result<Box<dyn DirectoryProvider>, String> = match name.as_str() {
+                "gpodder" => gpodder::GpodderDirectory::new(gpodder::GPODDER_URL)
+                    .map(|p| Box::new(p) as Box<dyn DirectoryProvider>),
+                "itunes" => itunes::ItunesDirectory::new(&config.itunes_country)
+                    .map(|p| Box::new(p) as Box<dyn DirectoryProvider>),
+                "podcastindex" => match (&config.podcastindex_key, &config.podcastindex_secret) {
+                    (Some(key), Some(secret)) => podcastindex::PodcastIndexDirectory::new(key, secret)
+                        .map(|p| Box::new(p) as Box<dyn DirectoryProvider>),
+                    _ => Err("podcastindex_key and podcastindex_secret are required".to_string()),
+                },
+                other => Err(format!("unknown provider '{}'", other)),
+            };
+
+            match provider {
+                Ok(provider) => providers.push(provider),
+                Err(e) => log::error!("Skipping directory provider {}: {}", name, e),
+            }
+        }
+
+        if providers.is_empty() {
+            log::warn!("No usable directory providers configured, using gpodder.net");
+            if let Ok(provider) = gpodder::GpodderDirectory::new(gpodder::GPODDER_URL) {
+                providers.push(Box::new(provider));
+            }
+        }
+
+        Self { providers }
+    }
+
+    pub fn provider_names(&self) -> Vec<&'static str> {
+        self.providers.iter().map(|p| p.name()).collect()
+    }
+
+    /// Run a query against every provider in parallel, returning results in provider order.
+    /// Fails only if every provider fails.
+    fn query_all<T, F>(&self, query: F) -> Result<Vec<T>, String>
+    where
+        T: Send,
+        F: Fn(&dyn DirectoryProvider) -> Result<Vec<T>, String> + Sync,
+    {
+        let results: Vec<Result<Vec<T>, String>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = self
+                .providers
+                .iter()
+                .map(|provider| scope.spawn(|| query(provider.as_ref())))
+                .collect();
+            handles
+                .into_iter()
+                .map(|h| h.join().unwrap_or_else(|_| Err("Provider panicked".to_string())))
+                .collect()
+        });
+
+        let mut merged = Vec::new();
+        let mut errors = Vec::new();
+        for (provider, result) in self.providers.iter().zip(results) {
+            match result {
+                Ok(items) => merged.extend(items),
+                Err(e) => {
+                    log::warn!("{} failed: {}", provider.name(), e);
+                    errors.push(format!("{}: {}", provider.name(), e));
+                }
+            }
+        }
+
+        if merged.is_empty() && !errors.is_empty() {
+            return Err(errors.join("; "));
+        }
+        Ok(merged)
+    }
+
+    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>, String> {
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut results = browse::dedupe_results(self.query_all(|p| p.search(query, limit))?);
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    pub fn toplist(&self, limit: usize) -> Result<Vec<SearchResult>, String> {
+        let mut results = browse::dedupe_results(self.query_all(|p| p.toplist(limit))?);
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    /// Categories from every provider, merged by title
+    pub fn categories(&self, limit: usize) -> Result<Vec<Category>, String> {
+        let mut merged: Vec<Category> = Vec::new();
+        for category in self.query_all(|p| p.categories(limit))? {
+            match merged
+                .iter_mut()
+                .find(|c| c.title.eq_ignore_ascii_case(&category.title))
+            {
+                Some(existing) => existing.ids.extend(category.ids),
+                None => merged.push(category),
+            }
+        }
+        Ok(merged)
+    }
+
+    pub fn category_podcasts(&self, category: &Category, limit: usize) -> Result<Vec<SearchResult>, String> {
+        let results = self.query_all(|p| match category.id_for(p.name()) {
+            Some(id) => p.category_podcasts(id, limit),
+            None => Ok(Vec::new()),
+        })?;
+        let mut results = browse::dedupe_results(results);
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    /// First provider that knows the feed
+    pub fn lookup(&self, feed_url: &str) -> Result<Option<SearchResult>, String> {
+        let mut last_error = None;
+        for provider in &self.providers {
+            match provider.lookup(feed_url) {
+                Ok(Some(result)) => return Ok(Some(result)),
+                Ok(None) => {}
+                Err(e) => last_error = Some(e),
+            }
+        }
+        match last_error {
+            Some(e) => Err(e),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedProvider {
+        name: &'static str,
+        results: Result<Vec<SearchResult>, String>,
+        categories: Vec<Category>,
+    }
+
+    impl DirectoryProvider for FixedProvider {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn search(&self, _query: &str, _limit: usize) -> Result<Vec<SearchResult>, String> {
+            self.results.clone()
+        }
+
+        fn toplist(&self, _limit: usize) -> Result<Vec<SearchResult>, String> {
+            self.results.clone()
+        }
+
+        fn categories(&self, _limit: usize) -> Result<Vec<Category>, String> {
+            Ok(self.categories.clone())
+        }
+
+        fn category_podcasts(&self, _id: &str, _limit: usize) -> Result<Vec<SearchResult>, String> {
+            self.results.clone()
+        }
+
+        fn lookup(&self, _feed_url: &str) -> Result<Option<SearchResult>, String> {
+            Ok(self.results.clone()?.into_iter().next())
+        }
+    }
+
+    fn result(title: &str, feed_url: &str, subscribers: u64) -> SearchResult {
+        SearchResult {
+            title: title.to_string(),
+            author: String::new(),
+            description: String::new(),
+            feed_url: feed_url.to_string(),
+            artwork_url: None,
+            subscribers,
+            website: None,
+        }
+    }
+
+    #[test]
+    fn test_directory_merges_providers_and_tolerates_failures() {
+        let directory = Directory::new(vec![
+            Box::new(FixedProvider {
+                name: "a",
+                results: Ok(vec![result("Show", "https://example.com/feed", 10)]),
+                categories: vec![Category::new("a", "tech", "Technology")],
+            }),
+            Box::new(FixedProvider {
+                name: "b",
+                results: Ok(vec![
+                    result("Show Podcast", "https://example.com/other-feed", 0),
+                    result("Second", "https://second.org/feed", 0),
+                ]),
+                categories: vec![Category::new("b", "1318", "technology")],
+            }),
+            Box::new(FixedProvider {
+                name: "c",
+                results: Err("offline".to_string()),
+                categories: vec![],
+            }),
+        ]);
+
+        let results = directory.search("show", 20).unwrap();
+        let titles: Vec<&str> = results.iter().map(|r| r.title.as_str()).collect();
+        assert_eq!(titles, vec!["Show", "Second"]);
+
+        let categories = directory.categories(10).unwrap();
+        assert_eq!(categories.len(), 1);
+        assert_eq!(categories[0].id_for("b"), Some("1318"));
+
+        // The first provider that knows the feed answers
+        assert_eq!(directory.lookup("https://example.com/feed").unwrap().unwrap().subscribers, 10);
+    }
+}
@@ -0,0 +1,143 @@
+use super::{http_client, Category, DirectoryProvider};
+use crate::browse::SearchResult;
+use reqwest::blocking::Client;
+use sha1::{Digest, Sha1};
+
+const API_URL: &str = "https://api.podcastindex.org/api/1.0";
+
+/// Podcast Index (free API key and secret from api.podcastindex.org)
+pub struct PodcastIndexDirectory {
+    client: Client,
+    key: String,
+    secret: String,
+}
+
+/// Authentication headers: the key, the request time and
+/// SHA-1(key + secret + time) as a lowercase hex string
+pub fn auth_headers(key: &str, secret: &str, now: i64) -> [(&'static str, String); 3] {
+    let digest = Sha1::digest(format!("{}{}{}", key, secret, now).as_bytes());
+    let hash: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    [
+        ("X-Auth-Key", key.to_string()),
+        ("X-Auth-Date", now.to_string()),
+        ("Authorization", hash),
+    ]
+}
+
+impl PodcastIndexDirectory {
+    pub fn new(key: &str, secret: &str) -> Result<Self, String> {
+        Ok(Self {
+            client: http_client()?,
+            key: key.to_string(),
+            secret: secret.to_string(),
+        })
+    }
+
+    fn get_json(&self, path: &str) -> Result<serde_json::Value, String> {
+        let mut request = self.client.get(format!("{}{}", API_URL, path));
+        for (name, value) in auth_headers(&self.key, &self.secret, chrono::Utc::now().timestamp()) {
+            request = request.header(name, value);
+        }
+
+        let response = request.send().map_err(|e| format!("Request failed: {}", e))?;
+
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err("Podcast Index rejected the API key".to_string());
+        }
+        if !response.status().is_success() {
+            return Err(format!("Podcast Index returned status {}", response.status()));
+        }
+
+        response
+            .json()
+            .map_err(|e| format!("Failed to parse response: {}", e))
+    }
+
+    fn get_feeds(&self, path: &str) -> Result<Vec<SearchResult>, String> {
+        let json = self.get_json(path)?;
+        let feeds = json["feeds"].as_array().ok_or("Invalid response format")?;
+        Ok(feeds.iter().filter_map(parse_feed).collect())
+    }
+}
+
+/// Parse a feed object. Podcast Index has no subscriber counts.
+fn parse_feed(item: &serde_json::Value) -> Option<SearchResult> {
+    let artwork = item["artwork"]
+        .as_str()
+        .filter(|s| !s.is_empty())
+        .or_else(|| item["image"].as_str())
+        .filter(|s| !s.is_empty());
+
+    Some(SearchResult {
+        title: item["title"].as_str()?.to_string(),
+        author: item["author"]
+            .as_str()
+            .filter(|s| !s.is_empty())
+            .unwrap_or("Unknown")
+            .to_string(),
+        description: item["description"].as_str().unwrap_or("").to_string(),
+        feed_url: item["url"].as_str()?.to_string(),
+        artwork_url: artwork.map(String::from),
+        subscribers: 0,
+        website: item["link"].as_str().filter(|s| !s.is_empty()).map(String::from),
+    })
+}
+
+impl DirectoryProvider for PodcastIndexDirectory {
+    fn name(&self) -> &'static str {
+        "podcastindex"
+    }
+
+    fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>, String> {
+        self.get_feeds(&format!(
+            "/search/byterm?q={}&max={}",
+            urlencoding::encode(query),
+            limit.clamp(1, 1000)
+        ))
+    }
+
+    fn toplist(&self, limit: usize) -> Result<Vec<SearchResult>, String> {
+        self.get_feeds(&format!("/podcasts/trending?max={}", limit.clamp(1, 1000)))
+    }
+
+    fn categories(&self, limit: usize) -> Result<Vec<Category>, String> {
+        let json = self.get_json("/categories/list")?;
+        let items = json["feeds"].as_array().ok_or("Invalid categories format")?;
+        Ok(items
+            .iter()
+            .filter_map(|item| {
+                let id = item["id"].as_u64()?.to_string();
+                Some(Category::new(self.name(), &id, item["name"].as_str()?))
+            })
+            .take(limit)
+            .collect())
+    }
+
+    fn category_podcasts(&self, id: &str, limit: usize) -> Result<Vec<SearchResult>, String> {
+        self.get_feeds(&format!(
+            "/podcasts/trending?max={}&cat={}",
+            limit.clamp(1, 1000),
+            urlencoding::encode(id)
+        ))
+    }
+
+    fn lookup(&self, feed_url: &str) -> Result<Option<SearchResult>, String> {
+        let json = self.get_json(&format!("/podcasts/byfeedurl?url={}", urlencoding::encode(feed_url)))?;
+        // Unknown feeds come back with an empty array instead of an object
+        Ok(parse_feed(&json["feed"]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auth_headers() {
+        let headers = auth_headers("KEY", "SECRET", 1_700_000_000);
+        assert_eq!(headers[0], ("X-Auth-Key", "KEY".to_string()));
+        assert_eq!(headers[1], ("X-Auth-Date", "1700000000".to_string()));
+        // sha1("KEYSECRET1700000000")
+        assert_eq!(headers[2].1, "5f8983664e541a83aaae7f3a47f1957fbca48aec");
+    }
+}
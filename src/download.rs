@@ -0,0 +1,109 @@
+//! Saving episodes to disk for offline listening
+
+use crate::app::Episode;
+use sha1::{Digest, Sha1};
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// File name for a downloaded episode, keeping the extension of its audio URL.
+/// A short hash of the URL tells apart episodes that share a title.
+pub fn file_name(podcast_title: &str, episode: &Episode) -> String {
+    let path = episode.audio_url.split(['?', '#']).next().unwrap_or_default();
+    let extension = path
+        .rsplit('/')
+        .next()
+        .and_then(|name| name.rsplit_once('.'))
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .filter(|ext| !ext.is_empty() && ext.len() <= 4 && ext.chars().all(|c| c.is_ascii_alphanumeric()))
+        .unwrap_or_else(|| "mp3".to_string());
+
+    let digest = Sha1::digest(episode.audio_url.as_bytes());
+    let hash: String = digest.iter().take(4).map(|b| format!("{:02x}", b)).collect();
+    let name = format!("{} - {} [{}].{}", podcast_title, episode.title, hash, extension);
+    name.chars()
+        .map(|c| if c.is_control() || "/\\:*?\"<>|".contains(c) { '_' } else { c })
+        .collect()
+}
+
+/// Download an episode into `dir`, returning the new file's path. The audio is
+/// written to a `.part` file first so an interrupted download is never mistaken
+/// for a complete one.
+pub fn download(podcast_title: &str, episode: &Episode, dir: &Path) -> Result<PathBuf, String> {
+    if episode.audio_url.is_empty() {
+        return Err("No audio URL for this episode".to_string());
+    }
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create downloads directory: {}", e))?;
+
+    let mut response = reqwest::blocking::get(&episode.audio_url)
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to download episode: {}", e))?;
+
+    let path = dir.join(file_name(podcast_title, episode));
+    let part = path.with_extension(format!(
+        "{}.part",
+        path.extension().and_then(|e| e.to_str()).unwrap_or_default()
+    ));
+    let mut file = File::create(&part).map_err(|e| format!("Failed to create download file: {}", e))?;
+    if let Err(e) = io::copy(&mut response, &mut file) {
+        let _ = fs::remove_file(&part);
+        return Err(format!("Failed to download episode: {}", e));
+    }
+    fs::rename(&part, &path).map_err(|e| format!("Failed to save download: {}", e))?;
+    Ok(path)
+}
+
+/// The downloaded file of an episode, if it is still there
+pub fn local_file(episode: &Episode) -> Option<&Path> {
+    episode.download_path.as_deref().map(Path::new).filter(|path| path.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn episode(title: &str, audio_url: &str) -> Episode {
+        Episode {
+            title: title.to_string(),
+            audio_url: audio_url.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_download_episode() {
+        let name = file_name("Show", &episode("1: Intro?", "https://x/a.M4A?t=1"));
+        assert!(name.starts_with("Show - 1_ Intro_ [") && name.ends_with("].m4a"), "{}", name);
+        assert!(file_name("Show", &episode("Two", "https://x/listen")).ends_with("].mp3"));
+        assert_ne!(
+            file_name("Show", &episode("Bonus", "https://x/1.mp3")),
+            file_name("Show", &episode("Bonus", "https://x/2.mp3")),
+            "episodes with the same title get their own files"
+        );
+
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let port = server.server_addr().to_ip().unwrap().port();
+        std::thread::spawn(move || {
+            for request in server.incoming_requests() {
+                let response = if request.url() == "/a.mp3" {
+                    tiny_http::Response::from_string("audio")
+                } else {
+                    tiny_http::Response::from_string("not found").with_status_code(404)
+                };
+                let _ = request.respond(response);
+            }
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut ep = episode("One", &format!("http://127.0.0.1:{}/a.mp3", port));
+        let path = download("Show", &ep, dir.path()).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "audio");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1, "no .part file left behind");
+
+        ep.download_path = Some(path.to_string_lossy().into_owned());
+        assert_eq!(local_file(&ep), Some(path.as_path()));
+
+        ep.audio_url = format!("http://127.0.0.1:{}/missing.mp3", port);
+        assert!(download("Show", &ep, dir.path()).is_err());
+    }
+}
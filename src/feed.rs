@@ -1,5 +1,7 @@
 use crate::app::{Episode, Podcast};
+use crate::chapters::podlove;
 use atom_syndication::Feed as AtomFeed;
+use chrono::{DateTime, FixedOffset};
 use rss::Channel;
 use std::io::BufReader;
 use std::time::Duration;
@@ -63,47 +65,74 @@ pub fn fetch_and_parse(url: &str) -> Result<Podcast, String> {
     Err("Failed to parse feed as RSS or Atom. Check debug.log for details.".to_string())
 }
 
-/// Refresh a podcast feed, preserving played status of existing episodes
-pub fn refresh_feed(podcast: &mut Podcast) -> Result<usize, String> {
+/// Refresh a podcast feed, returning the audio URLs of newly arrived episodes
+pub fn refresh_feed(podcast: &mut Podcast) -> Result<Vec<String>, String> {
     // Fetch fresh data
     let fresh = fetch_and_parse(&podcast.url)?;
+    Ok(merge_refresh(podcast, fresh))
+}
 
-    // Build a map of audio_url -> played status from existing episodes
-    let played_map: std::collections::HashMap<String, bool> = podcast
+/// Merge freshly fetched feed data into a podcast, preserving local state
+/// (played, position, stars, downloads) of existing episodes.
+/// Returns the audio URLs of episodes that were not known before.
+pub fn merge_refresh(podcast: &mut Podcast, fresh: Podcast) -> Vec<String> {
+    // Build a map of audio_url -> existing episode
+    let existing: std::collections::HashMap<String, Episode> = podcast
         .episodes
-        .iter()
-        .map(|ep| (ep.audio_url.clone(), ep.played))
+        .drain(..)
+        .map(|ep| (ep.audio_url.clone(), ep))
         .collect();
 
-    // Count new episodes
-    let old_count = podcast.episodes.len();
-
     // Update podcast metadata
     podcast.title = fresh.title;
     podcast.description = fresh.description;
+    podcast.categories = fresh.categories;
+    podcast.image_url = fresh.image_url;
+
+    let mut added = Vec::new();
 
-    // Merge episodes, preserving played status
+    // Merge episodes, preserving local state
     podcast.episodes = fresh
         .episodes
         .into_iter()
         .map(|mut ep| {
-            // Preserve played status if we've seen this episode before
-            if let Some(&played) = played_map.get(&ep.audio_url) {
-                ep.played = played;
+            // Preserve local state if we've seen this episode before
+            if let Some(old) = existing.get(&ep.audio_url) {
+                ep.played = old.played;
+                ep.position_secs = old.position_secs;
+                ep.starred = old.starred;
+                ep.download_path = old.download_path.clone();
+                ep.last_played = old.last_played;
+                ep.state_changed = old.state_changed;
+            } else {
+                added.push(ep.audio_url.clone());
             }
             ep
         })
         .collect();
 
-    let new_count = podcast.episodes.len();
-    let added = new_count.saturating_sub(old_count);
+    added
+}
 
-    Ok(added)
+/// Parse an episode publish date (RFC 2822 from RSS, RFC 3339 from Atom)
+pub fn parse_published(published: &str) -> Option<DateTime<FixedOffset>> {
+    let published = published.trim();
+    DateTime::parse_from_rfc2822(published)
+        .or_else(|_| DateTime::parse_from_rfc3339(published))
+        .ok()
 }
 
 fn parse_rss(channel: Channel, url: &str) -> Podcast {
     log::debug!("Parsing RSS feed: {}, found {} episodes", channel.title(), channel.items().len());
 
+    // Podlove chapters are usually under "psc", but the prefix is up to the feed
+    let psc_prefix = channel
+        .namespaces()
+        .iter()
+        .find(|(_, uri)| uri.trim_end_matches('/') == podlove::NAMESPACE)
+        .map(|(prefix, _)| prefix.as_str())
+        .unwrap_or("psc");
+
     let episodes: Vec<Episode> = channel
         .items()
         .iter()
@@ -145,10 +174,17 @@ fn parse_rss(channel: Channel, url: &str) -> Podcast {
                 .and_then(|chapter_elem| chapter_elem.attrs.get("url"))
                 .map(|s| s.to_string());
 
+            let image_url = item
+                .itunes_ext()
+                .and_then(|ext| ext.image())
+                .map(|s| s.to_string());
+
             if let Some(ref chapters) = chapters_url {
                 log::debug!("Found chapters URL for '{}': {}", item.title().unwrap_or("Unknown"), chapters);
             }
 
+            let feed_chapters = podlove::from_extensions(item.extensions(), psc_prefix);
+
             Episode {
                 title: item.title().unwrap_or("Untitled").to_string(),
                 description: html_to_text(item.description().unwrap_or("")),
@@ -158,15 +194,42 @@ fn parse_rss(channel: Channel, url: &str) -> Podcast {
                 played: false,
                 chapters_url,
                 position_secs: 0,
+                starred: false,
+                download_path: None,
+                last_played: None,
+                state_changed: None,
+                image_url,
+                feed_chapters,
             }
         })
         .collect();
 
+    // iTunes categories (with their subcategories), then plain RSS categories
+    let mut categories: Vec<String> = Vec::new();
+    if let Some(itunes) = channel.itunes_ext() {
+        for category in itunes.categories() {
+            categories.push(category.text().to_string());
+            if let Some(sub) = category.subcategory() {
+                categories.push(sub.text().to_string());
+            }
+        }
+    }
+    categories.extend(channel.categories().iter().map(|c| c.name().to_string()));
+    categories.retain(|c| !c.trim().is_empty());
+    categories.dedup();
+
     Podcast {
         title: channel.title().to_string(),
         description: html_to_text(channel.description()),
         url: url.to_string(),
         episodes,
+        filter: Default::default(),
+        categories,
+        image_url: channel
+            .itunes_ext()
+            .and_then(|ext| ext.image())
+            .or_else(|| channel.image().map(|image| image.url()))
+            .map(|s| s.to_string()),
     }
 }
 
@@ -202,6 +265,12 @@ fn parse_atom(feed: AtomFeed, url: &str) -> Podcast {
                 played: false,
                 chapters_url: None, // Atom feeds don't typically have chapters
                 position_secs: 0,
+                starred: false,
+                download_path: None,
+                last_played: None,
+                state_changed: None,
+                image_url: None,
+                feed_chapters: None,
             }
         })
         .collect();
@@ -211,6 +280,13 @@ fn parse_atom(feed: AtomFeed, url: &str) -> Podcast {
         description: html_to_text(&feed.subtitle().map(|s| s.value.clone()).unwrap_or_default()),
         url: url.to_string(),
         episodes,
+        filter: Default::default(),
+        categories: feed
+            .categories()
+            .iter()
+            .map(|c| c.label().unwrap_or(c.term()).to_string())
+            .collect(),
+        image_url: feed.logo().or(feed.icon()).map(|s| s.to_string()),
     }
 }
 
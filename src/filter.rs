@@ -0,0 +1,96 @@
+use crate::app::Episode;
+use serde::{Deserialize, Serialize};
+
+/// Episode filter for the episode pane, remembered per podcast
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EpisodeFilter {
+    #[serde(default)]
+    pub unplayed: bool,
+    #[serde(default)]
+    pub in_progress: bool,
+    #[serde(default)]
+    pub downloaded: bool,
+    #[serde(default)]
+    pub starred: bool,
+    /// Case-insensitive text matched against title and description
+    #[serde(default)]
+    pub text: String,
+}
+
+impl EpisodeFilter {
+    /// Whether any toggle or text search is active
+    pub fn is_active(&self) -> bool {
+        self.unplayed || self.in_progress || self.downloaded || self.starred || !self.text.is_empty()
+    }
+
+    /// Check an episode against every active toggle (toggles are combined with AND)
+    pub fn matches(&self, episode: &Episode) -> bool {
+        if self.unplayed && episode.played {
+            return false;
+        }
+        if self.in_progress && episode.position_secs == 0 {
+            return false;
+        }
+        if self.downloaded && episode.download_path.is_none() {
+            return false;
+        }
+        if self.starred && !episode.starred {
+            return false;
+        }
+        if !self.text.is_empty() {
+            let needle = self.text.to_lowercase();
+            if !episode.title.to_lowercase().contains(&needle)
+                && !episode.description.to_lowercase().contains(&needle)
+            {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Reset all toggles and the text search
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn episode(title: &str, played: bool, position_secs: u64) -> Episode {
+        Episode {
+            title: title.to_string(),
+            description: "A show about Rust".to_string(),
+            played,
+            position_secs,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_filter_toggles_combine() {
+        let filter = EpisodeFilter {
+            unplayed: true,
+            in_progress: true,
+            ..Default::default()
+        };
+
+        assert!(filter.matches(&episode("Half done", false, 120)));
+        assert!(!filter.matches(&episode("Not started", false, 0)));
+        assert!(!filter.matches(&episode("Finished", true, 120)));
+    }
+
+    #[test]
+    fn test_filter_text_matches_title_and_description() {
+        let mut filter = EpisodeFilter {
+            text: "RUST".to_string(),
+            ..Default::default()
+        };
+        assert!(filter.matches(&episode("Episode 1", false, 0)));
+
+        filter.text = "episode 1".to_string();
+        assert!(filter.matches(&episode("Episode 1", false, 0)));
+        assert!(!filter.matches(&episode("Episode 2", false, 0)));
+    }
+}
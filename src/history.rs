@@ -0,0 +1,279 @@
+use crate::app::EpisodeRef;
+use crate::persistence;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+
+const HISTORY_FILE: &str = "history.jsonl";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryEventKind {
+    Start,
+    Pause,
+    Resume,
+    Seek,
+    Stop,
+    Finish,
+    /// Another episode was started before this one finished
+    Skip,
+}
+
+impl HistoryEventKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Start => "start",
+            Self::Pause => "pause",
+            Self::Resume => "resume",
+            Self::Seek => "seek",
+            Self::Stop => "stop",
+            Self::Finish => "finish",
+            Self::Skip => "skip",
+        }
+    }
+}
+
+/// A single playback event. Titles are stored alongside the URLs so the
+/// log still makes sense after a podcast has been deleted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEvent {
+    /// Unix timestamp (seconds)
+    pub timestamp: i64,
+    pub kind: HistoryEventKind,
+    pub podcast_title: String,
+    pub podcast_url: String,
+    pub episode_title: String,
+    pub audio_url: String,
+    /// Playback position when the event happened
+    pub position_secs: u64,
+    /// Position before a seek
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_secs: Option<u64>,
+    #[serde(default = "default_speed")]
+    pub speed: f32,
+}
+
+fn default_speed() -> f32 {
+    1.0
+}
+
+/// Append one event to the history log
+pub fn append(event: &HistoryEvent) -> Result<(), String> {
+    let path = persistence::get_data_file(HISTORY_FILE)?;
+
+    let line = serde_json::to_string(event)
+        .map_err(|e| format!("Failed to serialize history event: {}", e))?;
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open history file: {}", e))?;
+
+    writeln!(file, "{}", line)
+        .map_err(|e| format!("Failed to write history file: {}", e))?;
+
+    Ok(())
+}
+
+/// Load every event from the history log, oldest first
+pub fn load() -> Result<Vec<HistoryEvent>, String> {
+    let path = persistence::get_data_file(HISTORY_FILE)?;
+
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let contents = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read history file: {}", e))?;
+
+    Ok(parse_lines(&contents))
+}
+
+/// Parse JSON lines, skipping any that are malformed (e.g. a partially written last line)
+fn parse_lines(contents: &str) -> Vec<HistoryEvent> {
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(event) => Some(event),
+            Err(e) => {
+                log::warn!("Skipping malformed history line: {}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Most recent activity for one episode, as listed on the History screen
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub episode: EpisodeRef,
+    pub podcast_title: String,
+    pub episode_title: String,
+    pub last_timestamp: i64,
+    pub last_kind: HistoryEventKind,
+    pub position_secs: u64,
+}
+
+/// Group events by episode, most recently active first
+pub fn recent_episodes(events: &[HistoryEvent]) -> Vec<HistoryEntry> {
+    let mut latest: HashMap<&str, &HistoryEvent> = HashMap::new();
+    for event in events {
+        latest
+            .entry(event.audio_url.as_str())
+            .and_modify(|existing| {
+                if event.timestamp >= existing.timestamp {
+                    *existing = event;
+                }
+            })
+            .or_insert(event);
+    }
+
+    let mut entries: Vec<HistoryEntry> = latest
+        .into_values()
+        .map(|event| HistoryEntry {
+            episode: EpisodeRef {
+                podcast_url: event.podcast_url.clone(),
+                audio_url: event.audio_url.clone(),
+            },
+            podcast_title: event.podcast_title.clone(),
+            episode_title: event.episode_title.clone(),
+            last_timestamp: event.timestamp,
+            last_kind: event.kind,
+            position_secs: event.position_secs,
+        })
+        .collect();
+
+    entries.sort_by_key(|e| std::cmp::Reverse(e.last_timestamp));
+    entries
+}
+
+pub struct HistoryState {
+    pub entries: Vec<HistoryEntry>,
+    pub selected_index: usize,
+}
+
+impl HistoryState {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            selected_index: 0,
+        }
+    }
+
+    /// Reload the log from disk
+    pub fn reload(&mut self) {
+        let events = load().unwrap_or_else(|e| {
+            log::error!("Failed to load history: {}", e);
+            Vec::new()
+        });
+        self.entries = recent_episodes(&events);
+        if self.selected_index >= self.entries.len() {
+            self.selected_index = self.entries.len().saturating_sub(1);
+        }
+    }
+
+    pub fn move_up(&mut self) {
+        if self.selected_index > 0 {
+            self.selected_index -= 1;
+        }
+    }
+
+    pub fn move_down(&mut self) {
+        if self.selected_index < self.entries.len().saturating_sub(1) {
+            self.selected_index += 1;
+        }
+    }
+
+    pub fn selected(&self) -> Option<&HistoryEntry> {
+        self.entries.get(self.selected_index)
+    }
+}
+
+/// Quote a CSV field if it contains separators, quotes or newlines
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Render events as CSV with a header row
+pub fn to_csv(events: &[HistoryEvent]) -> String {
+    let mut out = String::from(
+        "timestamp,event,podcast_title,podcast_url,episode_title,audio_url,position_secs,from_secs,speed\n",
+    );
+    for event in events {
+        let timestamp = chrono::DateTime::from_timestamp(event.timestamp, 0)
+            .map(|dt| dt.to_rfc3339())
+            .unwrap_or_else(|| event.timestamp.to_string());
+        out.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{}\n",
+            timestamp,
+            event.kind.label(),
+            csv_field(&event.podcast_title),
+            csv_field(&event.podcast_url),
+            csv_field(&event.episode_title),
+            csv_field(&event.audio_url),
+            event.position_secs,
+            event.from_secs.map(|s| s.to_string()).unwrap_or_default(),
+            event.speed,
+        ));
+    }
+    out
+}
+
+/// Render events as a pretty-printed JSON array
+pub fn to_json(events: &[HistoryEvent]) -> Result<String, String> {
+    serde_json::to_string_pretty(events)
+        .map_err(|e| format!("Failed to serialize history: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(timestamp: i64, kind: HistoryEventKind, audio_url: &str, title: &str) -> HistoryEvent {
+        HistoryEvent {
+            timestamp,
+            kind,
+            podcast_title: "Show".to_string(),
+            podcast_url: "https://example.com/feed".to_string(),
+            episode_title: title.to_string(),
+            audio_url: audio_url.to_string(),
+            position_secs: timestamp as u64,
+            from_secs: None,
+            speed: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_recent_episodes_groups_by_episode() {
+        let events = vec![
+            event(10, HistoryEventKind::Start, "a.mp3", "A"),
+            event(20, HistoryEventKind::Start, "b.mp3", "B"),
+            event(30, HistoryEventKind::Pause, "a.mp3", "A"),
+        ];
+
+        let entries = recent_episodes(&events);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].episode_title, "A");
+        assert_eq!(entries[0].last_kind, HistoryEventKind::Pause);
+        assert_eq!(entries[1].episode_title, "B");
+    }
+
+    #[test]
+    fn test_history_lines_round_trip_and_csv() {
+        let line = serde_json::to_string(&event(0, HistoryEventKind::Finish, "a.mp3", "Hello, \"World\"")).unwrap();
+        let events = parse_lines(&format!("{}\n{{broken\n", line));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, HistoryEventKind::Finish);
+
+        let csv = to_csv(&events);
+        assert!(csv.contains("finish,Show,"));
+        assert!(csv.contains("\"Hello, \"\"World\"\"\""));
+    }
+}
@@ -0,0 +1,164 @@
+use crate::app::Podcast;
+use crate::feed::parse_published;
+use chrono::{DateTime, FixedOffset, Utc};
+use std::collections::HashSet;
+
+/// An unplayed episode shown in the inbox
+#[derive(Debug, Clone)]
+pub struct InboxEntry {
+    pub podcast_index: usize,
+    pub episode_index: usize,
+    pub published: Option<DateTime<FixedOffset>>,
+}
+
+/// Cross-podcast list of unplayed episodes, newest first
+pub struct InboxState {
+    pub entries: Vec<InboxEntry>,
+    pub selected_index: usize,
+    /// Audio URLs of episodes that arrived during a refresh this session
+    pub new_episodes: HashSet<String>,
+}
+
+impl InboxState {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            selected_index: 0,
+            new_episodes: HashSet::new(),
+        }
+    }
+
+    /// Rebuild the entry list from the current podcasts
+    pub fn rebuild(&mut self, podcasts: &[Podcast]) {
+        self.entries = collect_unplayed(podcasts);
+        if self.selected_index >= self.entries.len() {
+            self.selected_index = self.entries.len().saturating_sub(1);
+        }
+    }
+
+    pub fn move_up(&mut self) {
+        if self.selected_index > 0 {
+            self.selected_index -= 1;
+        }
+    }
+
+    pub fn move_down(&mut self) {
+        if self.selected_index < self.entries.len().saturating_sub(1) {
+            self.selected_index += 1;
+        }
+    }
+
+    pub fn selected(&self) -> Option<&InboxEntry> {
+        self.entries.get(self.selected_index)
+    }
+}
+
+/// Collect unplayed episodes from every podcast, newest first.
+/// Episodes without a parseable date are listed last.
+pub fn collect_unplayed(podcasts: &[Podcast]) -> Vec<InboxEntry> {
+    let mut entries: Vec<InboxEntry> = podcasts
+        .iter()
+        .enumerate()
+        .flat_map(|(p, podcast)| {
+            podcast
+                .episodes
+                .iter()
+                .enumerate()
+                .filter(|(_, e)| !e.played)
+                .map(move |(e, episode)| InboxEntry {
+                    podcast_index: p,
+                    episode_index: e,
+                    published: parse_published(&episode.published),
+                })
+        })
+        .collect();
+
+    // Option orders None first, so reversing puts undated episodes last
+    entries.sort_by_key(|e| std::cmp::Reverse(e.published));
+    entries
+}
+
+/// Mark every unplayed episode published before the cutoff as played.
+/// Returns how many episodes were marked.
+pub fn mark_played_before(podcasts: &mut [Podcast], cutoff: DateTime<Utc>) -> usize {
+    let mut marked = 0;
+    for episode in podcasts.iter_mut().flat_map(|p| p.episodes.iter_mut()) {
+        if episode.played {
+            continue;
+        }
+        if let Some(published) = parse_published(&episode.published) {
+            if published < cutoff {
+                episode.played = true;
+                episode.touch();
+                marked += 1;
+            }
+        }
+    }
+    marked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::Episode;
+
+    fn episode(title: &str, published: &str, played: bool) -> Episode {
+        Episode {
+            title: title.to_string(),
+            published: published.to_string(),
+            audio_url: format!("https://example.com/{}.mp3", title),
+            played,
+            ..Default::default()
+        }
+    }
+
+    fn podcast(episodes: Vec<Episode>) -> Podcast {
+        Podcast {
+            title: "Test".to_string(),
+            description: String::new(),
+            url: "https://example.com/feed".to_string(),
+            episodes,
+            filter: Default::default(),
+            categories: Vec::new(),
+            image_url: None,
+        }
+    }
+
+    #[test]
+    fn test_inbox_orders_newest_first() {
+        let podcasts = vec![
+            podcast(vec![
+                episode("old", "Mon, 01 Jan 2024 10:00:00 +0000", false),
+                episode("played", "Tue, 02 Jan 2024 10:00:00 +0000", true),
+            ]),
+            podcast(vec![
+                episode("undated", "Unknown", false),
+                episode("new", "2024-03-01T10:00:00+00:00", false),
+            ]),
+        ];
+
+        let entries = collect_unplayed(&podcasts);
+        let order: Vec<(usize, usize)> = entries.iter().map(|e| (e.podcast_index, e.episode_index)).collect();
+        assert_eq!(order, vec![(1, 1), (0, 0), (1, 0)]);
+    }
+
+    #[test]
+    fn test_mark_played_before_cutoff() {
+        let mut podcasts = vec![podcast(vec![
+            episode("old", "Mon, 01 Jan 2024 10:00:00 +0000", false),
+            episode("new", "Fri, 01 Mar 2024 10:00:00 +0000", false),
+            episode("undated", "Unknown", false),
+        ])];
+
+        let cutoff = DateTime::parse_from_rfc3339("2024-02-01T00:00:00+00:00")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(mark_played_before(&mut podcasts, cutoff), 1);
+        assert!(podcasts[0].episodes[0].played);
+        assert!(!podcasts[0].episodes[1].played);
+        assert!(!podcasts[0].episodes[2].played);
+        // Sync needs to know when the episode was marked
+        assert!(podcasts[0].episodes[0].state_changed.is_some());
+        assert!(podcasts[0].episodes[1].state_changed.is_none());
+    }
+}
@@ -51,7 +51,13 @@ impl KeyMap {
 
         // Screen switching (like cmus)
         self.bind(KeyBinding::new(KeyCode::Char('1')), Action::SwitchToPodcasts);
+        self.bind(KeyBinding::new(KeyCode::Char('2')), Action::SwitchToInbox);
+        self.bind(KeyBinding::new(KeyCode::Char('3')), Action::SwitchToContinueListening);
+        self.bind(KeyBinding::new(KeyCode::Char('4')), Action::SwitchToHistory);
         self.bind(KeyBinding::new(KeyCode::Char('5')), Action::SwitchToBrowse);
+        self.bind(KeyBinding::new(KeyCode::Char('6')), Action::SwitchToStats);
+        self.bind(KeyBinding::new(KeyCode::Char('7')), Action::SwitchToNowPlaying);
+        self.bind(KeyBinding::new(KeyCode::Char('8')), Action::SwitchToBookmarks);
 
         // Navigation - Arrow keys
         self.bind(KeyBinding::new(KeyCode::Up), Action::MoveUp);
@@ -70,9 +76,22 @@ impl KeyMap {
         self.bind(KeyBinding::new(KeyCode::Char('a')), Action::AddFeed);
         self.bind(KeyBinding::new(KeyCode::Char('d')), Action::DeletePodcast);
         self.bind(KeyBinding::new(KeyCode::Char('r')), Action::RefreshFeed);
+        self.bind(KeyBinding::new(KeyCode::Char('R')), Action::RefreshAll);
+        self.bind(KeyBinding::new(KeyCode::Char('S')), Action::Sync);
 
         // Episode Management
         self.bind(KeyBinding::new(KeyCode::Char('m')), Action::TogglePlayed);
+        self.bind(KeyBinding::new(KeyCode::Char('f')), Action::ToggleStarred);
+        self.bind(KeyBinding::new(KeyCode::Char('e')), Action::Enqueue);
+        self.bind(KeyBinding::new(KeyCode::Char('M')), Action::MarkOlderThan);
+
+        // Episode Filters
+        self.bind(KeyBinding::new(KeyCode::Char('/')), Action::StartSearch);
+        self.bind(KeyBinding::new(KeyCode::Char('u')), Action::FilterUnplayed);
+        self.bind(KeyBinding::new(KeyCode::Char('p')), Action::FilterInProgress);
+        self.bind(KeyBinding::new(KeyCode::Char('o')), Action::FilterDownloaded);
+        self.bind(KeyBinding::new(KeyCode::Char('*')), Action::FilterStarred);
+        self.bind(KeyBinding::new(KeyCode::Char('F')), Action::ClearFilters);
 
         // Playback
         self.bind(KeyBinding::new(KeyCode::Char(' ')), Action::PlayPause);
@@ -83,6 +102,26 @@ impl KeyMap {
         self.bind(KeyBinding::new(KeyCode::Char('l')), Action::SeekForward);
         self.bind(KeyBinding::new(KeyCode::Char('[')), Action::SpeedDown);
         self.bind(KeyBinding::new(KeyCode::Char(']')), Action::SpeedUp);
+        self.bind(KeyBinding::new(KeyCode::Char('.')), Action::NextChapter);
+        self.bind(KeyBinding::new(KeyCode::Char(',')), Action::PrevChapter);
+        self.bind(KeyBinding::new(KeyCode::Char('-')), Action::VolumeDown);
+        self.bind(KeyBinding::new(KeyCode::Char('+')), Action::VolumeUp);
+        self.bind(KeyBinding::new(KeyCode::Char('=')), Action::VolumeUp);
+        self.bind(KeyBinding::new(KeyCode::Char('z')), Action::CycleSleepTimer);
+        self.bind(KeyBinding::new(KeyCode::Char('U')), Action::UndoChapterSkip);
+
+        // Bookmarks
+        self.bind(KeyBinding::new(KeyCode::Char('b')), Action::AddBookmark);
+        self.bind(KeyBinding::new(KeyCode::Char('B')), Action::ShowBookmarks);
+        self.bind(KeyBinding::new(KeyCode::Char('E')), Action::ExportBookmarks);
+
+        // Clips
+        self.bind(KeyBinding::new(KeyCode::Char('{')), Action::MarkClipStart);
+        self.bind(KeyBinding::new(KeyCode::Char('}')), Action::ExportClip);
+
+        // A-B loop
+        self.bind(KeyBinding::new(KeyCode::Char('A')), Action::SetLoopPoint);
+        self.bind(KeyBinding::new(KeyCode::Char('L')), Action::CycleLoopSlowdown);
     }
 }
 
@@ -0,0 +1,265 @@
+//! Changes to the podcast library, as sent to the daemon that owns it. A client
+//! attached to the daemon keeps a snapshot of what it last loaded and sends the
+//! difference instead of saving the files itself, so changes made by other
+//! clients in the meantime aren't overwritten.
+
+use crate::app::{App, Episode, EpisodeRef, Podcast};
+use crate::feed;
+use crate::filter::EpisodeFilter;
+use serde::{Deserialize, Serialize};
+
+/// The podcasts and queue as last loaded or sent
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    podcasts: Vec<Podcast>,
+    queue: Vec<EpisodeRef>,
+}
+
+impl Snapshot {
+    pub fn of(app: &App) -> Self {
+        Self {
+            podcasts: app.podcasts.clone(),
+            queue: app.queue.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum LibraryChange {
+    Subscribe { podcast: Podcast },
+    /// Remove a podcast, and its episodes from the queue
+    Unsubscribe { url: String },
+    /// Feed data fetched again, merged without touching local episode state
+    Refreshed { podcast: Podcast },
+    /// Kept only if it isn't older than the state it replaces
+    EpisodeState {
+        audio_url: String,
+        played: bool,
+        position_secs: u64,
+        last_played: Option<i64>,
+        state_changed: Option<i64>,
+    },
+    Star { audio_url: String, starred: bool },
+    Downloaded { audio_url: String, path: Option<String> },
+    Filter { podcast_url: String, filter: EpisodeFilter },
+    Enqueue { episode: EpisodeRef },
+    Dequeue { episode: EpisodeRef },
+}
+
+/// Whether the feed itself changed, as opposed to local state of its episodes
+fn feed_changed(before: &Podcast, after: &Podcast) -> bool {
+    before.title != after.title
+        || before.description != after.description
+        || before.categories != after.categories
+        || before.image_url != after.image_url
+        || before.episodes.len() != after.episodes.len()
+        || before
+            .episodes
+            .iter()
+            .zip(&after.episodes)
+            .any(|(b, a)| b.audio_url != a.audio_url || b.title != a.title || b.published != a.published)
+}
+
+fn episode_mut<'a>(app: &'a mut App, audio_url: &str) -> Option<&'a mut Episode> {
+    app.podcasts
+        .iter_mut()
+        .flat_map(|p| p.episodes.iter_mut())
+        .find(|e| e.audio_url == audio_url)
+}
+
+/// What changed in the app's podcasts and queue since the snapshot was taken
+pub fn diff(before: &Snapshot, app: &App) -> Vec<LibraryChange> {
+    let mut changes = Vec::new();
+
+    for old in &before.podcasts {
+        if !app.podcasts.iter().any(|p| p.url == old.url) {
+            changes.push(LibraryChange::Unsubscribe { url: old.url.clone() });
+        }
+    }
+
+    for podcast in &app.podcasts {
+        let Some(old) = before.podcasts.iter().find(|p| p.url == podcast.url) else {
+            changes.push(LibraryChange::Subscribe { podcast: podcast.clone() });
+            continue;
+        };
+        if feed_changed(old, podcast) {
+            changes.push(LibraryChange::Refreshed { podcast: podcast.clone() });
+        }
+        if old.filter != podcast.filter {
+            changes.push(LibraryChange::Filter {
+                podcast_url: podcast.url.clone(),
+                filter: podcast.filter.clone(),
+            });
+        }
+
+        for episode in &podcast.episodes {
+            let Some(was) = old.episodes.iter().find(|e| e.audio_url == episode.audio_url) else {
+                continue;
+            };
+            if (was.played, was.position_secs, was.last_played, was.state_changed)
+                != (episode.played, episode.position_secs, episode.last_played, episode.state_changed)
+            {
+                changes.push(LibraryChange::EpisodeState {
+                    audio_url: episode.audio_url.clone(),
+                    played: episode.played,
+                    position_secs: episode.position_secs,
+                    last_played: episode.last_played,
+                    state_changed: episode.state_changed,
+                });
+            }
+            if was.starred != episode.starred {
+                changes.push(LibraryChange::Star {
+                    audio_url: episode.audio_url.clone(),
+                    starred: episode.starred,
+                });
+            }
+            if was.download_path != episode.download_path {
+                changes.push(LibraryChange::Downloaded {
+                    audio_url: episode.audio_url.clone(),
+                    path: episode.download_path.clone(),
+                });
+            }
+        }
+    }
+
+    for episode in &before.queue {
+        if !app.queue.contains(episode) {
+            changes.push(LibraryChange::Dequeue { episode: episode.clone() });
+        }
+    }
+    for episode in &app.queue {
+        if !before.queue.contains(episode) {
+            changes.push(LibraryChange::Enqueue { episode: episode.clone() });
+        }
+    }
+
+    changes
+}
+
+/// Apply changes sent by another process. Changes to podcasts or episodes that
+/// have since been removed are dropped.
+pub fn apply(app: &mut App, changes: Vec<LibraryChange>) {
+    if changes.is_empty() {
+        return;
+    }
+
+    for change in changes {
+        match change {
+            LibraryChange::Subscribe { podcast } => {
+                if !app.is_subscribed(&podcast.url) {
+                    app.podcasts.push(podcast);
+                }
+            }
+            LibraryChange::Unsubscribe { url } => {
+                app.podcasts.retain(|p| p.url != url);
+                app.queue.retain(|e| e.podcast_url != url);
+            }
+            LibraryChange::Refreshed { podcast } => {
+                if let Some(existing) = app.podcasts.iter_mut().find(|p| p.url == podcast.url) {
+                    feed::merge_refresh(existing, podcast);
+                }
+            }
+            LibraryChange::EpisodeState { audio_url, played, position_secs, last_played, state_changed } => {
+                if let Some(episode) = episode_mut(app, &audio_url) {
+                    if state_changed >= episode.state_changed {
+                        episode.played = played;
+                        episode.position_secs = position_secs;
+                        episode.last_played = last_played;
+                        episode.state_changed = state_changed;
+                    }
+                }
+            }
+            LibraryChange::Star { audio_url, starred } => {
+                if let Some(episode) = episode_mut(app, &audio_url) {
+                    episode.starred = starred;
+                }
+            }
+            LibraryChange::Downloaded { audio_url, path } => {
+                if let Some(episode) = episode_mut(app, &audio_url) {
+                    episode.download_path = path;
+                }
+            }
+            LibraryChange::Filter { podcast_url, filter } => {
+                if let Some(podcast) = app.podcasts.iter_mut().find(|p| p.url == podcast_url) {
+                    podcast.filter = filter;
+                }
+            }
+            LibraryChange::Enqueue { episode } => {
+                if !app.queue.contains(&episode) {
+                    app.queue.push(episode);
+                }
+            }
+            LibraryChange::Dequeue { episode } => app.queue.retain(|e| *e != episode),
+        }
+    }
+
+    if app.selected_podcast_index >= app.podcasts.len() {
+        app.selected_podcast_index = app.podcasts.len().saturating_sub(1);
+    }
+    app.rebuild_views();
+    app.clamp_episode_selection();
+    app.needs_save = true;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn podcast(url: &str, episodes: &[&str]) -> Podcast {
+        Podcast {
+            title: url.to_string(),
+            description: String::new(),
+            url: url.to_string(),
+            episodes: episodes
+                .iter()
+                .map(|audio_url| Episode {
+                    title: audio_url.to_string(),
+                    audio_url: audio_url.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            filter: EpisodeFilter::default(),
+            categories: Vec::new(),
+            image_url: None,
+        }
+    }
+
+    #[test]
+    fn test_changes_from_two_clients_are_both_kept() {
+        let mut daemon = App::new();
+        daemon.podcasts = vec![podcast("feed-a", &["a1", "a2"]), podcast("feed-b", &["b1"])];
+
+        // A client loads the library, then marks an episode played and unsubscribes
+        let mut client = App::new();
+        client.podcasts = daemon.podcasts.clone();
+        let snapshot = Snapshot::of(&client);
+        client.podcasts[0].episodes[0].played = true;
+        client.podcasts[0].episodes[0].touch();
+        client.podcasts[0].episodes[1].starred = true;
+        client.remove_podcasts(&["feed-b".to_string()]);
+
+        // Meanwhile the daemon subscribed to another feed and queued an episode
+        daemon.podcasts.push(podcast("feed-c", &["c1"]));
+        daemon.queue.push(EpisodeRef { podcast_url: "feed-c".to_string(), audio_url: "c1".to_string() });
+
+        apply(&mut daemon, diff(&snapshot, &client));
+        let urls: Vec<_> = daemon.podcasts.iter().map(|p| p.url.as_str()).collect();
+        assert_eq!(urls, ["feed-a", "feed-c"]);
+        assert!(daemon.podcasts[0].episodes[0].played);
+        assert!(daemon.podcasts[0].episodes[1].starred);
+        assert_eq!(daemon.queue.len(), 1);
+        assert!(daemon.needs_save);
+
+        // An older position doesn't replace a newer one
+        let snapshot = Snapshot::of(&client);
+        client.podcasts[0].episodes[1].position_secs = 10;
+        client.podcasts[0].episodes[1].state_changed = Some(1);
+        daemon.podcasts[0].episodes[1].position_secs = 20;
+        daemon.podcasts[0].episodes[1].state_changed = Some(2);
+        apply(&mut daemon, diff(&snapshot, &client));
+        assert_eq!(daemon.podcasts[0].episodes[1].position_secs, 20);
+
+        assert!(diff(&Snapshot::of(&client), &client).is_empty());
+    }
+}
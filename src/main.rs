@@ -1,59 +1,200 @@
+mod ab_loop;
 mod actions;
 mod app;
+mod artwork;
+mod bookmarks;
 mod browse;
 mod chapters;
+mod cli;
+mod clip;
+mod continue_listening;
 mod config;
+mod daemon;
+mod directory;
+mod download;
 mod feed;
+mod filter;
+mod history;
+mod stats;
+mod suggestions;
+mod sync;
+mod inbox;
 mod keybindings;
+mod library;
+mod now_playing;
+mod opml;
 mod persistence;
 mod playback;
 mod theme;
 mod ui;
 
 use app::{App, AppEvent, InputMode};
+use history::{HistoryEvent, HistoryEventKind};
 use actions::Action;
 use keybindings::{KeyMap, KeyBinding};
-use playback::Player;
+use playback::{Playback, Player};
 
 use crossterm::{
     event::{self, Event, KeyCode, KeyEvent},
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
-use ratatui::{backend::CrosstermBackend, Terminal};
+use ratatui::{backend::{Backend, CrosstermBackend}, Terminal};
 use simplelog::*;
 use std::fs::File;
 use std::io::{Result, stdout};
 use std::sync::mpsc;
 
-fn handle_app_event(app: &mut App, event: AppEvent) {
+fn handle_app_event(app: &mut App, event: AppEvent, tx: &mpsc::Sender<AppEvent>) {
     match event {
-        AppEvent::SearchComplete(result) => {
+        AppEvent::BrowseResults { listing, page, result } => {
+            // Ignore results for a listing that has since been replaced
+            if listing != app.browse.listing {
+                return;
+            }
+            app.browse.search_pending = false;
             match result {
                 Ok(results) => {
-                    app.browse.search_results = results;
-                    app.browse.selected_index = 0;
-                    app.browse.showing_defaults = false;
-                    app.status_message = Some(format!("Found {} podcasts", app.browse.search_results.len()));
+                    app.browse.set_page_results(page, results);
+                    app.status_message = Some(format!(
+                        "Page {}: {} podcasts{}",
+                        page + 1,
+                        app.browse.search_results.len(),
+                        if app.browse.has_more { " (n for more)" } else { "" }
+                    ));
                 }
                 Err(e) => {
                     app.status_message = Some(format!("Search error: {}", e));
                 }
             }
         }
+        AppEvent::CategoriesLoaded(result) => {
+            match result {
+                Ok(categories) => {
+                    app.browse.categories = categories;
+                    app.browse.selected_category = 0;
+                    app.browse.categories_state = browse::LoadState::Loaded;
+                }
+                Err(e) => {
+                    log::error!("Failed to load categories: {}", e);
+                    app.browse.categories_state = browse::LoadState::Failed(e);
+                }
+            }
+        }
+        AppEvent::ToplistLoaded(result) => {
+            match result {
+                Ok(results) => {
+                    app.browse.toplist = browse::LoadState::Loaded;
+                    // Don't replace search results that arrived in the meantime
+                    if app.browse.is_featured() {
+                        app.browse.search_results = results;
+                        app.browse.selected_index = 0;
+                    }
+                }
+                Err(e) => {
+                    log::error!("Failed to load toplist: {}", e);
+                    app.browse.toplist = browse::LoadState::Failed(e);
+                }
+            }
+        }
         AppEvent::FeedLoaded(result) => {
             match result {
                 Ok(podcast) => {
-                    app.status_message = Some(format!("Added: {}", podcast.title));
-                    app.add_podcast(podcast);
+                    let title = podcast.title.clone();
+                    app.status_message = Some(if app.add_podcast(podcast) {
+                        format!("Added: {}", title)
+                    } else {
+                        format!("Already subscribed: {}", title)
+                    });
+                    if !app.pending_sync_updates.is_empty() {
+                        let pending = std::mem::take(&mut app.pending_sync_updates);
+                        app.pending_sync_updates = app.apply_sync_updates(pending);
+                    }
                 }
                 Err(e) => {
                     app.status_message = Some(format!("Error: {}", e));
                 }
             }
         }
-        AppEvent::PlaybackReady => {
-            // Playback buffer is ready, player will handle it
+        AppEvent::PreviewLoaded(url, result) => {
+            if let Some(preview) = app.browse.preview.as_mut().filter(|p| p.result.feed_url == url) {
+                if let Err(e) = &result {
+                    app.status_message = Some(format!("Error: {}", e));
+                }
+                preview.set_loaded(result);
+            }
+        }
+        AppEvent::DetailsLoaded(url, result) => match result {
+            Ok(Some(details)) => app.browse.details_loaded(&url, &details),
+            Ok(None) => {}
+            Err(e) => log::warn!("Failed to look up {} in the directory: {}", url, e),
+        },
+        AppEvent::FeedRefreshed(url, result) => {
+            app.pending_refreshes = app.pending_refreshes.saturating_sub(1);
+            match result {
+                Ok(fresh) => {
+                    if let Some(podcast) = app.podcasts.iter_mut().find(|p| p.url == url) {
+                        let added = feed::merge_refresh(podcast, fresh);
+                        app.refreshed_new += added.len();
+                        app.inbox.new_episodes.extend(added);
+                        app.needs_save = true;
+                    }
+                }
+                Err(e) => {
+                    log::warn!("Refresh failed for {}: {}", url, e);
+                }
+            }
+
+            app.status_message = if app.pending_refreshes == 0 {
+                Some(format!("Refreshed all: {} new episode(s)", app.refreshed_new))
+            } else {
+                Some(format!("Refreshing... {} podcast(s) left", app.pending_refreshes))
+            };
+            app.rebuild_views();
+            app.clamp_episode_selection();
+        }
+        AppEvent::SyncComplete(result) => {
+            app.is_syncing = false;
+            match result {
+                Ok(outcome) => {
+                    app.remove_podcasts(&outcome.unsubscribe);
+                    let unmatched = app.apply_sync_updates(outcome.updates.clone());
+                    app.status_message = Some(format!(
+                        "Synced: {} episode update(s) received, {} sent, +{}/-{} subscription(s)",
+                        outcome.updates.len() - unmatched.len(),
+                        outcome.pushed_actions,
+                        outcome.subscribe.len(),
+                        outcome.unsubscribe.len(),
+                    ));
+
+                    // Updates for new subscriptions are applied once their feeds load
+                    if !outcome.subscribe.is_empty() {
+                        app.pending_sync_updates = unmatched;
+                        let tx = tx.clone();
+                        std::thread::spawn(move || {
+                            for url in outcome.subscribe {
+                                let result = feed::fetch_and_parse(&url);
+                                let _ = tx.send(AppEvent::FeedLoaded(result));
+                            }
+                        });
+                    }
+                }
+                Err(e) => {
+                    app.status_message = Some(format!("Sync error: {}", e));
+                }
+            }
+        }
+        AppEvent::ArtworkLoaded(url, result) => {
+            app.artwork.image_loaded(url, result);
+        }
+        AppEvent::ChaptersLoaded(url, result) => {
+            app.chapters_loaded(&url, result);
+        }
+        AppEvent::ClipExported(result) => {
+            app.status_message = Some(match result {
+                Ok(path) => format!("Saved clip to {}", path.display()),
+                Err(e) => format!("Clip error: {}", e),
+            });
         }
     }
 }
@@ -97,40 +238,898 @@ fn handle_search_input(app: &mut App, key: KeyEvent, tx: &mpsc::Sender<AppEvent>
         KeyCode::Enter => {
             let query = app.browse.search_query.clone();
             app.cancel_search();
-            app.status_message = Some("Searching...".to_string());
+            if !query.is_empty() {
+                app.status_message = Some("Searching...".to_string());
+                app.browse.set_listing(browse::BrowseListing::Search(query));
+                app.browse.focus = app::PaneFocus::Right;
+                fetch_browse_page(app, tx, 0);
+            }
+        }
+        KeyCode::Esc => {
+            app.cancel_search();
+        }
+        _ => {}
+    }
+}
 
-            // Spawn background thread to perform search
-            let tx = tx.clone();
-            std::thread::spawn(move || {
-                let result = browse::search_podcasts(&query);
-                let _ = tx.send(AppEvent::SearchComplete(result));
+fn handle_episode_filter_input(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char(c) => {
+            app.update_filter(|f| f.text.push(c));
+            app.selected_episode_index = 0;
+        }
+        KeyCode::Backspace => {
+            app.update_filter(|f| {
+                f.text.pop();
             });
         }
+        KeyCode::Enter => {
+            app.finish_episode_filter();
+        }
         KeyCode::Esc => {
-            app.cancel_search();
+            app.update_filter(|f| f.text.clear());
+            app.finish_episode_filter();
+        }
+        _ => {}
+    }
+}
+
+fn handle_mark_older_input(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char(c) if c.is_ascii_digit() => {
+            app.input_buffer.push(c);
+        }
+        KeyCode::Backspace => {
+            app.input_buffer.pop();
+        }
+        KeyCode::Enter => {
+            let days = app.input_buffer.parse::<i64>();
+            app.cancel_input();
+            match days {
+                Ok(days) => {
+                    let cutoff = chrono::Utc::now() - chrono::Duration::days(days);
+                    let marked = inbox::mark_played_before(&mut app.podcasts, cutoff);
+                    app.rebuild_views();
+                    app.needs_save = true;
+                    app.status_message = Some(format!(
+                        "Marked {} episode(s) older than {} day(s) as played",
+                        marked, days
+                    ));
+                }
+                Err(_) => {
+                    app.status_message = Some("Enter a number of days".to_string());
+                }
+            }
+        }
+        KeyCode::Esc => {
+            app.cancel_input();
         }
         _ => {}
     }
 }
 
+fn handle_bookmark_note_input(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char(c) => {
+            app.input_buffer.push(c);
+        }
+        KeyCode::Backspace => {
+            app.input_buffer.pop();
+        }
+        KeyCode::Enter => {
+            app.finish_bookmark();
+        }
+        KeyCode::Esc => {
+            app.cancel_input();
+            app.pending_bookmark = None;
+        }
+        _ => {}
+    }
+}
+
+/// Refresh every subscription on a background thread, one event per feed
+fn refresh_all_feeds(app: &mut App, tx: &mpsc::Sender<AppEvent>) {
+    if app.pending_refreshes > 0 {
+        app.status_message = Some("Refresh already in progress".to_string());
+        return;
+    }
+
+    let urls: Vec<String> = app.podcasts.iter().map(|p| p.url.clone()).collect();
+    if urls.is_empty() {
+        return;
+    }
+
+    app.pending_refreshes = urls.len();
+    app.refreshed_new = 0;
+    app.status_message = Some(format!("Refreshing {} podcast(s)...", urls.len()));
+
+    let tx = tx.clone();
+    std::thread::spawn(move || {
+        for url in urls {
+            let result = feed::fetch_and_parse(&url);
+            let _ = tx.send(AppEvent::FeedRefreshed(url, result));
+        }
+    });
+}
+
+/// Sync subscriptions and episode state with the configured server on a background thread
+fn start_sync(app: &mut App, tx: &mpsc::Sender<AppEvent>) {
+    let Some(config) = app.sync_config.clone() else {
+        app.status_message = Some("Sync is not configured (add a [sync] section to config.toml)".to_string());
+        return;
+    };
+    if app.is_syncing {
+        app.status_message = Some("Sync already in progress".to_string());
+        return;
+    }
+
+    app.is_syncing = true;
+    app.status_message = Some("Syncing...".to_string());
+
+    let podcasts = app.podcasts.clone();
+    let tx = tx.clone();
+    std::thread::spawn(move || {
+        let result = sync::backend_for(&config).and_then(|backend| {
+            let mut state = sync::load_state(&config)?;
+            let now = chrono::Utc::now().timestamp();
+            let outcome = sync::run(&*backend, &config.device_id, &podcasts, &mut state, now)?;
+            sync::save_state(&state)?;
+            Ok(outcome)
+        });
+        let _ = tx.send(AppEvent::SyncComplete(result));
+    });
+}
+
+/// Append an event for the playing episode to the listening history
+fn record_history(app: &App, player: &dyn Playback, kind: HistoryEventKind, from_secs: Option<u64>) {
+    // The daemon records what it plays
+    if player.remote().is_some() {
+        return;
+    }
+    let Some((p, e)) = app.playing_episode_indices() else {
+        return;
+    };
+    let podcast = &app.podcasts[p];
+    let episode = &podcast.episodes[e];
+
+    // The player has no position yet when playback is just starting
+    let position_secs = if kind == HistoryEventKind::Start {
+        episode.position_secs
+    } else {
+        player.get_position().as_secs()
+    };
+
+    let event = HistoryEvent {
+        timestamp: chrono::Utc::now().timestamp(),
+        kind,
+        podcast_title: podcast.title.clone(),
+        podcast_url: podcast.url.clone(),
+        episode_title: episode.title.clone(),
+        audio_url: episode.audio_url.clone(),
+        position_secs,
+        from_secs,
+        speed: player.get_speed(),
+    };
+
+    if let Err(e) = history::append(&event) {
+        log::error!("Failed to record history: {}", e);
+    }
+}
+
+/// Save the playing episode's position so it can be resumed later
+fn save_playing_position(app: &mut App, player: &dyn Playback) {
+    // The daemon saves the position of what it plays
+    if app.playback.start.is_none() || player.remote().is_some() {
+        return;
+    }
+
+    let current_position = player.get_position().as_secs();
+    if let Some(episode) = app.playing_episode_mut() {
+        episode.position_secs = current_position;
+        episode.last_played = Some(chrono::Utc::now().timestamp());
+        episode.touch();
+        log::debug!("Saved position: {}s for episode '{}'", current_position, episode.title);
+        app.needs_save = true;
+    }
+    app.rebuild_views();
+}
+
+/// Save the podcasts and the queue
+fn save_library(app: &mut App) -> std::result::Result<(), String> {
+    let podcasts = persistence::save_podcasts(&app.podcasts);
+    let queue = persistence::save_queue(&app.queue);
+    app.needs_save = false;
+    app.library_modified = persistence::library_modified();
+    podcasts.and(queue)
+}
+
+/// Save the library, or have the daemon save what changed when attached to one,
+/// so changes made by its other clients aren't overwritten
+fn store_library(app: &mut App, player: &dyn Playback) -> std::result::Result<(), String> {
+    let (Some(remote), Some(snapshot)) = (player.remote(), &app.library_snapshot) else {
+        return save_library(app);
+    };
+
+    let changes = library::diff(snapshot, app);
+    if !changes.is_empty() {
+        // Nothing else writes the files once the daemon is gone
+        if !remote.is_running() {
+            return save_library(app);
+        }
+        // Retried once the daemon answers again
+        if !remote.is_connected() {
+            return Err("The daemon isn't answering".to_string());
+        }
+        remote.update(changes)?;
+    }
+    app.library_snapshot = Some(library::Snapshot::of(app));
+    app.needs_save = false;
+    Ok(())
+}
+
+/// Load the podcasts and the queue again if another process saved them, as the
+/// daemon does for the TUI attached to it
+fn reload_changed_library(app: &mut App) {
+    let modified = persistence::library_modified();
+    if app.needs_save || modified == app.library_modified {
+        return;
+    }
+
+    // A file caught halfway through being written is read again next time
+    let podcasts = match persistence::load_podcasts() {
+        Ok(podcasts) => podcasts,
+        Err(e) => {
+            log::warn!("Failed to reload podcasts: {}", e);
+            return;
+        }
+    };
+    app.podcasts = podcasts;
+    app.queue = persistence::load_queue().unwrap_or_default();
+    app.library_modified = modified;
+    if app.library_snapshot.is_some() {
+        app.library_snapshot = Some(library::Snapshot::of(app));
+    }
+
+    if app.selected_podcast_index >= app.podcasts.len() {
+        app.selected_podcast_index = app.podcasts.len().saturating_sub(1);
+    }
+    app.rebuild_views();
+    app.clamp_episode_selection();
+}
+
+/// Show the episode the daemon is playing, which another client may have changed
+fn follow_daemon(app: &mut App, remote: &daemon::client::RemotePlayer, tx: &mpsc::Sender<AppEvent>) {
+    let (url, duration_secs) = {
+        let status = remote.status();
+        (status.audio_url.clone(), status.duration_secs)
+    };
+    if url == app.playback.url {
+        return;
+    }
+
+    let Some(url) = url else {
+        clear_playback(app);
+        app.rebuild_views();
+        return;
+    };
+    app.playback.url = Some(url);
+    app.playback.start = Some(std::time::Instant::now());
+    app.playback.duration_secs = duration_secs;
+    app.playback.paused_at = None;
+    app.playback.paused_duration = std::time::Duration::ZERO;
+    app.now_playing.notes_scroll = 0;
+    app.ab_loop = None;
+    match app.playing_episode().cloned() {
+        Some(episode) => {
+            app.status_message = Some(format!("Playing: {}", episode.title));
+            load_playing_chapters(app, &episode, tx);
+        }
+        None => app.playback.chapters = None,
+    }
+}
+
+/// Start playing the episode at the given indices, resuming from its saved position
+fn start_episode(
+    app: &mut App,
+    player: &mut dyn Playback,
+    podcast_index: usize,
+    episode_index: usize,
+    tx: &mpsc::Sender<AppEvent>,
+) {
+    let episode = app.podcasts.get(podcast_index)
+        .and_then(|p| p.episodes.get(episode_index))
+        .cloned();
+
+    if let Some(episode) = episode {
+        start_playback(app, player, &episode, tx);
+    }
+}
+
+/// Play an episode, which may be one from the Browse preview that isn't subscribed to yet
+fn start_playback(app: &mut App, player: &mut dyn Playback, episode: &app::Episode, tx: &mpsc::Sender<AppEvent>) {
+    if episode.audio_url.is_empty() {
+        app.status_message = Some("No audio URL for this episode".to_string());
+        return;
+    }
+
+    // Play the downloaded file when there is one. The daemon makes that choice for its clients.
+    let source = match download::local_file(episode) {
+        Some(path) if player.remote().is_none() => path.to_string_lossy().into_owned(),
+        _ => episode.audio_url.clone(),
+    };
+
+    // Resume from saved position if available
+    let position_secs = episode.position_secs;
+    match player.play(&source, position_secs) {
+        Ok(_) => {
+            if position_secs > 0 {
+                app.status_message = Some(format!("Resuming: {} at {}:{:02}",
+                    episode.title, position_secs / 60, position_secs % 60));
+            } else {
+                app.status_message = Some(format!("Playing: {}", episode.title));
+            }
+            app.playback.url = Some(episode.audio_url.clone());
+            if let Some(episode) = app.playing_episode_mut() {
+                episode.last_played = Some(chrono::Utc::now().timestamp());
+            }
+            record_history(app, player, HistoryEventKind::Start, None);
+
+            // Start playback tracking
+            app.playback.start = Some(std::time::Instant::now());
+            app.playback.duration_secs = episode.duration.map(|d| d.as_secs()).unwrap_or(0);
+            app.playback.paused_at = None;
+            app.playback.paused_duration = std::time::Duration::ZERO;
+            app.now_playing.notes_scroll = 0;
+            app.ab_loop = None;
+            load_playing_chapters(app, episode, tx);
+        }
+        Err(e) => {
+            app.status_message = Some(format!("Error: {}", e));
+            app.playback.url = None;
+            app.playback.start = None;
+        }
+    }
+}
+
+/// Load the chapters of the episode that just started playing on a background thread
+fn load_playing_chapters(app: &mut App, episode: &app::Episode, tx: &mpsc::Sender<AppEvent>) {
+    app.playback.chapters = None;
+    load_chapters(episode, None, tx);
+}
+
+/// Load an episode's chapters from the cache, or find and cache them, on a background thread
+fn load_chapters(episode: &app::Episode, audio_path: Option<std::path::PathBuf>, tx: &mpsc::Sender<AppEvent>) {
+    let episode = episode.clone();
+    let tx = tx.clone();
+    std::thread::spawn(move || {
+        let result = chapters::cache::load(&episode, audio_path.as_deref());
+        let _ = tx.send(AppEvent::ChaptersLoaded(episode.audio_url, result));
+    });
+}
+
+/// Open the chapters popup for the targeted episode, or the playing one if
+/// nothing is selected, and load its chapters if they aren't loaded yet
+fn show_chapters(app: &mut App, player: &dyn Playback, tx: &mpsc::Sender<AppEvent>) {
+    let episode = app
+        .target_episode_indices()
+        .and_then(|(p, e)| app.podcasts.get(p)?.episodes.get(e))
+        .or_else(|| app.preview_episode())
+        .or_else(|| app.playing_episode())
+        .cloned();
+    let Some(episode) = episode else {
+        app.status_message = Some("No episode selected".to_string());
+        return;
+    };
+
+    if app.open_chapters_popup(&episode, player.get_position().as_secs_f64()) {
+        // The partly streamed file of the playing episode may have embedded chapters
+        let audio_path = player
+            .get_temp_file_path()
+            .filter(|_| app.playback.url.as_ref() == Some(&episode.audio_url));
+        load_chapters(&episode, audio_path, tx);
+    }
+}
+
+/// Seek to the start of a chapter of the playing episode
+fn jump_to_chapter(app: &mut App, player: &dyn Playback, chapter: &chapters::Chapter) {
+    if app.playback.start.is_none() {
+        app.status_message = Some("Start playback first".to_string());
+        return;
+    }
+
+    let from = player.get_position().as_secs();
+    match player.seek_to(std::time::Duration::from_secs_f64(chapter.start_time.max(0.0))) {
+        Ok(_) => {
+            record_history(app, player, HistoryEventKind::Seek, Some(from));
+            app.status_message = Some(format!("Chapter: {}", chapter.title));
+        }
+        Err(e) => {
+            app.status_message = Some(format!("Seek error: {}", e));
+        }
+    }
+}
+
+/// Skip past the playing chapter if its title matches a skip pattern
+fn skip_matching_chapter(app: &mut App, player: &dyn Playback) {
+    if app.skip_rules.is_empty() {
+        return;
+    }
+    let (Some(url), Some(list)) = (app.playback.url.clone(), app.playback.chapters.as_ref()) else {
+        return;
+    };
+    let podcast_url = app.playing_podcast().map(|p| p.url.clone());
+
+    let position = player.get_position();
+    let rules = &app.skip_rules;
+    let skip = app.chapter_skips.check(
+        &url,
+        list,
+        position.as_secs_f64(),
+        app.playback.duration_secs as f64,
+        |chapter| rules.matches(podcast_url.as_deref(), &chapter.title),
+    );
+    let Some(skip) = skip else { return };
+
+    match player.seek_to(std::time::Duration::from_secs_f64(skip.to)) {
+        Ok(_) => {
+            record_history(app, player, HistoryEventKind::Seek, Some(position.as_secs()));
+            app.status_message = Some(format!("Skipped chapter: {} (U to undo)", skip.title));
+        }
+        Err(e) => {
+            // Don't retry on every tick
+            app.chapter_skips.allow(&skip);
+            app.status_message = Some(format!("Seek error: {}", e));
+        }
+    }
+}
+
+/// Set the start of an A-B loop, then its end, then clear it
+fn set_loop_point(app: &mut App, player: &dyn Playback) {
+    let Some(url) = app.playback.url.clone().filter(|_| app.playback.start.is_some()) else {
+        app.status_message = Some("Start playback first".to_string());
+        return;
+    };
+    let position = player.get_position().as_secs_f64();
+
+    match app.ab_loop.take() {
+        Some(mut ab_loop) if ab_loop.audio_url == url && ab_loop.b.is_none() => {
+            if position <= ab_loop.a {
+                app.status_message = Some("The loop end must be after its start".to_string());
+                app.ab_loop = Some(ab_loop);
+                return;
+            }
+            ab_loop.b = Some(position);
+            app.status_message = Some(format!(
+                "Looping {}-{} (A to clear, L for loop speed)",
+                now_playing::format_time(ab_loop.a as u64),
+                now_playing::format_time(position as u64)
+            ));
+            if let Err(e) = player.seek_to(std::time::Duration::from_secs_f64(ab_loop.a)) {
+                app.status_message = Some(format!("Seek error: {}", e));
+            }
+            app.ab_loop = Some(ab_loop);
+        }
+        Some(ab_loop) => {
+            player.set_speed(ab_loop.base_speed);
+            app.status_message = Some("Loop cleared".to_string());
+        }
+        None => {
+            app.ab_loop = Some(ab_loop::AbLoop::new(url, position, player.get_speed()));
+            app.status_message = Some(format!(
+                "Loop starts at {} - press A at the end",
+                now_playing::format_time(position as u64)
+            ));
+        }
+    }
+}
+
+/// Jump back to the start of the A-B loop at its end, playing the loop at its own speed
+fn repeat_ab_loop(app: &mut App, player: &dyn Playback) {
+    let Some(ab_loop) = app.ab_loop.as_ref().filter(|l| app.playback.url.as_ref() == Some(&l.audio_url)) else {
+        return;
+    };
+
+    let mut position = player.get_position().as_secs_f64();
+    if let Some(a) = ab_loop.restart_at(position) {
+        if let Err(e) = player.seek_to(std::time::Duration::from_secs_f64(a)) {
+            player.set_speed(ab_loop.base_speed);
+            app.ab_loop = None;
+            app.status_message = Some(format!("Loop cleared, seek error: {}", e));
+            return;
+        }
+        position = a;
+    }
+
+    let speed = ab_loop.speed_at(position, app.loop_slowdown);
+    if (player.get_speed() - speed).abs() > f32::EPSILON {
+        player.set_speed(speed);
+    }
+}
+
+/// Write the playing episode from the marked clip start to the current position
+/// into the clips directory on a background thread
+fn export_clip(app: &mut App, player: &dyn Playback, tx: &mpsc::Sender<AppEvent>) {
+    let start_secs = match &app.clip_start {
+        Some((url, start)) if app.playback.url.as_ref() == Some(url) => *start,
+        _ => {
+            app.status_message = Some("Press { at the start of the clip first".to_string());
+            return;
+        }
+    };
+    let end_secs = player.get_position().as_secs_f64();
+    if end_secs <= start_secs {
+        app.status_message = Some("The clip end must be after its start".to_string());
+        return;
+    }
+
+    let (Some(podcast), Some(episode)) = (app.playing_podcast(), app.playing_episode()) else {
+        return;
+    };
+    // Prefer the complete download over the partly streamed temp file
+    let source = episode
+        .download_path
+        .as_ref()
+        .map(std::path::PathBuf::from)
+        .filter(|path| path.exists())
+        .or_else(|| player.get_temp_file_path());
+    let Some(source) = source else {
+        app.status_message = Some("No audio file to cut the clip from".to_string());
+        return;
+    };
+    let info = clip::ClipInfo {
+        podcast_title: podcast.title.clone(),
+        episode_title: episode.title.clone(),
+        audio_url: episode.audio_url.clone(),
+        start_secs,
+        end_secs,
+    };
+    let dir = match app.clips_config.directory() {
+        Ok(dir) => dir,
+        Err(e) => {
+            app.status_message = Some(format!("Clip error: {}", e));
+            return;
+        }
+    };
+
+    app.clip_start = None;
+    app.status_message = Some(format!("Exporting clip {}...", info.range()));
+    let with_tags = app.clips_config.id3;
+    let tx = tx.clone();
+    std::thread::spawn(move || {
+        let _ = tx.send(AppEvent::ClipExported(clip::export(&source, &dir, &info, with_tags)));
+    });
+}
+
+/// Seek to a bookmark, starting its episode first if something else is playing
+fn jump_to_bookmark(app: &mut App, player: &mut dyn Playback, bookmark: &bookmarks::Bookmark, tx: &mpsc::Sender<AppEvent>) {
+    if app.playback.start.is_some() && app.playback.url.as_ref() == Some(&bookmark.audio_url) {
+        let from = player.get_position().as_secs();
+        match player.seek_to(std::time::Duration::from_secs(bookmark.position_secs)) {
+            Ok(_) => {
+                record_history(app, player, HistoryEventKind::Seek, Some(from));
+                app.status_message = Some(format!("Bookmark: {}", bookmark.label()));
+            }
+            Err(e) => {
+                app.status_message = Some(format!("Seek error: {}", e));
+            }
+        }
+        return;
+    }
+
+    let Some((p, e)) = app.find_episode(&bookmark.episode()) else {
+        app.status_message = Some(format!("'{}' is no longer in your subscriptions", bookmark.episode_title));
+        return;
+    };
+    if app.playback.start.is_some() {
+        record_history(app, player, HistoryEventKind::Skip, None);
+    }
+    save_playing_position(app, player);
+
+    let mut episode = app.podcasts[p].episodes[e].clone();
+    episode.position_secs = bookmark.position_secs;
+    start_playback(app, player, &episode, tx);
+    if app.playback.url.as_ref() == Some(&bookmark.audio_url) {
+        app.status_message = Some(format!("Bookmark: {}", bookmark.label()));
+    }
+}
+
+/// Pause the playing episode and record it in the history
+fn pause_playback(app: &mut App, player: &dyn Playback) {
+    player.pause();
+    record_history(app, player, HistoryEventKind::Pause, None);
+    if let Some(episode) = app.playing_episode() {
+        app.status_message = Some(format!("Paused: {}", episode.title));
+    }
+
+    // Mark pause time
+    app.playback.paused_at = Some(std::time::Instant::now());
+}
+
+/// Resume the paused episode and record it in the history
+fn resume_playback(app: &mut App, player: &dyn Playback) {
+    player.resume();
+    record_history(app, player, HistoryEventKind::Resume, None);
+    if let Some(episode) = app.playing_episode() {
+        app.status_message = Some(format!("Resumed: {}", episode.title));
+    }
+
+    // Resume playback tracking
+    if let Some(paused_at) = app.playback.paused_at {
+        app.playback.paused_duration += std::time::Instant::now().duration_since(paused_at);
+        app.playback.paused_at = None;
+    }
+}
+
+/// Stop playback, saving the position to resume from later
+fn stop_playback(app: &mut App, player: &mut dyn Playback) {
+    // Save current playback position before stopping
+    if app.playback.start.is_some() {
+        record_history(app, player, HistoryEventKind::Stop, None);
+    }
+    save_playing_position(app, player);
+
+    player.stop();
+    app.status_message = Some("Stopped".to_string());
+    clear_playback(app);
+}
+
+/// Forget the playing episode once the player has stopped
+fn clear_playback(app: &mut App) {
+    app.playback.url = None;
+    app.ab_loop = None;
+
+    // Clear playback tracking
+    app.playback.start = None;
+    app.playback.paused_at = None;
+    app.playback.paused_duration = std::time::Duration::ZERO;
+}
+
+/// Mark the finished episode as played and move on to the next queued episode
+fn handle_playback_finished(app: &mut App, player: &mut dyn Playback, tx: &mpsc::Sender<AppEvent>) {
+    record_history(app, player, HistoryEventKind::Finish, None);
+
+    if let Some(episode) = app.playing_episode_mut() {
+        log::info!("Finished episode '{}'", episode.title);
+        episode.played = true;
+        episode.position_secs = 0;
+        episode.touch();
+        app.needs_save = true;
+    }
+
+    player.stop();
+    clear_playback(app);
+    app.rebuild_views();
+
+    if let Some((p, e)) = app.pop_queue() {
+        start_episode(app, player, p, e, tx);
+    } else {
+        app.status_message = Some("Finished".to_string());
+    }
+}
+
+/// Load the featured podcasts on a background thread, from the disk cache while it is fresh
+fn load_toplist(app: &mut App, tx: &mpsc::Sender<AppEvent>, force: bool) {
+    if app.browse.toplist == browse::LoadState::Loading {
+        return;
+    }
+    app.browse.toplist = browse::LoadState::Loading;
+
+    let directory = std::sync::Arc::clone(&app.directory);
+    let tx = tx.clone();
+    std::thread::spawn(move || {
+        let providers = directory.provider_names();
+        let now = chrono::Utc::now().timestamp();
+        let cached = browse::load_toplist_cache().unwrap_or_else(|e| {
+            log::warn!("Ignoring toplist cache: {}", e);
+            None
+        });
+
+        if let Some(cache) = cached.as_ref().filter(|c| !force && c.is_fresh(&providers, now)) {
+            let _ = tx.send(AppEvent::ToplistLoaded(Ok(cache.results.clone())));
+            return;
+        }
+
+        let result = match directory.toplist(browse::TOPLIST_LIMIT) {
+            Ok(results) => {
+                if !results.is_empty() {
+                    let cache = browse::ToplistCache {
+                        fetched_at: now,
+                        providers: providers.iter().map(|p| p.to_string()).collect(),
+                        results: results.clone(),
+                    };
+                    if let Err(e) = browse::save_toplist_cache(&cache) {
+                        log::error!("Failed to cache toplist: {}", e);
+                    }
+                }
+                Ok(results)
+            }
+            // Offline: an expired list is better than none
+            Err(e) => match cached {
+                Some(cache) if cache.providers == providers => {
+                    log::warn!("Using expired toplist cache: {}", e);
+                    Ok(cache.results)
+                }
+                _ => Err(e),
+            },
+        };
+        let _ = tx.send(AppEvent::ToplistLoaded(result));
+    });
+}
+
+/// Load the category pane on a background thread
+fn load_categories(app: &mut App, tx: &mpsc::Sender<AppEvent>) {
+    if app.browse.categories_state == browse::LoadState::Loading {
+        return;
+    }
+    app.browse.categories_state = browse::LoadState::Loading;
+
+    let directory = std::sync::Arc::clone(&app.directory);
+    let tx = tx.clone();
+    std::thread::spawn(move || {
+        let result = directory.categories(browse::CATEGORY_LIMIT);
+        let _ = tx.send(AppEvent::CategoriesLoaded(result));
+    });
+}
+
+/// Look up the previewed feed, or the selected one if subscribed, in the directory
+/// to fill in details its listing left out
+fn load_details(app: &mut App, tx: &mpsc::Sender<AppEvent>) {
+    let feed_url = match &app.browse.preview {
+        Some(preview) => preview.result.feed_url.clone(),
+        None => match app.browse.selected_result() {
+            Some(result) if app.is_subscribed(&result.feed_url) => result.feed_url.clone(),
+            _ => return,
+        },
+    };
+    if !app.browse.looked_up.insert(browse::normalize_feed_url(&feed_url)) {
+        return;
+    }
+
+    let directory = std::sync::Arc::clone(&app.directory);
+    let tx = tx.clone();
+    std::thread::spawn(move || {
+        let result = directory.lookup(&feed_url);
+        let _ = tx.send(AppEvent::DetailsLoaded(feed_url, result));
+    });
+}
+
+/// Fetch a page of the current search or category listing on a background thread
+fn fetch_browse_page(app: &mut App, tx: &mpsc::Sender<AppEvent>, page: usize) {
+    let listing = app.browse.listing.clone();
+    if listing == browse::BrowseListing::Featured {
+        return;
+    }
+    app.browse.search_pending = true;
+
+    let source = match listing {
+        browse::BrowseListing::Suggested => Some(suggestions::SuggestionSource::new(
+            &app.podcasts,
+            app.sync_config.as_ref(),
+            &app.browse.categories,
+        )),
+        _ => None,
+    };
+
+    let directory = std::sync::Arc::clone(&app.directory);
+    let tx = tx.clone();
+    std::thread::spawn(move || {
+        let limit = browse::page_fetch_limit(page);
+        let result = match (&listing, &source) {
+            (browse::BrowseListing::Search(query), _) => directory.search(query, limit),
+            (browse::BrowseListing::Category(category), _) => directory.category_podcasts(category, limit),
+            (browse::BrowseListing::Suggested, Some(source)) => suggestions::fetch(source, &directory, limit),
+            _ => Ok(Vec::new()),
+        };
+        let _ = tx.send(AppEvent::BrowseResults { listing, page, result });
+    });
+}
+
+/// Subscribe to the previewed podcast using the feed that was already fetched
+fn subscribe_from_preview(app: &mut App) {
+    let Some(preview) = app.browse.preview.as_mut() else {
+        return;
+    };
+
+    if let Some(podcast) = preview.podcast.take() {
+        app.browse.preview = None;
+        let title = podcast.title.clone();
+        app.status_message = Some(if app.add_podcast(podcast) {
+            format!("Added: {}", title)
+        } else {
+            format!("Already subscribed: {}", title)
+        });
+        app.rebuild_views();
+        app.screen = app::AppScreen::Podcasts;
+    } else {
+        app.status_message = Some(match &preview.state {
+            browse::LoadState::Failed(e) => format!("Can't subscribe: {}", e),
+            _ => "Feed is still loading...".to_string(),
+        });
+    }
+}
+
+fn handle_preview_key(app: &mut App, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Esc if !app.show_help => {
+            app.browse.preview = None;
+            true
+        }
+        KeyCode::Enter => {
+            subscribe_from_preview(app);
+            true
+        }
+        // Browse keys are disabled while previewing, the rest fall through to the keymap
+        KeyCode::Char('/') | KeyCode::Char('r') | KeyCode::Char('t') | KeyCode::Char('n') | KeyCode::Char('N')
+        | KeyCode::PageDown | KeyCode::PageUp => true,
+        _ => false,
+    }
+}
+
 fn handle_browse_screen_key(app: &mut App, key: KeyEvent, tx: &mpsc::Sender<AppEvent>) -> bool {
+    if app.browse.preview.is_some() {
+        return handle_preview_key(app, key);
+    }
+
     match key.code {
         KeyCode::Char('/') => {
             app.start_search();
             true
         }
+        KeyCode::Char('r') => {
+            // Reload (or retry) the featured podcasts
+            app.browse.search_query.clear();
+            app.browse.set_listing(browse::BrowseListing::Featured);
+            load_toplist(app, tx, true);
+            if matches!(app.browse.categories_state, browse::LoadState::Failed(_)) {
+                load_categories(app, tx);
+            }
+            true
+        }
+        KeyCode::Char('t') => {
+            // Switch between the featured and suggested tabs
+            if app.browse.listing == browse::BrowseListing::Suggested {
+                app.browse.set_listing(browse::BrowseListing::Featured);
+                load_toplist(app, tx, false);
+            } else {
+                app.browse.set_listing(browse::BrowseListing::Suggested);
+                app.browse.focus = app::PaneFocus::Right;
+                fetch_browse_page(app, tx, 0);
+            }
+            true
+        }
+        KeyCode::Char('n') | KeyCode::PageDown => {
+            if app.browse.has_more && !app.browse.search_pending {
+                fetch_browse_page(app, tx, app.browse.page + 1);
+            }
+            true
+        }
+        KeyCode::Char('N') | KeyCode::PageUp => {
+            if app.browse.page > 0 && !app.browse.search_pending {
+                fetch_browse_page(app, tx, app.browse.page - 1);
+            }
+            true
+        }
+        KeyCode::Enter if app.browse.focus == app::PaneFocus::Left => {
+            // Show the podcasts in the selected category
+            if let Some(category) = app.browse.selected_category().cloned() {
+                app.status_message = Some(format!("Loading {}...", category.title));
+                app.browse.set_listing(browse::BrowseListing::Category(category));
+                app.browse.focus = app::PaneFocus::Right;
+                fetch_browse_page(app, tx, 0);
+            }
+            true
+        }
         KeyCode::Enter => {
-            // Subscribe to selected podcast
-            if let Some(result) = app.browse.selected_result() {
+            // Preview the selected podcast without subscribing
+            if let Some(result) = app.browse.selected_result().cloned() {
                 let feed_url = result.feed_url.clone();
-                app.status_message = Some("Loading feed...".to_string());
-                app.screen = app::AppScreen::Podcasts;
+                app.browse.preview = Some(browse::Preview::new(result));
 
                 // Spawn background thread to fetch and parse feed
                 let tx = tx.clone();
                 std::thread::spawn(move || {
                     let result = feed::fetch_and_parse(&feed_url);
-                    let _ = tx.send(AppEvent::FeedLoaded(result));
+                    let _ = tx.send(AppEvent::PreviewLoaded(feed_url, result));
                 });
             }
             true
@@ -141,9 +1140,10 @@ fn handle_browse_screen_key(app: &mut App, key: KeyEvent, tx: &mpsc::Sender<AppE
 
 fn handle_normal_key(
     app: &mut App,
-    player: &mut Player,
+    player: &mut dyn Playback,
     keymap: &KeyMap,
     key: KeyEvent,
+    tx: &mpsc::Sender<AppEvent>,
 ) -> bool {
     // Clear status message on any keypress
     app.status_message = None;
@@ -158,49 +1158,79 @@ fn handle_normal_key(
             app.show_info = false;
             return false;
         }
-        if app.show_chapters {
-            app.show_chapters = false;
-            app.cached_chapters = None; // Clear cache when closing with Escape
+        if app.chapters_popup.is_some() {
+            app.chapters_popup = None;
+            return false;
+        }
+        if app.bookmarks_popup.is_some() {
+            app.bookmarks_popup = None;
             return false;
         }
     }
 
-    // Handle chapter navigation when chapters popup is visible
-    if app.show_chapters {
+    // Handle bookmark navigation when the bookmarks popup is visible
+    if app.bookmarks_popup.is_some() {
         match key.code {
             KeyCode::Char('j') | KeyCode::Down => {
-                // Use cached chapters to get max index
-                if let Some(chapter_list) = &app.cached_chapters {
-                    let max_idx = chapter_list.chapters.len().saturating_sub(1);
-                    app.move_chapter_down(max_idx);
+                app.move_bookmark_down();
+                return false;
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                app.move_bookmark_up();
+                return false;
+            }
+            KeyCode::Enter => {
+                if let Some(bookmark) = app.selected_popup_bookmark() {
+                    jump_to_bookmark(app, player, &bookmark, tx);
+                }
+                app.bookmarks_popup = None;
+                return false;
+            }
+            KeyCode::Char('d') => {
+                if let Some(bookmark) = app.selected_popup_bookmark() {
+                    app.delete_bookmark(&bookmark);
                 }
                 return false;
             }
+            KeyCode::Char('B') => {
+                app.bookmarks_popup = None;
+                return false;
+            }
+            _ => {}
+        }
+    }
+
+    // Enter on the Bookmarks screen jumps to the selected bookmark
+    if app.screen == app::AppScreen::Bookmarks && key.code == KeyCode::Enter {
+        if let Some(bookmark) = app.bookmarks.selected().cloned() {
+            jump_to_bookmark(app, player, &bookmark, tx);
+        }
+        return false;
+    }
+
+    // Handle chapter navigation when chapters popup is visible
+    if let Some(popup) = &app.chapters_popup {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => {
+                app.move_chapter_down();
+                return false;
+            }
             KeyCode::Char('k') | KeyCode::Up => {
                 app.move_chapter_up();
                 return false;
             }
             KeyCode::Enter => {
-                // Jump to selected chapter from cache
-                if let Some(chapter_list) = &app.cached_chapters {
-                    if let Some(chapter) = chapter_list.chapters.get(app.selected_chapter_index) {
-                        let start_secs = chapter.start_time as u64;
-                        // Seek to the chapter start time
-                        if app.playback.start.is_some() {
-                            // Calculate current position and seek relative to it
-                            let current_pos = player.get_position().as_secs();
-                            if start_secs > current_pos {
-                                let _ = player.seek_forward(start_secs - current_pos);
-                            } else if start_secs < current_pos {
-                                let _ = player.seek_backward(current_pos - start_secs);
-                            }
-                            app.status_message = Some(format!("Jumped to: {}", chapter.title));
-                        } else {
-                            app.status_message = Some("Start playback first".to_string());
-                        }
-                    }
+                let chapter = popup.toc().get(popup.selected).map(|&c| c.clone());
+                if app.playback.url.as_ref() != Some(&popup.audio_url) {
+                    app.status_message = Some("Play the episode to jump to its chapters".to_string());
+                } else if let Some(chapter) = chapter {
+                    jump_to_chapter(app, player, &chapter);
                 }
-                app.show_chapters = false;
+                app.chapters_popup = None;
+                return false;
+            }
+            KeyCode::Char('c') => {
+                app.chapters_popup = None;
                 return false;
             }
             _ => {}
@@ -214,17 +1244,12 @@ fn handle_normal_key(
         if matches!(action, Action::Quit) {
             // Save current playback position before quitting
             if app.playback.start.is_some() {
-                let current_position = player.get_position().as_secs();
-                if let Some(podcast) = app.podcasts.get_mut(app.selected_podcast_index) {
-                    if let Some(episode) = podcast.episodes.get_mut(app.selected_episode_index) {
-                        episode.position_secs = current_position;
-                        log::debug!("Saved position on quit: {}s for episode '{}'", current_position, episode.title);
-                    }
-                }
+                record_history(app, player, HistoryEventKind::Stop, None);
             }
+            save_playing_position(app, player);
 
             // Save to disk immediately before quitting
-            if let Err(e) = persistence::save_podcasts(&app.podcasts) {
+            if let Err(e) = store_library(app, player) {
                 log::error!("Failed to save on quit: {}", e);
             }
 
@@ -233,105 +1258,78 @@ fn handle_normal_key(
 
         // Handle playback actions
         match action {
+            Action::PlayPause if app.screen == app::AppScreen::Bookmarks => {
+                if let Some(bookmark) = app.bookmarks.selected().cloned() {
+                    jump_to_bookmark(app, player, &bookmark, tx);
+                }
+            }
+            Action::SetLoopPoint => {
+                set_loop_point(app, player);
+            }
+            Action::MarkClipStart => {
+                match app.playback.url.clone().filter(|_| app.playback.start.is_some()) {
+                    Some(url) => {
+                        let position = player.get_position().as_secs_f64();
+                        app.clip_start = Some((url, position));
+                        app.status_message = Some(format!(
+                            "Clip starts at {} - press }} at the end",
+                            now_playing::format_time(position as u64)
+                        ));
+                    }
+                    None => app.status_message = Some("Start playback first".to_string()),
+                }
+            }
+            Action::ExportClip => {
+                export_clip(app, player, tx);
+            }
+            Action::AddBookmark => {
+                if app.playback.start.is_some() {
+                    app.start_bookmark(player.get_position().as_secs());
+                } else {
+                    app.status_message = Some("Start playback first".to_string());
+                }
+            }
             Action::PlayPause => {
-                let selected_url = app.selected_episode_url();
-                let is_different_episode = selected_url.as_ref() != app.playback.url.as_ref();
+                let target = app.target_episode_indices();
+                let preview_episode = app.preview_episode().cloned();
+                let target_url = if app.screen == app::AppScreen::NowPlaying {
+                    // Always the playing episode, even one played from a Browse preview
+                    app.playback.url.clone()
+                } else {
+                    target
+                        .and_then(|(p, e)| app.episode_ref(p, e))
+                        .map(|r| r.audio_url)
+                        .or_else(|| preview_episode.as_ref().map(|e| e.audio_url.clone()))
+                };
+                let is_different_episode = target_url.as_ref() != app.playback.url.as_ref();
 
                 // If user selected a different episode, stop current and play new one
                 if is_different_episode || (!player.is_playing() && !player.is_paused()) {
-                    // Get episode info before borrowing
-                    let episode_info = app.selected_podcast()
-                        .and_then(|p| p.episodes.get(app.selected_episode_index))
-                        .map(|e| (e.audio_url.clone(), e.title.clone(), e.duration, e.position_secs));
-
-                    if let Some((audio_url, title, duration, position_secs)) = episode_info {
-                        if !audio_url.is_empty() {
-                            match player.play(&audio_url) {
-                                Ok(_) => {
-                                    // Resume from saved position if available
-                                    if position_secs > 0 {
-                                        if let Err(e) = player.seek_forward(position_secs) {
-                                            log::warn!("Failed to seek to saved position: {}", e);
-                                        } else {
-                                            app.status_message = Some(format!("Resuming: {} at {}:{:02}",
-                                                title, position_secs / 60, position_secs % 60));
-                                        }
-                                    } else {
-                                        app.status_message = Some(format!("Playing: {}", title));
-                                    }
-                                    app.playback.url = Some(audio_url);
-
-                                    // Start playback tracking
-                                    app.playback.start = Some(std::time::Instant::now());
-                                    app.playback.duration_secs = duration.map(|d| d.as_secs()).unwrap_or(0);
-                                    app.playback.paused_at = None;
-                                    app.playback.paused_duration = std::time::Duration::ZERO;
-                                }
-                                Err(e) => {
-                                    app.status_message = Some(format!("Error: {}", e));
-                                    app.playback.url = None;
-                                    app.playback.start = None;
-                                }
-                            }
-                        } else {
-                            app.status_message = Some("No audio URL for this episode".to_string());
-                        }
+                    if app.playback.start.is_some() {
+                        let kind = if is_different_episode { HistoryEventKind::Skip } else { HistoryEventKind::Stop };
+                        record_history(app, player, kind, None);
                     }
-                } else if player.is_paused() {
-                    // Resume current episode
-                    player.resume();
-                    let title = app.selected_podcast()
-                        .and_then(|p| p.episodes.get(app.selected_episode_index))
-                        .map(|e| e.title.clone());
-                    if let Some(title) = title {
-                        app.status_message = Some(format!("Resumed: {}", title));
-                    }
-
-                    // Resume playback tracking
-                    if let Some(paused_at) = app.playback.paused_at {
-                        app.playback.paused_duration += std::time::Instant::now().duration_since(paused_at);
-                        app.playback.paused_at = None;
+                    save_playing_position(app, player);
+                    if let Some((p, e)) = target {
+                        start_episode(app, player, p, e, tx);
+                    } else if let Some(episode) = preview_episode {
+                        start_playback(app, player, &episode, tx);
                     }
+                } else if player.is_paused() {
+                    resume_playback(app, player);
                 } else {
-                    // Pause current episode
-                    player.pause();
-                    let title = app.selected_podcast()
-                        .and_then(|p| p.episodes.get(app.selected_episode_index))
-                        .map(|e| e.title.clone());
-                    if let Some(title) = title {
-                        app.status_message = Some(format!("Paused: {}", title));
-                    }
-
-                    // Mark pause time
-                    app.playback.paused_at = Some(std::time::Instant::now());
+                    pause_playback(app, player);
                 }
             }
             Action::Stop => {
-                // Save current playback position before stopping
-                if app.playback.start.is_some() {
-                    let current_position = player.get_position().as_secs();
-                    if let Some(podcast) = app.podcasts.get_mut(app.selected_podcast_index) {
-                        if let Some(episode) = podcast.episodes.get_mut(app.selected_episode_index) {
-                            episode.position_secs = current_position;
-                            app.needs_save = true;
-                            log::debug!("Saved position: {}s for episode '{}'", current_position, episode.title);
-                        }
-                    }
-                }
-
-                player.stop();
-                app.status_message = Some("Stopped".to_string());
-                app.playback.url = None;
-
-                // Clear playback tracking
-                app.playback.start = None;
-                app.playback.paused_at = None;
-                app.playback.paused_duration = std::time::Duration::ZERO;
+                stop_playback(app, player);
             }
             Action::SeekForward => {
                 if app.playback.start.is_some() {
+                    let from = player.get_position().as_secs();
                     match player.seek_forward(30) {
                         Ok(_) => {
+                            record_history(app, player, HistoryEventKind::Seek, Some(from));
                             app.status_message = Some("⏩ +30s".to_string());
                         }
                         Err(e) => {
@@ -342,8 +1340,10 @@ fn handle_normal_key(
             }
             Action::SeekBackward => {
                 if app.playback.start.is_some() {
+                    let from = player.get_position().as_secs();
                     match player.seek_backward(30) {
                         Ok(_) => {
+                            record_history(app, player, HistoryEventKind::Seek, Some(from));
                             app.status_message = Some("⏪ -30s".to_string());
                         }
                         Err(e) => {
@@ -354,29 +1354,93 @@ fn handle_normal_key(
             }
             Action::SpeedUp => {
                 if app.playback.start.is_some() {
-                    let current_speed = player.get_speed();
+                    // Inside an A-B loop the player runs slowed down, so change the loop's normal speed
+                    let current_speed = app.ab_loop.as_ref().map(|l| l.base_speed).unwrap_or_else(|| player.get_speed());
                     let new_speed = (current_speed + 0.25).min(3.0); // Cap at 3x
                     player.set_speed(new_speed);
+                    if let Some(ab_loop) = &mut app.ab_loop {
+                        ab_loop.base_speed = new_speed;
+                    }
                     app.status_message = Some(format!("Speed: {:.2}x", new_speed));
                 }
             }
             Action::SpeedDown => {
                 if app.playback.start.is_some() {
-                    let current_speed = player.get_speed();
+                    // Inside an A-B loop the player runs slowed down, so change the loop's normal speed
+                    let current_speed = app.ab_loop.as_ref().map(|l| l.base_speed).unwrap_or_else(|| player.get_speed());
                     let new_speed = (current_speed - 0.25).max(0.5); // Minimum 0.5x
                     player.set_speed(new_speed);
+                    if let Some(ab_loop) = &mut app.ab_loop {
+                        ab_loop.base_speed = new_speed;
+                    }
                     app.status_message = Some(format!("Speed: {:.2}x", new_speed));
                 }
             }
+            Action::ShowChapters => {
+                show_chapters(app, player, tx);
+            }
+            Action::NextChapter | Action::PrevChapter => {
+                if app.playback.start.is_some() {
+                    let position = player.get_position().as_secs_f64();
+                    let target = app.playback.chapters.as_ref().map(|list| {
+                        let chapter = if *action == Action::NextChapter {
+                            list.next_chapter(position)
+                        } else {
+                            list.previous_chapter(position)
+                        };
+                        chapter.map(|(_, c)| c.clone())
+                    });
+                    match target {
+                        Some(Some(chapter)) => jump_to_chapter(app, player, &chapter),
+                        Some(None) => app.status_message = Some("No more chapters".to_string()),
+                        None => app.status_message = Some("No chapters available".to_string()),
+                    }
+                }
+            }
+            Action::UndoChapterSkip => {
+                let skip = app.playback.url.clone().and_then(|url| app.chapter_skips.undo(&url));
+                match skip {
+                    Some(skip) => {
+                        let chapter = chapters::Chapter {
+                            start_time: skip.from,
+                            title: skip.title,
+                            ..Default::default()
+                        };
+                        jump_to_chapter(app, player, &chapter);
+                    }
+                    None => app.status_message = Some("No skipped chapter to undo".to_string()),
+                }
+            }
+            Action::VolumeUp => {
+                let volume = (player.get_volume() + 0.1).min(1.5);
+                player.set_volume(volume);
+                app.status_message = Some(format!("Volume: {:.0}%", volume * 100.0));
+            }
+            Action::VolumeDown => {
+                let volume = (player.get_volume() - 0.1).max(0.0);
+                player.set_volume(volume);
+                app.status_message = Some(format!("Volume: {:.0}%", volume * 100.0));
+            }
+            Action::RefreshAll => {
+                refresh_all_feeds(app, tx);
+            }
+            Action::Sync => {
+                start_sync(app, tx);
+            }
+            Action::RefreshFeed if app.screen == app::AppScreen::Inbox => {
+                refresh_all_feeds(app, tx);
+            }
             Action::RefreshFeed => {
                 if let Some(podcast) = app.podcasts.get_mut(app.selected_podcast_index) {
                     match feed::refresh_feed(podcast) {
-                        Ok(count) => {
-                            app.status_message = if count > 0 {
-                                Some(format!("Refreshed: {} new episode(s)", count))
+                        Ok(added) => {
+                            app.status_message = if !added.is_empty() {
+                                Some(format!("Refreshed: {} new episode(s)", added.len()))
                             } else {
                                 Some("Refreshed: No new episodes".to_string())
                             };
+                            app.inbox.new_episodes.extend(added);
+                            app.clamp_episode_selection();
                             app.needs_save = true;
                         }
                         Err(e) => {
@@ -386,7 +1450,7 @@ fn handle_normal_key(
                 }
             }
             _ => {
-                action.execute(app, player);
+                action.execute(app);
             }
         }
     }
@@ -395,6 +1459,12 @@ fn handle_normal_key(
 }
 
 fn main() -> Result<()> {
+    // Non-interactive commands, run before the terminal is taken over
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
+
     // Initialize logging
     CombinedLogger::init(vec![WriteLogger::new(
         LevelFilter::Debug,
@@ -425,20 +1495,82 @@ fn main() -> Result<()> {
         }
         Err(_) => App::new(),
     };
+    app.queue = persistence::load_queue().unwrap_or_default();
+    app.library_modified = persistence::library_modified();
+    app.bookmarks.reload();
+    app.clips_config = config.clips.clone();
+    app.sync_config = config.sync.clone();
+    app.directory = std::sync::Arc::new(directory::Directory::from_config(&config.directory));
+    app.artwork = artwork::ArtworkRenderer::new(artwork::Protocol::from_config(&config.artwork.protocol));
+    app.skip_rules = chapters::skip::SkipRules::from_config(&config.chapters);
+    log::info!("Using directories: {}", app.directory.provider_names().join(", "));
     let keymap = KeyMap::with_defaults();
 
-    // Initialize audio player
-    let mut player = Player::new().expect("Failed to initialize audio player");
+    // Play through the daemon when one is running, so playback outlives the TUI
+    let mut player: Box<dyn Playback> = match daemon::client::RemotePlayer::connect() {
+        Ok(remote) => {
+            log::info!("Attached to the daemon");
+            app.library_snapshot = Some(library::Snapshot::of(&app));
+            Box::new(remote)
+        }
+        Err(_) => Box::new(Player::new().expect("Failed to initialize audio player")),
+    };
 
     // Create channel for background thread communication
     let (tx, rx) = mpsc::channel::<AppEvent>();
 
     loop {
-        terminal.draw(|f| ui::draw_ui(f, &app, &player, &theme))?;
+        let mut artwork = None;
+        let frame = terminal.draw(|f| artwork = ui::draw_ui(f, &app, &*player, &theme))?;
+
+        // Images drawn over the frame aren't known to ratatui, so repaint the cells under a stale one
+        if let Some(stale) = app.artwork.stale_area(artwork.as_ref()) {
+            let stale = stale.intersection(frame.area);
+            let cells: Vec<_> = stale
+                .positions()
+                .map(|p| (p.x, p.y, frame.buffer[(p.x, p.y)].clone()))
+                .collect();
+            terminal.backend_mut().draw(cells.iter().map(|(x, y, cell)| (*x, *y, cell)))?;
+            Backend::flush(terminal.backend_mut())?;
+        }
+        let size = terminal.size()?;
+        app.artwork.render(&mut stdout(), artwork, ratatui::layout::Rect::new(0, 0, size.width, size.height), &tx)?;
 
         // Check for background events (non-blocking)
         while let Ok(event) = rx.try_recv() {
-            handle_app_event(&mut app, event);
+            handle_app_event(&mut app, event, &tx);
+        }
+
+        // Fetch the featured podcasts and categories the first time Browse is opened
+        if app.is_browse_screen() {
+            if app.browse.toplist == browse::LoadState::NotLoaded {
+                load_toplist(&mut app, &tx, false);
+            }
+            if app.browse.categories_state == browse::LoadState::NotLoaded {
+                load_categories(&mut app, &tx);
+            }
+            load_details(&mut app, &tx);
+        }
+
+        // Advance to the next queued episode when the current one ends. The
+        // daemon does this itself, along with skipping chapters.
+        if let Some(remote) = player.remote() {
+            follow_daemon(&mut app, remote, &tx);
+            reload_changed_library(&mut app);
+        } else if app.playback.start.is_some() && player.is_finished() {
+            handle_playback_finished(&mut app, &mut *player, &tx);
+        }
+
+        if player.is_playing() {
+            if player.remote().is_none() {
+                skip_matching_chapter(&mut app, &*player);
+            }
+            repeat_ab_loop(&mut app, &*player);
+        }
+
+        if app.now_playing.take_expired_sleep_timer(std::time::Instant::now()) && player.is_playing() {
+            pause_playback(&mut app, &*player);
+            app.status_message = Some("Sleep timer: paused".to_string());
         }
 
         if event::poll(std::time::Duration::from_millis(50))? {
@@ -450,6 +1582,15 @@ fn main() -> Result<()> {
                 InputMode::Searching => {
                     handle_search_input(&mut app, key, &tx);
                 }
+                InputMode::FilteringEpisodes => {
+                    handle_episode_filter_input(&mut app, key);
+                }
+                InputMode::MarkingOlderThan => {
+                    handle_mark_older_input(&mut app, key);
+                }
+                InputMode::BookmarkNote => {
+                    handle_bookmark_note_input(&mut app, key);
+                }
                 InputMode::Normal => {
                     // Handle browse-specific keys first
                     if app.is_browse_screen() && handle_browse_screen_key(&mut app, key, &tx) {
@@ -457,7 +1598,7 @@ fn main() -> Result<()> {
                     }
 
                     // Handle normal mode keys
-                    if handle_normal_key(&mut app, &mut player, &keymap, key) {
+                    if handle_normal_key(&mut app, &mut *player, &keymap, key, &tx) {
                         break;
                     }
                 }
@@ -467,10 +1608,9 @@ fn main() -> Result<()> {
 
         // Centralized persistence
         if app.needs_save {
-            if let Err(e) = persistence::save_podcasts(&app.podcasts) {
+            if let Err(e) = store_library(&mut app, &*player) {
                 app.status_message = Some(format!("Save error: {}", e));
             }
-            app.needs_save = false;
         }
     }
 
@@ -0,0 +1,131 @@
+use crate::playback::BufferStatus;
+use std::time::{Duration, Instant};
+
+/// Sleep timer lengths in minutes, in the order `z` cycles through them
+pub const SLEEP_TIMER_STEPS: &[u64] = &[15, 30, 45, 60, 90];
+
+/// State of the Now Playing screen
+pub struct NowPlayingState {
+    /// Lines scrolled in the show notes
+    pub notes_scroll: u16,
+    /// Length of the running sleep timer in minutes, and when it pauses playback
+    pub sleep_timer: Option<(u64, Instant)>,
+}
+
+impl NowPlayingState {
+    pub fn new() -> Self {
+        Self {
+            notes_scroll: 0,
+            sleep_timer: None,
+        }
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.notes_scroll = self.notes_scroll.saturating_sub(1);
+    }
+
+    pub fn scroll_down(&mut self) {
+        self.notes_scroll = self.notes_scroll.saturating_add(1);
+    }
+
+    /// Move to the next sleep timer length, or turn the timer off after the last one.
+    /// Returns the new length in minutes.
+    pub fn cycle_sleep_timer(&mut self, now: Instant) -> Option<u64> {
+        let next = match self.sleep_timer {
+            None => SLEEP_TIMER_STEPS.first().copied(),
+            Some((minutes, _)) => SLEEP_TIMER_STEPS.iter().copied().find(|&m| m > minutes),
+        };
+        self.sleep_timer = next.map(|m| (m, now + Duration::from_secs(m * 60)));
+        next
+    }
+
+    /// Time left on the sleep timer
+    pub fn sleep_remaining(&self, now: Instant) -> Option<Duration> {
+        self.sleep_timer.map(|(_, at)| at.saturating_duration_since(now))
+    }
+
+    /// True once when the sleep timer runs out, which also turns it off
+    pub fn take_expired_sleep_timer(&mut self, now: Instant) -> bool {
+        match self.sleep_timer {
+            Some((_, at)) if at <= now => {
+                self.sleep_timer = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// A row of `width` cells with a tick under the start of every chapter after the first
+pub fn chapter_ticks(starts: &[f64], duration_secs: u64, width: u16) -> String {
+    let mut row = vec![' '; width as usize];
+    if duration_secs == 0 || width == 0 {
+        return String::new();
+    }
+    for &start in starts.iter().filter(|&&s| s > 0.0) {
+        let col = (start / duration_secs as f64 * width as f64) as usize;
+        if let Some(cell) = row.get_mut(col) {
+            *cell = '▲';
+        }
+    }
+    row.into_iter().collect()
+}
+
+/// Playback time as m:ss, or h:mm:ss for long episodes
+pub fn format_time(secs: u64) -> String {
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+/// Download progress as shown on the Now Playing screen
+pub fn describe_buffer(status: &BufferStatus, buffering: bool) -> String {
+    let mb = |bytes: u64| bytes as f64 / (1024.0 * 1024.0);
+    if status.complete {
+        format!("Downloaded ({:.1} MB)", mb(status.downloaded_bytes))
+    } else if buffering {
+        format!("Buffering... {:.1} MB", mb(status.downloaded_bytes))
+    } else {
+        match status.total_bytes {
+            Some(total) if total > 0 => format!(
+                "Streaming {:.1} / {:.1} MB",
+                mb(status.downloaded_bytes),
+                mb(total)
+            ),
+            _ => format!("Streaming {:.1} MB", mb(status.downloaded_bytes)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sleep_timer_cycles_and_expires() {
+        let now = Instant::now();
+        let mut state = NowPlayingState::new();
+
+        assert_eq!(state.cycle_sleep_timer(now), Some(15));
+        assert_eq!(state.cycle_sleep_timer(now), Some(30));
+        assert_eq!(state.sleep_remaining(now), Some(Duration::from_secs(30 * 60)));
+        assert!(!state.take_expired_sleep_timer(now));
+        assert!(state.take_expired_sleep_timer(now + Duration::from_secs(30 * 60)));
+        assert_eq!(state.sleep_timer, None);
+
+        for _ in 0..SLEEP_TIMER_STEPS.len() {
+            state.cycle_sleep_timer(now);
+        }
+        assert_eq!(state.cycle_sleep_timer(now), None);
+    }
+
+    #[test]
+    fn test_chapter_ticks_and_time() {
+        assert_eq!(chapter_ticks(&[0.0, 50.0, 75.0], 100, 8), "    ▲ ▲ ");
+        assert_eq!(chapter_ticks(&[10.0], 0, 8), "");
+        assert_eq!(format_time(125), "2:05");
+        assert_eq!(format_time(3725), "1:02:05");
+    }
+}
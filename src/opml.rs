@@ -0,0 +1,121 @@
+//! OPML subscription lists, for moving subscriptions between podcast apps
+
+use crate::app::Podcast;
+use quick_xml::escape::escape;
+use quick_xml::events::Event;
+use quick_xml::{Reader, XmlVersion};
+
+/// A feed listed in an OPML file
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpmlFeed {
+    /// The outline's title, or its text if it has no title
+    pub title: String,
+    pub url: String,
+}
+
+/// Every feed in an OPML document, including ones nested in category outlines
+pub fn parse(xml: &str) -> Result<Vec<OpmlFeed>, String> {
+    let mut reader = Reader::from_str(xml);
+    let mut feeds: Vec<OpmlFeed> = Vec::new();
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("Failed to parse OPML at byte {}: {}", reader.error_position(), e))?;
+        let outline = match event {
+            Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"outline" => e,
+            Event::Eof => break,
+            _ => continue,
+        };
+
+        let (mut title, mut text, mut url) = (None, None, None);
+        for attr in outline.attributes() {
+            let attr = attr.map_err(|e| format!("Failed to parse OPML outline: {}", e))?;
+            let value = attr
+                .normalized_value(XmlVersion::Implicit1_0)
+                .map_err(|e| format!("Failed to parse OPML outline: {}", e))?
+                .trim()
+                .to_string();
+            match attr.key.as_ref() {
+                b"title" => title = Some(value),
+                b"text" => text = Some(value),
+                b"xmlUrl" => url = Some(value),
+                _ => {}
+            }
+        }
+
+        let Some(url) = url.filter(|u| !u.is_empty()) else {
+            continue; // A category, not a feed
+        };
+        if feeds.iter().any(|f| f.url == url) {
+            continue;
+        }
+        let title = title.filter(|t| !t.is_empty()).or(text).unwrap_or_else(|| url.clone());
+        feeds.push(OpmlFeed { title, url });
+    }
+
+    Ok(feeds)
+}
+
+/// An OPML document listing every subscription
+pub fn to_opml(podcasts: &[Podcast]) -> String {
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <opml version=\"2.0\">\n  \
+         <head>\n    <title>shellcast subscriptions</title>\n  </head>\n  \
+         <body>\n",
+    );
+    for podcast in podcasts {
+        let title = escape(podcast.title.as_str());
+        out.push_str(&format!(
+            "    <outline type=\"rss\" text=\"{}\" title=\"{}\" xmlUrl=\"{}\"/>\n",
+            title,
+            title,
+            escape(podcast.url.as_str())
+        ));
+    }
+    out.push_str("  </body>\n</opml>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opml_round_trip_and_nested_outlines() {
+        let xml = r#"<?xml version="1.0"?>
+            <opml version="1.0"><head><title>Export</title></head><body>
+              <outline text="Tech">
+                <outline type="rss" text="Show &amp; Tell" xmlUrl="https://example.com/feed?a=1&amp;b=2"/>
+                <outline type="rss" text="Dupe" xmlUrl="https://example.com/feed?a=1&amp;b=2"/>
+              </outline>
+              <outline type="rss" text="Text only" title="" xmlUrl=" https://example.org/rss "></outline>
+              <outline text="Not a feed"/>
+            </body></opml>"#;
+
+        let feeds = parse(xml).unwrap();
+        assert_eq!(
+            feeds,
+            vec![
+                OpmlFeed { title: "Show & Tell".to_string(), url: "https://example.com/feed?a=1&b=2".to_string() },
+                OpmlFeed { title: "Text only".to_string(), url: "https://example.org/rss".to_string() },
+            ]
+        );
+
+        let podcasts: Vec<Podcast> = feeds
+            .iter()
+            .map(|f| Podcast {
+                title: f.title.clone(),
+                description: String::new(),
+                url: f.url.clone(),
+                episodes: Vec::new(),
+                filter: Default::default(),
+                categories: Vec::new(),
+                image_url: None,
+            })
+            .collect();
+        assert_eq!(parse(&to_opml(&podcasts)).unwrap(), feeds);
+        assert!(parse("<opml><body><outline xmlUrl=\"x\"").is_err());
+    }
+}
@@ -1,14 +1,21 @@
-use crate::app::Podcast;
+use crate::app::{EpisodeRef, Podcast};
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Serialize, Deserialize)]
 struct PersistentData {
     podcasts: Vec<Podcast>,
 }
 
-fn get_data_path() -> Result<PathBuf, String> {
+#[derive(Debug, Serialize, Deserialize)]
+struct QueueData {
+    queue: Vec<EpisodeRef>,
+}
+
+/// Get a file path inside the shellcast config directory, creating the directory if needed
+pub fn get_data_file(name: &str) -> Result<PathBuf, String> {
     let config_dir = dirs::config_dir()
         .ok_or_else(|| "Could not determine config directory".to_string())?;
 
@@ -16,7 +23,20 @@ fn get_data_path() -> Result<PathBuf, String> {
     fs::create_dir_all(&app_dir)
         .map_err(|e| format!("Failed to create config directory: {}", e))?;
 
-    Ok(app_dir.join("podcasts.json"))
+    Ok(app_dir.join(name))
+}
+
+/// Replace a file in one step, so a process reading it never sees it half written
+pub fn write_atomic(path: &Path, contents: &str) -> std::io::Result<()> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let mut temp = tempfile::NamedTempFile::new_in(dir)?;
+    temp.write_all(contents.as_bytes())?;
+    temp.persist(path).map_err(|e| e.error)?;
+    Ok(())
+}
+
+fn get_data_path() -> Result<PathBuf, String> {
+    get_data_file("podcasts.json")
 }
 
 pub fn save_podcasts(podcasts: &[Podcast]) -> Result<(), String> {
@@ -29,7 +49,7 @@ pub fn save_podcasts(podcasts: &[Podcast]) -> Result<(), String> {
     let json = serde_json::to_string_pretty(&data)
         .map_err(|e| format!("Failed to serialize podcasts: {}", e))?;
 
-    fs::write(&path, json)
+    write_atomic(&path, &json)
         .map_err(|e| format!("Failed to write podcasts file: {}", e))?;
 
     Ok(())
@@ -50,3 +70,58 @@ pub fn load_podcasts() -> Result<Vec<Podcast>, String> {
 
     Ok(data.podcasts)
 }
+
+/// When the podcasts or the queue were last saved, to notice another process saving them
+pub fn library_modified() -> Option<std::time::SystemTime> {
+    ["podcasts.json", "queue.json"]
+        .iter()
+        .filter_map(|name| get_data_file(name).ok()?.metadata().ok()?.modified().ok())
+        .max()
+}
+
+pub fn save_queue(queue: &[EpisodeRef]) -> Result<(), String> {
+    let path = get_data_file("queue.json")?;
+
+    let data = QueueData {
+        queue: queue.to_vec(),
+    };
+
+    let json = serde_json::to_string_pretty(&data)
+        .map_err(|e| format!("Failed to serialize queue: {}", e))?;
+
+    write_atomic(&path, &json)
+        .map_err(|e| format!("Failed to write queue file: {}", e))?;
+
+    Ok(())
+}
+
+pub fn load_queue() -> Result<Vec<EpisodeRef>, String> {
+    let path = get_data_file("queue.json")?;
+
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let json = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read queue file: {}", e))?;
+
+    let data: QueueData = serde_json::from_str(&json)
+        .map_err(|e| format!("Failed to deserialize queue: {}", e))?;
+
+    Ok(data.queue)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_atomic_replaces_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("podcasts.json");
+        write_atomic(&path, "first").unwrap();
+        write_atomic(&path, "second").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1, "no temp file left behind");
+    }
+}
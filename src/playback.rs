@@ -1,12 +1,140 @@
+use crate::daemon::client::RemotePlayer;
+use serde::{Deserialize, Serialize};
 use std::fs::File;
-use std::io::BufReader;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 use tempfile::NamedTempFile;
 
+/// Audio that must arrive after the decoder ran dry before it is reopened,
+/// about 30 seconds at 128 kbps, so a slow download doesn't stutter
+const RESUME_BYTES: u64 = 512 * 1024;
+
+/// How much of the playing episode has been downloaded
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct BufferStatus {
+    pub downloaded_bytes: u64,
+    /// Size of the episode, if the server sent a Content-Length
+    pub total_bytes: Option<u64>,
+    pub complete: bool,
+}
+
+/// The temp file an episode is downloaded to, as read by the decoder. Reaching
+/// its end before the download is complete is recorded, so running out of
+/// buffered audio isn't mistaken for the end of the episode.
+struct DownloadingFile {
+    file: File,
+    position: u64,
+    buffer: Arc<Mutex<BufferStatus>>,
+    /// Where the file ended when the decoder ran dry, if it did
+    starved_at: Arc<Mutex<Option<u64>>>,
+}
+
+impl Read for DownloadingFile {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.file.read(buf)?;
+        self.position += n as u64;
+        if n == 0 && !buf.is_empty() {
+            let complete = self.buffer.lock().map(|s| s.complete).unwrap_or(true);
+            if !complete {
+                if let Ok(mut starved_at) = self.starved_at.lock() {
+                    *starved_at = Some(self.position);
+                }
+            }
+        }
+        Ok(n)
+    }
+}
+
+impl Seek for DownloadingFile {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.position = self.file.seek(pos)?;
+        Ok(self.position)
+    }
+}
+
+fn open_decoder(
+    path: &Path,
+    buffer: &Arc<Mutex<BufferStatus>>,
+    starved_at: &Arc<Mutex<Option<u64>>>,
+) -> Result<rodio::Decoder<BufReader<DownloadingFile>>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open temp file for playback: {}", e))?;
+    let reader = DownloadingFile {
+        file,
+        position: 0,
+        buffer: buffer.clone(),
+        starved_at: starved_at.clone(),
+    };
+    let decoder = rodio::Decoder::new(BufReader::new(reader)).map_err(|e| format!("Failed to decode audio: {}", e))?;
+    // Probing the format may look at the end of the file; only running dry while playing counts
+    if let Ok(mut starved_at) = starved_at.lock() {
+        *starved_at = None;
+    }
+    Ok(decoder)
+}
+
+/// What an empty sink means
+#[derive(Debug, PartialEq)]
+enum Underrun {
+    /// The decoder read the whole episode
+    Finished,
+    /// Enough audio has arrived since the decoder ran dry to carry on
+    Reopen,
+    /// Still waiting for the download
+    Wait,
+}
+
+fn underrun(starved_at: Option<u64>, buffer: BufferStatus) -> Underrun {
+    match starved_at {
+        None => Underrun::Finished,
+        Some(end) if buffer.complete || buffer.downloaded_bytes >= end + RESUME_BYTES => Underrun::Reopen,
+        Some(_) => Underrun::Wait,
+    }
+}
+
+/// Audio playback, either in this process or in a running `shellcast daemon`
+pub trait Playback {
+    /// Start streaming `url`, or reading a local file, beginning playback at
+    /// `start_secs` once enough audio is buffered
+    fn play(&mut self, url: &str, start_secs: u64) -> Result<(), String>;
+    fn pause(&self);
+    fn resume(&self);
+    fn stop(&mut self);
+    fn is_playing(&self) -> bool;
+    /// True once a started episode has played to the end, not merely to the
+    /// end of what has been downloaded so far
+    fn is_finished(&self) -> bool;
+    fn is_paused(&self) -> bool;
+    fn seek_forward(&self, seconds: u64) -> Result<(), String>;
+    fn seek_backward(&self, seconds: u64) -> Result<(), String>;
+    fn seek_to(&self, position: std::time::Duration) -> Result<(), String>;
+    fn get_position(&self) -> std::time::Duration;
+    fn get_temp_file_path(&self) -> Option<std::path::PathBuf>;
+    /// Set playback speed (1.0 = normal, 0.5 = half speed, 2.0 = double speed)
+    fn set_speed(&self, speed: f32);
+    fn get_speed(&self) -> f32;
+    /// Set the volume (1.0 = 100%), kept for episodes played later
+    fn set_volume(&mut self, volume: f32);
+    fn get_volume(&self) -> f32;
+    /// True while an episode has been started but isn't buffered enough to play yet
+    fn is_buffering(&self) -> bool;
+    /// Download progress of the playing episode
+    fn buffer_status(&self) -> BufferStatus;
+
+    /// The daemon connection, when playback happens in `shellcast daemon`
+    fn remote(&self) -> Option<&RemotePlayer> {
+        None
+    }
+}
+
 pub struct Player {
     temp_file: Option<NamedTempFile>,
     sink: Arc<Mutex<Option<rodio::Sink>>>,
     stream_handle: rodio::OutputStream,
+    buffer: Arc<Mutex<BufferStatus>>,
+    starved_at: Arc<Mutex<Option<u64>>>,
+    /// Volume applied to every new sink (1.0 = 100%)
+    volume: f32,
     // Keep the response body alive for streaming
     _http_body: Option<Box<dyn std::io::Read + Send>>,
 }
@@ -21,11 +149,46 @@ impl Player {
             temp_file: None,
             sink: Arc::new(Mutex::new(None)),
             stream_handle,
+            buffer: Arc::new(Mutex::new(BufferStatus::default())),
+            starved_at: Arc::new(Mutex::new(None)),
+            volume: 1.0,
             _http_body: None,
         })
     }
 
-    pub fn play(&mut self, url: &str) -> Result<(), String> {
+    /// Called once the sink has played everything queued: carry on from the
+    /// same position if more of the episode has been downloaded since
+    fn resume_after_underrun(&self, sink: &rodio::Sink) -> Underrun {
+        let starved_at = self.starved_at.lock().map(|s| *s).unwrap_or(None);
+        let action = underrun(starved_at, self.buffer_status());
+        if action != Underrun::Reopen {
+            return action;
+        }
+        let Some(path) = self.get_temp_file_path() else {
+            return Underrun::Finished;
+        };
+
+        let position = sink.get_pos();
+        match open_decoder(&path, &self.buffer, &self.starved_at) {
+            Ok(source) => {
+                log::info!("Playback caught up with the download, continuing at {:?}", position);
+                sink.append(source);
+                if let Err(e) = sink.try_seek(position) {
+                    log::warn!("Failed to seek to {:?} after buffering: {}", position, e);
+                }
+                Underrun::Reopen
+            }
+            Err(e) => {
+                log::error!("{}", e);
+                Underrun::Finished
+            }
+        }
+    }
+}
+
+impl Playback for Player {
+    /// Start streaming `url`, beginning playback at `start_secs` once enough audio is buffered
+    fn play(&mut self, url: &str, start_secs: u64) -> Result<(), String> {
         // Stop any current playback
         self.stop();
 
@@ -37,6 +200,7 @@ impl Player {
         };
 
         log::info!("Starting playback from URL: {}", actual_url);
+        let is_local = std::path::Path::new(&actual_url).is_file();
 
         // Create temp file first
         let temp_file = NamedTempFile::new()
@@ -47,20 +211,48 @@ impl Player {
         // Clone mixer and sink for background thread
         let mixer = self.stream_handle.mixer().clone();
         let sink_arc = self.sink.clone();
+        let volume = self.volume;
+
+        // A fresh status per episode, so a previous download thread can't overwrite it
+        self.buffer = Arc::new(Mutex::new(BufferStatus::default()));
+        let buffer = self.buffer.clone();
+        self.starved_at = Arc::new(Mutex::new(None));
+        let starved_at = self.starved_at.clone();
 
         // Spawn background thread to download and start playback
         std::thread::spawn(move || {
             use std::io::{Read, Write};
 
             log::info!("Background: Fetching audio...");
-            let mut response = match reqwest::blocking::get(&actual_url) {
-                Ok(r) => r,
-                Err(e) => {
-                    log::error!("Background: Failed to fetch audio: {}", e);
-                    return;
+            // Downloaded episodes are read from disk the same way
+            let (mut response, total_bytes): (Box<dyn Read>, Option<u64>) = if is_local {
+                match File::open(&actual_url) {
+                    Ok(f) => {
+                        let len = f.metadata().ok().map(|m| m.len());
+                        (Box::new(f), len)
+                    }
+                    Err(e) => {
+                        log::error!("Background: Failed to open audio file: {}", e);
+                        return;
+                    }
+                }
+            } else {
+                match reqwest::blocking::get(&actual_url) {
+                    Ok(r) => {
+                        let len = r.content_length();
+                        (Box::new(r), len)
+                    }
+                    Err(e) => {
+                        log::error!("Background: Failed to fetch audio: {}", e);
+                        return;
+                    }
                 }
             };
 
+            if let Ok(mut status) = buffer.lock() {
+                status.total_bytes = total_bytes;
+            }
+
             // Buffer 10MB before starting playback
             let buffer_size = 10 * 1024 * 1024; // 10MB
             let mut initial_buffer = Vec::with_capacity(buffer_size);
@@ -69,10 +261,19 @@ impl Player {
             let mut chunk_buffer = vec![0u8; 8192];
             while total_read < buffer_size {
                 match response.read(&mut chunk_buffer) {
-                    Ok(0) => break, // EOF
+                    Ok(0) => {
+                        // The whole episode fit in the initial buffer
+                        if let Ok(mut status) = buffer.lock() {
+                            status.complete = true;
+                        }
+                        break;
+                    }
                     Ok(n) => {
                         initial_buffer.extend_from_slice(&chunk_buffer[..n]);
                         total_read += n;
+                        if let Ok(mut status) = buffer.lock() {
+                            status.downloaded_bytes = total_read as u64;
+                        }
                     }
                     Err(e) => {
                         log::error!("Background: Failed to buffer audio: {}", e);
@@ -97,25 +298,25 @@ impl Player {
             }
 
             // Start playback from the buffered file
-            let playback_file = match File::open(&temp_path_for_playback) {
-                Ok(f) => f,
-                Err(e) => {
-                    log::error!("Background: Failed to open temp file for playback: {}", e);
-                    return;
-                }
-            };
-
-            let source = match rodio::Decoder::new(BufReader::new(playback_file)) {
+            let source = match open_decoder(&temp_path_for_playback, &buffer, &starved_at) {
                 Ok(s) => s,
                 Err(e) => {
-                    log::error!("Background: Failed to decode audio: {}", e);
+                    log::error!("Background: {}", e);
                     return;
                 }
             };
 
             let new_sink = rodio::Sink::connect_new(&mixer);
+            new_sink.set_volume(volume);
             new_sink.append(source);
 
+            // Resume from the requested position now that the decoder exists
+            if start_secs > 0 {
+                if let Err(e) = new_sink.try_seek(std::time::Duration::from_secs(start_secs)) {
+                    log::warn!("Background: Failed to seek to {}s: {}", start_secs, e);
+                }
+            }
+
             // Store the sink in the shared Arc<Mutex<>>
             if let Ok(mut sink_guard) = sink_arc.lock() {
                 *sink_guard = Some(new_sink);
@@ -136,6 +337,9 @@ impl Player {
                 match response.read(&mut chunk_buffer) {
                     Ok(0) => {
                         log::info!("Background: Download complete");
+                        if let Ok(mut status) = buffer.lock() {
+                            status.complete = true;
+                        }
                         break;
                     }
                     Ok(n) => {
@@ -143,6 +347,9 @@ impl Player {
                             log::error!("Background: Failed to write chunk: {}", e);
                             break;
                         }
+                        if let Ok(mut status) = buffer.lock() {
+                            status.downloaded_bytes += n as u64;
+                        }
                     }
                     Err(e) => {
                         log::error!("Background: Failed to read chunk: {}", e);
@@ -160,7 +367,7 @@ impl Player {
         Ok(())
     }
 
-    pub fn pause(&self) {
+    fn pause(&self) {
         if let Ok(sink_guard) = self.sink.lock() {
             if let Some(sink) = sink_guard.as_ref() {
                 sink.pause();
@@ -168,7 +375,7 @@ impl Player {
         }
     }
 
-    pub fn resume(&self) {
+    fn resume(&self) {
         if let Ok(sink_guard) = self.sink.lock() {
             if let Some(sink) = sink_guard.as_ref() {
                 sink.play();
@@ -176,7 +383,7 @@ impl Player {
         }
     }
 
-    pub fn stop(&mut self) {
+    fn stop(&mut self) {
         if let Ok(mut sink_guard) = self.sink.lock() {
             if let Some(sink) = sink_guard.take() {
                 sink.stop();
@@ -186,7 +393,7 @@ impl Player {
         self.temp_file = None;
     }
 
-    pub fn is_playing(&self) -> bool {
+    fn is_playing(&self) -> bool {
         if let Ok(sink_guard) = self.sink.lock() {
             sink_guard
                 .as_ref()
@@ -197,7 +404,20 @@ impl Player {
         }
     }
 
-    pub fn is_paused(&self) -> bool {
+    /// True once a started episode has played to the end. When playback only
+    /// caught up with the download, it carries on once more has arrived.
+    fn is_finished(&self) -> bool {
+        if let Ok(sink_guard) = self.sink.lock() {
+            match sink_guard.as_ref() {
+                Some(sink) if sink.empty() => self.resume_after_underrun(sink) == Underrun::Finished,
+                _ => false,
+            }
+        } else {
+            false
+        }
+    }
+
+    fn is_paused(&self) -> bool {
         if let Ok(sink_guard) = self.sink.lock() {
             sink_guard
                 .as_ref()
@@ -208,7 +428,7 @@ impl Player {
         }
     }
 
-    pub fn seek_forward(&self, seconds: u64) -> Result<(), String> {
+    fn seek_forward(&self, seconds: u64) -> Result<(), String> {
         if let Ok(sink_guard) = self.sink.lock() {
             if let Some(sink) = sink_guard.as_ref() {
                 let current_pos = sink.get_pos();
@@ -220,7 +440,7 @@ impl Player {
         Ok(())
     }
 
-    pub fn seek_backward(&self, seconds: u64) -> Result<(), String> {
+    fn seek_backward(&self, seconds: u64) -> Result<(), String> {
         if let Ok(sink_guard) = self.sink.lock() {
             if let Some(sink) = sink_guard.as_ref() {
                 let current_pos = sink.get_pos();
@@ -232,7 +452,17 @@ impl Player {
         Ok(())
     }
 
-    pub fn get_position(&self) -> std::time::Duration {
+    fn seek_to(&self, position: std::time::Duration) -> Result<(), String> {
+        if let Ok(sink_guard) = self.sink.lock() {
+            if let Some(sink) = sink_guard.as_ref() {
+                sink.try_seek(position)
+                    .map_err(|e| format!("Seek failed: {}", e))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn get_position(&self) -> std::time::Duration {
         if let Ok(sink_guard) = self.sink.lock() {
             sink_guard
                 .as_ref()
@@ -243,12 +473,12 @@ impl Player {
         }
     }
 
-    pub fn get_temp_file_path(&self) -> Option<std::path::PathBuf> {
+    fn get_temp_file_path(&self) -> Option<std::path::PathBuf> {
         self.temp_file.as_ref().map(|f| f.path().to_path_buf())
     }
 
     /// Set playback speed (1.0 = normal, 0.5 = half speed, 2.0 = double speed)
-    pub fn set_speed(&self, speed: f32) {
+    fn set_speed(&self, speed: f32) {
         if let Ok(sink_guard) = self.sink.lock() {
             if let Some(sink) = sink_guard.as_ref() {
                 sink.set_speed(speed);
@@ -258,7 +488,7 @@ impl Player {
     }
 
     /// Get current playback speed
-    pub fn get_speed(&self) -> f32 {
+    fn get_speed(&self) -> f32 {
         if let Ok(sink_guard) = self.sink.lock() {
             sink_guard
                 .as_ref()
@@ -268,4 +498,69 @@ impl Player {
             1.0
         }
     }
+
+    /// Set the volume (1.0 = 100%), kept for episodes played later
+    fn set_volume(&mut self, volume: f32) {
+        self.volume = volume;
+        if let Ok(sink_guard) = self.sink.lock() {
+            if let Some(sink) = sink_guard.as_ref() {
+                sink.set_volume(volume);
+            }
+        }
+    }
+
+    fn get_volume(&self) -> f32 {
+        self.volume
+    }
+
+    /// True while an episode has been started but isn't buffered enough to play yet
+    fn is_buffering(&self) -> bool {
+        let starved = self.starved_at.lock().map(|s| s.is_some()).unwrap_or(false);
+        self.temp_file.is_some()
+            && (starved || self.sink.lock().map(|s| s.is_none()).unwrap_or(false))
+    }
+
+    /// Download progress of the playing episode
+    fn buffer_status(&self) -> BufferStatus {
+        self.buffer.lock().map(|s| *s).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_running_out_of_a_partial_download_is_not_the_end() {
+        let mut temp = NamedTempFile::new().unwrap();
+        temp.write_all(b"partial").unwrap();
+        let buffer = Arc::new(Mutex::new(BufferStatus { downloaded_bytes: 7, total_bytes: Some(10_000_000), complete: false }));
+        let starved_at = Arc::new(Mutex::new(None));
+        let mut reader = DownloadingFile {
+            file: File::open(temp.path()).unwrap(),
+            position: 0,
+            buffer: buffer.clone(),
+            starved_at: starved_at.clone(),
+        };
+
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).unwrap();
+        assert_eq!(*starved_at.lock().unwrap(), Some(7));
+        let status = *buffer.lock().unwrap();
+        assert_eq!(underrun(Some(7), status), Underrun::Wait);
+        assert_eq!(
+            underrun(Some(7), BufferStatus { downloaded_bytes: 7 + RESUME_BYTES, ..status }),
+            Underrun::Reopen
+        );
+        assert_eq!(underrun(Some(7), BufferStatus { complete: true, ..status }), Underrun::Reopen);
+
+        // Reaching the end of a complete download is the end of the episode
+        buffer.lock().unwrap().complete = true;
+        *starved_at.lock().unwrap() = None;
+        reader.seek(SeekFrom::Start(0)).unwrap();
+        reader.read_to_end(&mut data).unwrap();
+        assert_eq!(*starved_at.lock().unwrap(), None);
+        assert_eq!(underrun(None, status), Underrun::Finished);
+    }
 }
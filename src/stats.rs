@@ -0,0 +1,289 @@
+use crate::app::Podcast;
+use crate::history::{HistoryEvent, HistoryEventKind};
+use chrono::{Local, NaiveDate, TimeZone};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PodcastStats {
+    pub podcast_title: String,
+    /// Wall-clock seconds spent listening
+    pub listened_secs: u64,
+    pub episodes_finished: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DayStats {
+    /// Local date as YYYY-MM-DD
+    pub date: String,
+    pub listened_secs: u64,
+}
+
+/// Listening statistics derived from the history log
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ListeningStats {
+    /// Wall-clock seconds spent listening
+    pub total_listened_secs: u64,
+    /// Seconds of audio covered (more than listened time when sped up)
+    pub content_secs: u64,
+    /// Seconds saved by listening faster than 1x
+    pub time_saved_secs: u64,
+    pub episodes_finished: usize,
+    pub current_streak_days: u32,
+    pub longest_streak_days: u32,
+    /// Sorted by listening time, most listened first
+    pub per_podcast: Vec<PodcastStats>,
+    /// Every day from the first listening day until today, oldest first
+    pub per_day: Vec<DayStats>,
+}
+
+/// An open stretch of continuous playback
+struct Segment {
+    start_pos: u64,
+    start_ts: i64,
+    speed: f32,
+}
+
+#[derive(Default)]
+struct Totals {
+    listened: f64,
+    content: f64,
+}
+
+/// Compute statistics from history events.
+/// `durations` maps audio URLs to episode lengths in seconds, used for finish positions.
+pub fn compute<Tz: TimeZone>(
+    events: &[HistoryEvent],
+    durations: &HashMap<String, u64>,
+    today: NaiveDate,
+    tz: &Tz,
+) -> ListeningStats {
+    let mut sorted: Vec<&HistoryEvent> = events.iter().collect();
+    sorted.sort_by_key(|e| e.timestamp);
+
+    let mut open: HashMap<&str, Segment> = HashMap::new();
+    let mut totals = Totals::default();
+    let mut per_podcast: HashMap<&str, (Totals, usize)> = HashMap::new();
+    let mut per_day: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+    let mut finished = 0;
+
+    for event in sorted {
+        let url = event.audio_url.as_str();
+
+        // Where the current segment (if any) ends for this event
+        let end_pos = match event.kind {
+            HistoryEventKind::Seek => event.from_secs,
+            HistoryEventKind::Finish => Some(
+                durations
+                    .get(url)
+                    .copied()
+                    .unwrap_or(0)
+                    .max(event.position_secs),
+            ),
+            _ => Some(event.position_secs),
+        };
+
+        if let (Some(end_pos), Some(segment)) = (end_pos, open.remove(url)) {
+            let content = end_pos.saturating_sub(segment.start_pos) as f64;
+            let speed = segment.speed.max(0.1) as f64;
+            // Never count more audio than could have played in the elapsed wall time
+            let elapsed = (event.timestamp - segment.start_ts).max(0) as f64;
+            let content = content.min(elapsed * speed);
+            let listened = content / speed;
+
+            totals.listened += listened;
+            totals.content += content;
+
+            let podcast = per_podcast.entry(event.podcast_title.as_str()).or_default();
+            podcast.0.listened += listened;
+            podcast.0.content += content;
+
+            if let Some(date) = local_date(event.timestamp, tz) {
+                *per_day.entry(date).or_default() += listened;
+            }
+        }
+
+        match event.kind {
+            HistoryEventKind::Start | HistoryEventKind::Resume => {
+                open.insert(url, Segment {
+                    start_pos: event.position_secs,
+                    start_ts: event.timestamp,
+                    speed: event.speed,
+                });
+            }
+            HistoryEventKind::Seek if event.from_secs.is_some() => {
+                // Seeking while playing continues playback from the new position
+                open.insert(url, Segment {
+                    start_pos: event.position_secs,
+                    start_ts: event.timestamp,
+                    speed: event.speed,
+                });
+            }
+            HistoryEventKind::Finish => {
+                finished += 1;
+                per_podcast.entry(event.podcast_title.as_str()).or_default().1 += 1;
+            }
+            _ => {}
+        }
+    }
+
+    let mut podcasts: Vec<PodcastStats> = per_podcast
+        .into_iter()
+        .map(|(title, (t, finished))| PodcastStats {
+            podcast_title: title.to_string(),
+            listened_secs: t.listened.round() as u64,
+            episodes_finished: finished,
+        })
+        .collect();
+    podcasts.sort_by(|a, b| {
+        b.listened_secs
+            .cmp(&a.listened_secs)
+            .then_with(|| a.podcast_title.cmp(&b.podcast_title))
+    });
+
+    let (current_streak_days, longest_streak_days) = streaks(&per_day, today);
+
+    ListeningStats {
+        total_listened_secs: totals.listened.round() as u64,
+        content_secs: totals.content.round() as u64,
+        time_saved_secs: (totals.content - totals.listened).max(0.0).round() as u64,
+        episodes_finished: finished,
+        current_streak_days,
+        longest_streak_days,
+        per_podcast: podcasts,
+        per_day: fill_days(&per_day, today),
+    }
+}
+
+/// Compute statistics for the local timezone, taking episode durations from the subscriptions
+pub fn compute_local(events: &[HistoryEvent], podcasts: &[Podcast]) -> ListeningStats {
+    let durations: HashMap<String, u64> = podcasts
+        .iter()
+        .flat_map(|p| p.episodes.iter())
+        .filter_map(|e| e.duration.map(|d| (e.audio_url.clone(), d.as_secs())))
+        .collect();
+
+    compute(events, &durations, Local::now().date_naive(), &Local)
+}
+
+fn local_date<Tz: TimeZone>(timestamp: i64, tz: &Tz) -> Option<NaiveDate> {
+    chrono::DateTime::from_timestamp(timestamp, 0).map(|dt| dt.with_timezone(tz).date_naive())
+}
+
+/// Current streak (ending today, or yesterday if nothing yet today) and longest streak
+fn streaks(per_day: &BTreeMap<NaiveDate, f64>, today: NaiveDate) -> (u32, u32) {
+    let days: Vec<NaiveDate> = per_day
+        .iter()
+        .filter(|(_, secs)| **secs >= 1.0)
+        .map(|(date, _)| *date)
+        .collect();
+
+    let mut longest = 0;
+    let mut run = 0;
+    let mut prev: Option<NaiveDate> = None;
+    for &day in &days {
+        run = match prev {
+            Some(p) if p.succ_opt() == Some(day) => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        prev = Some(day);
+    }
+
+    let mut current = 0;
+    let mut day = if days.contains(&today) {
+        Some(today)
+    } else {
+        today.pred_opt()
+    };
+    while let Some(d) = day {
+        if !days.contains(&d) {
+            break;
+        }
+        current += 1;
+        day = d.pred_opt();
+    }
+
+    (current, longest)
+}
+
+/// Expand the per-day map into a contiguous series ending today
+fn fill_days(per_day: &BTreeMap<NaiveDate, f64>, today: NaiveDate) -> Vec<DayStats> {
+    let Some(first) = per_day.keys().next().copied() else {
+        return Vec::new();
+    };
+
+    let mut days = Vec::new();
+    let mut day = first.min(today);
+    while day <= today {
+        days.push(DayStats {
+            date: day.format("%Y-%m-%d").to_string(),
+            listened_secs: per_day.get(&day).copied().unwrap_or(0.0).round() as u64,
+        });
+        match day.succ_opt() {
+            Some(next) => day = next,
+            None => break,
+        }
+    }
+    days
+}
+
+/// Format seconds as e.g. "3h 05m" or "12m"
+pub fn format_hours(secs: u64) -> String {
+    let hours = secs / 3600;
+    let mins = (secs % 3600) / 60;
+    if hours > 0 {
+        format!("{}h {:02}m", hours, mins)
+    } else {
+        format!("{}m", mins)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    // 2024-01-01T12:00:00Z
+    const DAY1: i64 = 1_704_110_400;
+    const DAY: i64 = 86_400;
+
+    fn event(timestamp: i64, kind: HistoryEventKind, position_secs: u64, from_secs: Option<u64>, speed: f32) -> HistoryEvent {
+        HistoryEvent {
+            timestamp,
+            kind,
+            podcast_title: "Show".to_string(),
+            podcast_url: "https://example.com/feed".to_string(),
+            episode_title: "Episode".to_string(),
+            audio_url: "a.mp3".to_string(),
+            position_secs,
+            from_secs,
+            speed,
+        }
+    }
+
+    #[test]
+    fn test_stats_listened_and_saved_time() {
+        let events = vec![
+            // 600s of audio at 2x over 300s of wall time
+            event(DAY1, HistoryEventKind::Start, 0, None, 2.0),
+            event(DAY1 + 300, HistoryEventKind::Pause, 600, None, 2.0),
+            // Resume, skip ahead 30s, then finish (duration 1000s)
+            event(DAY1 + DAY, HistoryEventKind::Resume, 600, None, 1.0),
+            event(DAY1 + DAY + 100, HistoryEventKind::Seek, 730, Some(700), 1.0),
+            event(DAY1 + DAY + 400, HistoryEventKind::Finish, 0, None, 1.0),
+        ];
+        let durations = HashMap::from([("a.mp3".to_string(), 1000)]);
+        let today = NaiveDate::from_ymd_opt(2024, 1, 3).unwrap();
+
+        let stats = compute(&events, &durations, today, &Utc);
+        assert_eq!(stats.content_secs, 600 + 100 + 270);
+        assert_eq!(stats.total_listened_secs, 300 + 100 + 270);
+        assert_eq!(stats.time_saved_secs, 300);
+        assert_eq!(stats.episodes_finished, 1);
+        assert_eq!(stats.per_podcast[0].episodes_finished, 1);
+        assert_eq!(stats.per_day.len(), 3);
+        assert_eq!(stats.per_day[2].listened_secs, 0);
+        assert_eq!((stats.current_streak_days, stats.longest_streak_days), (2, 2));
+    }
+}
@@ -0,0 +1,146 @@
+use crate::app::Podcast;
+use crate::browse::{self, SearchResult};
+use crate::config::SyncConfig;
+use crate::directory::{Category, Directory};
+use crate::sync::gpodder::GpodderBackend;
+use std::collections::{HashMap, HashSet};
+
+/// Number of the most common subscription tags to suggest from
+pub const SUGGESTION_TAGS: usize = 5;
+
+/// What suggestions are based on, gathered on the UI thread before fetching
+pub struct SuggestionSource {
+    /// gpodder.net account, if one is configured for sync
+    pub account: Option<SyncConfig>,
+    /// Most common tags of the subscribed podcasts
+    pub tags: Vec<String>,
+    /// Normalized feed URLs of the subscribed podcasts
+    pub subscribed: HashSet<String>,
+    /// Directory categories that tags are matched against
+    pub categories: Vec<Category>,
+}
+
+impl SuggestionSource {
+    pub fn new(podcasts: &[Podcast], sync_config: Option<&SyncConfig>, categories: &[Category]) -> Self {
+        Self {
+            // Other sync backends have no suggestions endpoint
+            account: sync_config.filter(|c| c.backend == "gpodder").cloned(),
+            tags: top_tags(podcasts, SUGGESTION_TAGS),
+            subscribed: podcasts.iter().map(|p| browse::normalize_feed_url(&p.url)).collect(),
+            categories: categories.to_vec(),
+        }
+    }
+
+    fn exclude_subscribed(&self, results: Vec<SearchResult>) -> Vec<SearchResult> {
+        results
+            .into_iter()
+            .filter(|r| !self.subscribed.contains(&browse::normalize_feed_url(&r.feed_url)))
+            .collect()
+    }
+}
+
+/// The most common categories across the subscribed podcasts, most common first
+pub fn top_tags(podcasts: &[Podcast], limit: usize) -> Vec<String> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    let mut order: Vec<String> = Vec::new();
+
+    for podcast in podcasts {
+        let tags: HashSet<String> = podcast.categories.iter().map(|c| c.trim().to_lowercase()).collect();
+        for tag in tags {
+            let count = counts.entry(tag.clone()).or_insert(0);
+            if *count == 0 {
+                order.push(tag);
+            }
+            *count += 1;
+        }
+    }
+
+    // Stable sort keeps first-seen order for ties
+    order.sort_by_key(|tag| std::cmp::Reverse(counts[tag]));
+    order.truncate(limit);
+    order
+}
+
+/// Directory category for a tag: a listed category with the same name, or the gpodder.net tag
+fn tag_category(tag: &str, categories: &[Category]) -> Category {
+    categories
+        .iter()
+        .find(|c| c.title.eq_ignore_ascii_case(tag))
+        .cloned()
+        .unwrap_or_else(|| Category::new("gpodder", tag, tag))
+}
+
+/// Suggested podcasts, excluding ones already subscribed. Uses gpodder.net's
+/// suggestions for the account when possible, otherwise the subscriptions' tags.
+pub fn fetch(source: &SuggestionSource, directory: &Directory, limit: usize) -> Result<Vec<SearchResult>, String> {
+    if let Some(config) = &source.account {
+        match GpodderBackend::new(config).and_then(|backend| backend.suggestions(limit)) {
+            Ok(results) if !results.is_empty() => {
+                let mut results = source.exclude_subscribed(results);
+                results.truncate(limit);
+                return Ok(results);
+            }
+            Ok(_) => log::info!("No gpodder.net suggestions yet, using tags"),
+            Err(e) => log::warn!("gpodder.net suggestions failed, using tags: {}", e),
+        }
+    }
+
+    let mut results = Vec::new();
+    let mut last_error = None;
+    for tag in &source.tags {
+        match directory.category_podcasts(&tag_category(tag, &source.categories), limit) {
+            Ok(found) => results.extend(found),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    if results.is_empty() {
+        if let Some(e) = last_error {
+            return Err(e);
+        }
+    }
+
+    let mut results = source.exclude_subscribed(browse::dedupe_results(results));
+    results.truncate(limit);
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn podcast(url: &str, categories: &[&str]) -> Podcast {
+        Podcast {
+            title: String::new(),
+            description: String::new(),
+            url: url.to_string(),
+            episodes: Vec::new(),
+            filter: Default::default(),
+            categories: categories.iter().map(|c| c.to_string()).collect(),
+            image_url: None,
+        }
+    }
+
+    #[test]
+    fn test_top_tags_and_exclusion() {
+        let podcasts = vec![
+            podcast("https://a.com/feed", &["Technology", "News"]),
+            podcast("https://b.com/feed", &["Comedy"]),
+            podcast("https://c.com/feed", &["technology", "Comedy", "Technology"]),
+        ];
+        assert_eq!(top_tags(&podcasts, 2), vec!["technology", "comedy"]);
+
+        let source = SuggestionSource::new(&podcasts, None, &[]);
+        let result = |url: &str| SearchResult {
+            title: String::new(),
+            author: String::new(),
+            description: String::new(),
+            feed_url: url.to_string(),
+            artwork_url: None,
+            subscribers: 0,
+            website: None,
+        };
+        let kept = source.exclude_subscribed(vec![result("http://www.a.com/feed/"), result("https://d.com/feed")]);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].feed_url, "https://d.com/feed");
+    }
+}
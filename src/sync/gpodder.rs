@@ -0,0 +1,155 @@
+use super::{EpisodeAction, HttpClient, SubscriptionChanges, SyncBackend};
+use crate::browse::SearchResult;
+use crate::config::SyncConfig;
+use crate::directory;
+use serde::Deserialize;
+use serde_json::json;
+
+/// gpodder.net API v2 (or a self-hosted instance such as mygpo)
+pub struct GpodderBackend {
+    http: HttpClient,
+    server: String,
+    username: String,
+    device_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct UploadResponse {
+    timestamp: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct EpisodeActionsResponse {
+    #[serde(default)]
+    actions: Vec<EpisodeAction>,
+    timestamp: i64,
+}
+
+impl GpodderBackend {
+    pub fn new(config: &SyncConfig) -> Result<Self, String> {
+        Ok(Self {
+            http: HttpClient::new(config)?,
+            server: config.server.trim_end_matches('/').to_string(),
+            username: config.username.clone(),
+            device_id: config.device_id.clone(),
+        })
+    }
+
+    fn subscriptions_url(&self) -> String {
+        format!("{}/api/2/subscriptions/{}/{}.json", self.server, self.username, self.device_id)
+    }
+
+    fn episodes_url(&self) -> String {
+        format!("{}/api/2/episodes/{}.json", self.server, self.username)
+    }
+
+    /// Podcasts gpodder.net recommends for this account
+    pub fn suggestions(&self, limit: usize) -> Result<Vec<SearchResult>, String> {
+        let count = limit.clamp(1, 100); // API accepts 1-100
+        let items: Vec<serde_json::Value> =
+            self.http.get(&format!("{}/suggestions/{}.json", self.server, count))?;
+        Ok(items.iter().filter_map(directory::gpodder::parse_podcast).collect())
+    }
+}
+
+impl SyncBackend for GpodderBackend {
+    fn register_device(&self) -> Result<(), String> {
+        let url = format!("{}/api/2/devices/{}/{}.json", self.server, self.username, self.device_id);
+        let body = json!({ "caption": "shellcast", "type": "desktop" });
+
+        // The response body is empty on success
+        self.http.post_empty(&url, &body)
+    }
+
+    fn pull_subscriptions(&self, since: i64) -> Result<SubscriptionChanges, String> {
+        self.http.get(&format!("{}?since={}", self.subscriptions_url(), since))
+    }
+
+    fn push_subscriptions(&self, add: &[String], remove: &[String]) -> Result<i64, String> {
+        let body = json!({ "add": add, "remove": remove });
+        let response: UploadResponse = self.http.post(&self.subscriptions_url(), &body)?;
+        Ok(response.timestamp)
+    }
+
+    fn pull_episode_actions(&self, since: i64) -> Result<(Vec<EpisodeAction>, i64), String> {
+        let response: EpisodeActionsResponse =
+            self.http.get(&format!("{}?since={}", self.episodes_url(), since))?;
+        Ok((response.actions, response.timestamp))
+    }
+
+    fn push_episode_actions(&self, actions: &[EpisodeAction]) -> Result<i64, String> {
+        let response: UploadResponse = self.http.post(&self.episodes_url(), actions)?;
+        Ok(response.timestamp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{stub, ActionKind};
+
+    #[test]
+    fn test_gpodder_endpoints_against_stub_server() {
+        let server = stub::serve(vec![
+            ("POST", "/api/2/devices/alice/laptop.json", String::new()),
+            (
+                "GET",
+                "/api/2/subscriptions/alice/laptop.json",
+                r#"{"add": ["https://example.com/feed"], "remove": [], "timestamp": 12}"#.to_string(),
+            ),
+            (
+                "POST",
+                "/api/2/subscriptions/alice/laptop.json",
+                r#"{"timestamp": 13, "update_urls": []}"#.to_string(),
+            ),
+            (
+                "GET",
+                "/api/2/episodes/alice.json",
+                r#"{"actions": [{"podcast": "https://example.com/feed", "episode": "https://example.com/1.mp3",
+                    "device": "phone", "action": "play", "timestamp": "2024-01-01T12:00:00",
+                    "started": 0, "position": 120, "total": 600}], "timestamp": 14}"#
+                    .to_string(),
+            ),
+            ("POST", "/api/2/episodes/alice.json", r#"{"timestamp": 15, "update_urls": []}"#.to_string()),
+            (
+                "GET",
+                "/suggestions/10.json",
+                r#"[{"url": "https://example.com/suggested", "title": "Suggested", "subscribers": 7}]"#.to_string(),
+            ),
+        ]);
+
+        let backend = GpodderBackend::new(&SyncConfig {
+            backend: "gpodder".to_string(),
+            server: format!("{}/", server.url),
+            username: "alice".to_string(),
+            password: "secret".to_string(),
+            device_id: "laptop".to_string(),
+        })
+        .unwrap();
+
+        backend.register_device().unwrap();
+
+        let changes = backend.pull_subscriptions(5).unwrap();
+        assert_eq!(changes.add, vec!["https://example.com/feed".to_string()]);
+        assert_eq!(changes.timestamp, 12);
+        assert_eq!(backend.push_subscriptions(&["https://example.com/other".to_string()], &[]).unwrap(), 13);
+
+        let (actions, timestamp) = backend.pull_episode_actions(0).unwrap();
+        assert_eq!(timestamp, 14);
+        assert_eq!(actions[0].kind(), Some(ActionKind::Play));
+        assert_eq!(actions[0].position, Some(120));
+        assert_eq!(backend.push_episode_actions(&actions).unwrap(), 15);
+
+        let suggestions = backend.suggestions(10).unwrap();
+        assert_eq!(suggestions[0].feed_url, "https://example.com/suggested");
+        assert_eq!(suggestions[0].subscribers, 7);
+
+        let requests = server.requests.lock().unwrap();
+        assert_eq!(requests.len(), 6);
+        assert!(requests.iter().all(|r| r.authorized));
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[1].url, "/api/2/subscriptions/alice/laptop.json?since=5");
+        assert!(requests[2].body.contains("https://example.com/other"));
+        assert!(requests[4].body.contains("\"position\":120"));
+    }
+}
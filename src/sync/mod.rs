@@ -0,0 +1,710 @@
+pub mod gpodder;
+pub mod nextcloud;
+
+use crate::app::Podcast;
+use crate::config::SyncConfig;
+use crate::persistence;
+use chrono::{DateTime, NaiveDateTime};
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::time::Duration;
+
+const SYNC_FILE: &str = "sync.json";
+
+/// A server that stores subscriptions and episode actions for several devices
+pub trait SyncBackend {
+    /// Announce this device to the server before syncing
+    fn register_device(&self) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Subscription changes made by other devices since the given timestamp
+    fn pull_subscriptions(&self, since: i64) -> Result<SubscriptionChanges, String>;
+
+    /// Upload local subscription changes, returning the server timestamp
+    fn push_subscriptions(&self, add: &[String], remove: &[String]) -> Result<i64, String>;
+
+    /// Episode actions uploaded since the given timestamp, with the server timestamp
+    fn pull_episode_actions(&self, since: i64) -> Result<(Vec<EpisodeAction>, i64), String>;
+
+    /// Upload episode actions, returning the server timestamp
+    fn push_episode_actions(&self, actions: &[EpisodeAction]) -> Result<i64, String>;
+}
+
+/// Create the backend selected in the config
+pub fn backend_for(config: &SyncConfig) -> Result<Box<dyn SyncBackend + Send>, String> {
+    match config.backend.as_str() {
+        "gpodder" => Ok(Box::new(gpodder::GpodderBackend::new(config)?)),
+        "nextcloud" => Ok(Box::new(nextcloud::NextcloudBackend::new(config)?)),
+        other => Err(format!("Unknown sync backend '{}'", other)),
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SubscriptionChanges {
+    #[serde(default)]
+    pub add: Vec<String>,
+    #[serde(default)]
+    pub remove: Vec<String>,
+    #[serde(default)]
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionKind {
+    Play,
+    Download,
+    Delete,
+    New,
+}
+
+impl ActionKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Play => "play",
+            Self::Download => "download",
+            Self::Delete => "delete",
+            Self::New => "new",
+        }
+    }
+}
+
+/// An episode action in the gpodder wire format
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EpisodeAction {
+    pub podcast: String,
+    pub episode: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device: Option<String>,
+    pub action: String,
+    /// UTC time formatted as YYYY-MM-DDTHH:MM:SS
+    pub timestamp: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<i64>,
+}
+
+impl EpisodeAction {
+    fn new(podcast: &str, episode: &str, device: &str, kind: ActionKind, timestamp: i64) -> Self {
+        Self {
+            podcast: podcast.to_string(),
+            episode: episode.to_string(),
+            device: Some(device.to_string()),
+            action: kind.name().to_string(),
+            timestamp: format_timestamp(timestamp),
+            started: None,
+            position: None,
+            total: None,
+        }
+    }
+
+    /// The action type; servers differ in capitalization
+    pub fn kind(&self) -> Option<ActionKind> {
+        match self.action.to_lowercase().as_str() {
+            "play" => Some(ActionKind::Play),
+            "download" => Some(ActionKind::Download),
+            "delete" => Some(ActionKind::Delete),
+            "new" => Some(ActionKind::New),
+            _ => None,
+        }
+    }
+}
+
+pub fn format_timestamp(timestamp: i64) -> String {
+    DateTime::from_timestamp(timestamp, 0)
+        .map(|dt| dt.format("%Y-%m-%dT%H:%M:%S").to_string())
+        .unwrap_or_default()
+}
+
+/// Parse an action timestamp, with or without fractional seconds or an offset
+pub fn parse_timestamp(value: &str) -> Option<i64> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Some(dt.timestamp());
+    }
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")
+        .ok()
+        .map(|dt| dt.and_utc().timestamp())
+}
+
+/// Synced state of one episode
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EpisodeSnapshot {
+    #[serde(default)]
+    pub played: bool,
+    #[serde(default)]
+    pub position_secs: u64,
+    #[serde(default)]
+    pub downloaded: bool,
+}
+
+/// What was known at the end of the last successful sync
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncState {
+    /// Backend, server, user and device the state belongs to
+    #[serde(default)]
+    pub account: String,
+    #[serde(default)]
+    pub subscriptions_since: i64,
+    #[serde(default)]
+    pub actions_since: i64,
+    /// Subscribed feed URLs as of the last sync
+    #[serde(default)]
+    pub subscriptions: BTreeSet<String>,
+    /// Episode state by audio URL as of the last sync (default states are omitted)
+    #[serde(default)]
+    pub episodes: HashMap<String, EpisodeSnapshot>,
+}
+
+fn account_key(config: &SyncConfig) -> String {
+    format!("{}:{}:{}:{}", config.backend, config.server, config.username, config.device_id)
+}
+
+/// Load the sync state, starting over if the account has changed
+pub fn load_state(config: &SyncConfig) -> Result<SyncState, String> {
+    let path = persistence::get_data_file(SYNC_FILE)?;
+    let account = account_key(config);
+
+    let state = if path.exists() {
+        let json = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read sync state: {}", e))?;
+        serde_json::from_str(&json)
+            .map_err(|e| format!("Failed to deserialize sync state: {}", e))?
+    } else {
+        SyncState::default()
+    };
+
+    if state.account != account {
+        return Ok(SyncState {
+            account,
+            ..Default::default()
+        });
+    }
+    Ok(state)
+}
+
+pub fn save_state(state: &SyncState) -> Result<(), String> {
+    let path = persistence::get_data_file(SYNC_FILE)?;
+
+    let json = serde_json::to_string_pretty(state)
+        .map_err(|e| format!("Failed to serialize sync state: {}", e))?;
+
+    persistence::write_atomic(&path, &json)
+        .map_err(|e| format!("Failed to write sync state: {}", e))?;
+
+    Ok(())
+}
+
+/// Remote episode state to apply locally
+#[derive(Debug, Clone, PartialEq)]
+pub struct EpisodeUpdate {
+    pub audio_url: String,
+    pub played: bool,
+    pub position_secs: u64,
+    pub timestamp: i64,
+}
+
+/// Changes to apply locally after a sync
+#[derive(Debug, Default)]
+pub struct SyncOutcome {
+    /// Feeds subscribed on another device
+    pub subscribe: Vec<String>,
+    /// Feeds unsubscribed on another device
+    pub unsubscribe: Vec<String>,
+    pub updates: Vec<EpisodeUpdate>,
+    pub pushed_actions: usize,
+}
+
+/// Run a full sync of subscriptions and episode state.
+/// `podcasts` is left untouched; the returned outcome describes what to apply.
+pub fn run(
+    backend: &dyn SyncBackend,
+    device_id: &str,
+    podcasts: &[Podcast],
+    state: &mut SyncState,
+    now: i64,
+) -> Result<SyncOutcome, String> {
+    backend.register_device()?;
+
+    let mut outcome = SyncOutcome::default();
+    sync_subscriptions(backend, podcasts, state, &mut outcome)?;
+    sync_episodes(backend, device_id, podcasts, state, now, &mut outcome)?;
+    Ok(outcome)
+}
+
+fn sync_subscriptions(
+    backend: &dyn SyncBackend,
+    podcasts: &[Podcast],
+    state: &mut SyncState,
+    outcome: &mut SyncOutcome,
+) -> Result<(), String> {
+    let local: BTreeSet<String> = podcasts.iter().map(|p| p.url.clone()).collect();
+    let local_add: Vec<String> = local.difference(&state.subscriptions).cloned().collect();
+    let local_remove: Vec<String> = state.subscriptions.difference(&local).cloned().collect();
+
+    let remote = backend.pull_subscriptions(state.subscriptions_since)?;
+    let mut since = remote.timestamp;
+
+    // Local changes since the last sync win over remote ones
+    outcome.subscribe = remote
+        .add
+        .iter()
+        .filter(|url| !local.contains(*url) && !local_remove.contains(url))
+        .cloned()
+        .collect();
+    outcome.unsubscribe = remote
+        .remove
+        .iter()
+        .filter(|url| local.contains(*url) && !local_add.contains(url))
+        .cloned()
+        .collect();
+
+    if !local_add.is_empty() || !local_remove.is_empty() {
+        since = since.max(backend.push_subscriptions(&local_add, &local_remove)?);
+    }
+
+    // Feeds still to be fetched are left out, so a failed fetch is never pushed as a removal
+    state.subscriptions = local
+        .into_iter()
+        .filter(|url| !outcome.unsubscribe.contains(url))
+        .collect();
+    state.subscriptions_since = since;
+    Ok(())
+}
+
+fn sync_episodes(
+    backend: &dyn SyncBackend,
+    device_id: &str,
+    podcasts: &[Podcast],
+    state: &mut SyncState,
+    now: i64,
+    outcome: &mut SyncOutcome,
+) -> Result<(), String> {
+    let (remote_actions, remote_since) = backend.pull_episode_actions(state.actions_since)?;
+
+    // Latest play/new action per episode from other devices
+    let mut remote: HashMap<&str, (i64, EpisodeSnapshot)> = HashMap::new();
+    for action in &remote_actions {
+        if action.device.as_deref() == Some(device_id) {
+            continue;
+        }
+        let Some(timestamp) = parse_timestamp(&action.timestamp) else {
+            log::warn!("Skipping episode action with bad timestamp '{}'", action.timestamp);
+            continue;
+        };
+        let remote_state = match action.kind() {
+            Some(ActionKind::Play) => {
+                let position = action.position.unwrap_or(0).max(0) as u64;
+                let total = action.total.unwrap_or(0).max(0) as u64;
+                if total > 0 && position >= total {
+                    EpisodeSnapshot { played: true, position_secs: 0, downloaded: false }
+                } else {
+                    EpisodeSnapshot { played: false, position_secs: position, downloaded: false }
+                }
+            }
+            Some(ActionKind::New) => EpisodeSnapshot::default(),
+            // Downloads are per device, so remote ones don't change local state
+            Some(ActionKind::Download) | Some(ActionKind::Delete) | None => continue,
+        };
+        let entry = remote.entry(action.episode.as_str()).or_insert((timestamp, remote_state));
+        if timestamp >= entry.0 {
+            *entry = (timestamp, remote_state);
+        }
+    }
+
+    let mut push = Vec::new();
+    let mut snapshots = HashMap::new();
+
+    for podcast in podcasts {
+        for episode in &podcast.episodes {
+            let url = episode.audio_url.as_str();
+            let previous = state.episodes.get(url).copied().unwrap_or_default();
+            let mut current = EpisodeSnapshot {
+                played: episode.played,
+                position_secs: if episode.played { 0 } else { episode.position_secs },
+                downloaded: episode.download_path.is_some(),
+            };
+
+            let progress_changed = current.played != previous.played
+                || current.position_secs != previous.position_secs;
+            let local_ts = episode.state_changed.unwrap_or(now);
+
+            // A remote change wins unless there is a newer local change
+            let remote_change = remote
+                .remove(url)
+                .filter(|(remote_ts, _)| !progress_changed || *remote_ts > local_ts);
+
+            if let Some((remote_ts, remote_state)) = remote_change {
+                if remote_state.played != current.played
+                    || remote_state.position_secs != current.position_secs
+                {
+                    outcome.updates.push(EpisodeUpdate {
+                        audio_url: url.to_string(),
+                        played: remote_state.played,
+                        position_secs: remote_state.position_secs,
+                        timestamp: remote_ts,
+                    });
+                    current.played = remote_state.played;
+                    current.position_secs = remote_state.position_secs;
+                }
+            } else if progress_changed {
+                push.push(progress_action(&podcast.url, episode, &current, device_id, local_ts));
+            }
+
+            if current.downloaded != previous.downloaded {
+                let kind = if current.downloaded { ActionKind::Download } else { ActionKind::Delete };
+                push.push(EpisodeAction::new(&podcast.url, url, device_id, kind, now));
+            }
+
+            if current != EpisodeSnapshot::default() {
+                snapshots.insert(url.to_string(), current);
+            }
+        }
+    }
+
+    // Episodes of feeds that are not subscribed locally (yet)
+    for (url, (timestamp, remote_state)) in remote {
+        outcome.updates.push(EpisodeUpdate {
+            audio_url: url.to_string(),
+            played: remote_state.played,
+            position_secs: remote_state.position_secs,
+            timestamp,
+        });
+        if remote_state != EpisodeSnapshot::default() {
+            snapshots.insert(url.to_string(), remote_state);
+        }
+    }
+
+    let mut since = remote_since;
+    if !push.is_empty() {
+        since = since.max(backend.push_episode_actions(&push)?);
+    }
+
+    outcome.pushed_actions = push.len();
+    state.episodes = snapshots;
+    state.actions_since = since;
+    Ok(())
+}
+
+/// Describe an episode's played/position state as a play or new action
+fn progress_action(
+    podcast_url: &str,
+    episode: &crate::app::Episode,
+    current: &EpisodeSnapshot,
+    device_id: &str,
+    timestamp: i64,
+) -> EpisodeAction {
+    let total = episode.duration.map(|d| d.as_secs() as i64);
+
+    if current.played {
+        // A play action that reaches the end marks the episode as played
+        let total = total.unwrap_or(1).max(1);
+        EpisodeAction {
+            started: Some(0),
+            position: Some(total),
+            total: Some(total),
+            ..EpisodeAction::new(podcast_url, &episode.audio_url, device_id, ActionKind::Play, timestamp)
+        }
+    } else if current.position_secs > 0 {
+        let position = current.position_secs as i64;
+        EpisodeAction {
+            started: Some(0),
+            position: Some(position),
+            total: Some(total.unwrap_or(0).max(position + 1)),
+            ..EpisodeAction::new(podcast_url, &episode.audio_url, device_id, ActionKind::Play, timestamp)
+        }
+    } else {
+        EpisodeAction::new(podcast_url, &episode.audio_url, device_id, ActionKind::New, timestamp)
+    }
+}
+
+/// Blocking JSON client with HTTP basic auth, shared by the backends
+struct HttpClient {
+    client: Client,
+    username: String,
+    password: String,
+}
+
+impl HttpClient {
+    fn new(config: &SyncConfig) -> Result<Self, String> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .user_agent(concat!("shellcast/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+        Ok(Self {
+            client,
+            username: config.username.clone(),
+            password: config.password.clone(),
+        })
+    }
+
+    /// Send an authenticated request, turning error statuses into errors
+    fn send(&self, request: RequestBuilder) -> Result<Response, String> {
+        let response = request
+            .basic_auth(&self.username, Some(&self.password))
+            .send()
+            .map_err(|e| format!("Failed to reach sync server: {}", e))?;
+
+        let status = response.status();
+        if status == StatusCode::UNAUTHORIZED {
+            return Err("Sync server rejected the username or password".to_string());
+        }
+        if !status.is_success() {
+            return Err(format!("Sync server returned status {}", status));
+        }
+        Ok(response)
+    }
+
+    fn get<T: DeserializeOwned>(&self, url: &str) -> Result<T, String> {
+        self.send(self.client.get(url))?
+            .json()
+            .map_err(|e| format!("Failed to parse sync response: {}", e))
+    }
+
+    fn post<B: Serialize + ?Sized, T: DeserializeOwned>(&self, url: &str, body: &B) -> Result<T, String> {
+        self.send(self.client.post(url).json(body))?
+            .json()
+            .map_err(|e| format!("Failed to parse sync response: {}", e))
+    }
+
+    /// POST ignoring the response body
+    fn post_empty<B: Serialize + ?Sized>(&self, url: &str, body: &B) -> Result<(), String> {
+        self.send(self.client.post(url).json(body)).map(|_| ())
+    }
+}
+
+/// Minimal HTTP server answering fixed JSON responses, for backend tests
+#[cfg(test)]
+pub(crate) mod stub {
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug, Clone)]
+    pub struct Request {
+        pub method: String,
+        pub url: String,
+        pub body: String,
+        pub authorized: bool,
+    }
+
+    pub struct StubServer {
+        pub url: String,
+        pub requests: Arc<Mutex<Vec<Request>>>,
+    }
+
+    /// Serve `(method, path, response body)` routes; the query string is ignored when matching
+    pub fn serve(routes: Vec<(&'static str, &'static str, String)>) -> StubServer {
+        let server = tiny_http::Server::http("127.0.0.1:0").expect("Failed to start stub server");
+        let port = server.server_addr().to_ip().expect("Stub server has no IP address").port();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let log = Arc::clone(&requests);
+        std::thread::spawn(move || {
+            for mut request in server.incoming_requests() {
+                let mut body = String::new();
+                let _ = request.as_reader().read_to_string(&mut body);
+                let method = request.method().as_str().to_string();
+                let url = request.url().to_string();
+                let path = url.split('?').next().unwrap_or_default().to_string();
+                let authorized = request
+                    .headers()
+                    .iter()
+                    .any(|h| h.field.equiv("Authorization") && h.value.as_str().starts_with("Basic "));
+
+                log.lock().unwrap().push(Request { method: method.clone(), url, body, authorized });
+
+                let response = match routes.iter().find(|(m, p, _)| *m == method && *p == path) {
+                    Some((_, _, body)) => tiny_http::Response::from_string(body.clone())
+                        .with_header("Content-Type: application/json".parse::<tiny_http::Header>().unwrap()),
+                    None => tiny_http::Response::from_string("not found").with_status_code(404),
+                };
+                let _ = request.respond(response);
+            }
+        });
+
+        StubServer {
+            url: format!("http://127.0.0.1:{}", port),
+            requests,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::Episode;
+    use std::cell::RefCell;
+
+    /// In-memory backend recording what was pushed
+    #[derive(Default)]
+    struct MemoryBackend {
+        subscriptions: SubscriptionChanges,
+        actions: Vec<EpisodeAction>,
+        pushed_subscriptions: RefCell<Vec<(Vec<String>, Vec<String>)>>,
+        pushed_actions: RefCell<Vec<EpisodeAction>>,
+    }
+
+    impl SyncBackend for MemoryBackend {
+        fn pull_subscriptions(&self, _since: i64) -> Result<SubscriptionChanges, String> {
+            Ok(self.subscriptions.clone())
+        }
+
+        fn push_subscriptions(&self, add: &[String], remove: &[String]) -> Result<i64, String> {
+            self.pushed_subscriptions.borrow_mut().push((add.to_vec(), remove.to_vec()));
+            Ok(200)
+        }
+
+        fn pull_episode_actions(&self, _since: i64) -> Result<(Vec<EpisodeAction>, i64), String> {
+            Ok((self.actions.clone(), 100))
+        }
+
+        fn push_episode_actions(&self, actions: &[EpisodeAction]) -> Result<i64, String> {
+            self.pushed_actions.borrow_mut().extend_from_slice(actions);
+            Ok(200)
+        }
+    }
+
+    fn episode(audio_url: &str, played: bool, position_secs: u64, state_changed: Option<i64>) -> Episode {
+        Episode {
+            title: audio_url.to_string(),
+            duration: Some(Duration::from_secs(600)),
+            audio_url: audio_url.to_string(),
+            played,
+            position_secs,
+            state_changed,
+            ..Default::default()
+        }
+    }
+
+    fn podcast(url: &str, episodes: Vec<Episode>) -> Podcast {
+        Podcast {
+            title: url.to_string(),
+            description: String::new(),
+            url: url.to_string(),
+            episodes,
+            filter: Default::default(),
+            categories: Vec::new(),
+            image_url: None,
+        }
+    }
+
+    fn play(episode: &str, timestamp: i64, position: i64) -> EpisodeAction {
+        EpisodeAction {
+            started: Some(0),
+            position: Some(position),
+            total: Some(600),
+            ..EpisodeAction::new("feed", episode, "phone", ActionKind::Play, timestamp)
+        }
+    }
+
+    #[test]
+    fn test_sync_subscriptions_merge_both_ways() {
+        let backend = MemoryBackend {
+            subscriptions: SubscriptionChanges {
+                add: vec!["remote-new".to_string(), "locally-removed".to_string()],
+                remove: vec!["kept".to_string()],
+                timestamp: 50,
+            },
+            ..Default::default()
+        };
+        let podcasts = vec![podcast("kept", vec![]), podcast("local-new", vec![])];
+        let mut state = SyncState {
+            subscriptions: ["kept", "locally-removed"].iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        };
+
+        let outcome = run(&backend, "laptop", &podcasts, &mut state, 1_000).unwrap();
+        assert_eq!(outcome.subscribe, vec!["remote-new".to_string()]);
+        assert_eq!(outcome.unsubscribe, vec!["kept".to_string()]);
+        assert_eq!(
+            backend.pushed_subscriptions.borrow().as_slice(),
+            &[(vec!["local-new".to_string()], vec!["locally-removed".to_string()])]
+        );
+        assert_eq!(state.subscriptions.iter().collect::<Vec<_>>(), vec!["local-new"]);
+        assert_eq!(state.subscriptions_since, 200);
+    }
+
+    #[test]
+    fn test_sync_episode_conflicts_resolved_by_timestamp() {
+        let backend = MemoryBackend {
+            actions: vec![
+                // Newer than the local change: remote wins
+                play("a.mp3", 2_000, 120),
+                // Older than the local change: local wins and is pushed
+                play("b.mp3", 500, 300),
+                // Unchanged locally: remote finished it
+                play("c.mp3", 100, 600),
+                // Our own upload is ignored
+                EpisodeAction::new("feed", "d.mp3", "laptop", ActionKind::New, 3_000),
+            ],
+            ..Default::default()
+        };
+        let podcasts = vec![podcast("feed", vec![
+            episode("a.mp3", true, 0, Some(1_000)),
+            episode("b.mp3", false, 60, Some(1_000)),
+            episode("c.mp3", false, 0, None),
+            episode("d.mp3", false, 30, None),
+        ])];
+        let mut state = SyncState {
+            episodes: HashMap::from([(
+                "d.mp3".to_string(),
+                EpisodeSnapshot { played: false, position_secs: 30, downloaded: false },
+            )]),
+            ..Default::default()
+        };
+
+        let outcome = run(&backend, "laptop", &podcasts, &mut state, 5_000).unwrap();
+
+        let updates: Vec<(&str, bool, u64)> = outcome
+            .updates
+            .iter()
+            .map(|u| (u.audio_url.as_str(), u.played, u.position_secs))
+            .collect();
+        assert_eq!(updates, vec![("a.mp3", false, 120), ("c.mp3", true, 0)]);
+
+        let pushed = backend.pushed_actions.borrow();
+        assert_eq!(pushed.len(), 1);
+        assert_eq!(pushed[0].episode, "b.mp3");
+        assert_eq!(pushed[0].kind(), Some(ActionKind::Play));
+        assert_eq!(pushed[0].position, Some(60));
+        assert_eq!(parse_timestamp(&pushed[0].timestamp), Some(1_000));
+
+        assert_eq!(state.episodes["a.mp3"].position_secs, 120);
+        assert!(state.episodes["c.mp3"].played);
+        assert_eq!(state.actions_since, 200);
+    }
+
+    #[test]
+    fn test_local_changes_during_sync_survive() {
+        let backend = MemoryBackend {
+            actions: vec![play("a.mp3", 2_000, 120), play("b.mp3", 2_000, 300)],
+            ..Default::default()
+        };
+        let mut app = crate::app::App::new();
+        app.podcasts = vec![podcast("feed", vec![
+            episode("a.mp3", false, 0, Some(1_000)),
+            episode("b.mp3", false, 0, Some(1_000)),
+        ])];
+
+        // The sync runs over a snapshot while the user marks an episode played
+        let snapshot = app.podcasts.clone();
+        app.podcasts[0].episodes[0].played = true;
+        app.podcasts[0].episodes[0].touch();
+        let outcome = run(&backend, "laptop", &snapshot, &mut SyncState::default(), 5_000).unwrap();
+        assert_eq!(outcome.updates.len(), 2);
+
+        assert!(app.apply_sync_updates(outcome.updates).is_empty());
+        let episodes = &app.podcasts[0].episodes;
+        assert!(episodes[0].played);
+        assert_eq!(episodes[0].position_secs, 0);
+        assert_eq!(episodes[1].position_secs, 300);
+        assert_eq!(episodes[1].state_changed, Some(2_000));
+    }
+}
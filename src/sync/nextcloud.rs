@@ -0,0 +1,139 @@
+use super::{EpisodeAction, HttpClient, SubscriptionChanges, SyncBackend};
+use crate::config::SyncConfig;
+use serde::Deserialize;
+use serde_json::json;
+
+/// Nextcloud with the gpoddersync app. Subscriptions are per account rather than per device.
+pub struct NextcloudBackend {
+    http: HttpClient,
+    /// Base URL of the gpoddersync app
+    base: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct UploadResponse {
+    timestamp: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct EpisodeActionsResponse {
+    #[serde(default)]
+    actions: Vec<EpisodeAction>,
+    timestamp: i64,
+}
+
+impl NextcloudBackend {
+    pub fn new(config: &SyncConfig) -> Result<Self, String> {
+        Ok(Self {
+            http: HttpClient::new(config)?,
+            base: format!("{}/index.php/apps/gpoddersync", config.server.trim_end_matches('/')),
+        })
+    }
+}
+
+impl SyncBackend for NextcloudBackend {
+    fn pull_subscriptions(&self, since: i64) -> Result<SubscriptionChanges, String> {
+        self.http.get(&format!("{}/subscriptions?since={}", self.base, since))
+    }
+
+    fn push_subscriptions(&self, add: &[String], remove: &[String]) -> Result<i64, String> {
+        let body = json!({ "add": add, "remove": remove });
+        let response: UploadResponse =
+            self.http.post(&format!("{}/subscription_change/create", self.base), &body)?;
+        Ok(response.timestamp)
+    }
+
+    fn pull_episode_actions(&self, since: i64) -> Result<(Vec<EpisodeAction>, i64), String> {
+        let response: EpisodeActionsResponse =
+            self.http.get(&format!("{}/episode_action?since={}", self.base, since))?;
+        Ok((response.actions, response.timestamp))
+    }
+
+    fn push_episode_actions(&self, actions: &[EpisodeAction]) -> Result<i64, String> {
+        let response: UploadResponse =
+            self.http.post(&format!("{}/episode_action/create", self.base), actions)?;
+        Ok(response.timestamp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{Episode, Podcast};
+    use crate::sync::{self, stub, SyncState};
+
+    #[test]
+    fn test_nextcloud_sync_against_stub_server() {
+        let server = stub::serve(vec![
+            (
+                "GET",
+                "/index.php/apps/gpoddersync/subscriptions",
+                r#"{"add": ["https://example.com/remote"], "remove": [], "timestamp": 20}"#.to_string(),
+            ),
+            (
+                "POST",
+                "/index.php/apps/gpoddersync/subscription_change/create",
+                r#"{"timestamp": 21}"#.to_string(),
+            ),
+            (
+                "GET",
+                "/index.php/apps/gpoddersync/episode_action",
+                r#"{"actions": [{"podcast": "https://example.com/local", "episode": "https://example.com/1.mp3",
+                    "action": "PLAY", "timestamp": "2024-01-01T12:00:00",
+                    "started": 0, "position": 90, "total": 600}], "timestamp": 22}"#
+                    .to_string(),
+            ),
+            (
+                "POST",
+                "/index.php/apps/gpoddersync/episode_action/create",
+                r#"{"timestamp": 23}"#.to_string(),
+            ),
+        ]);
+
+        let config = SyncConfig {
+            backend: "nextcloud".to_string(),
+            server: server.url.clone(),
+            username: "alice".to_string(),
+            password: "app-password".to_string(),
+            device_id: "laptop".to_string(),
+        };
+        let backend = sync::backend_for(&config).unwrap();
+
+        let episode = |url: &str, played: bool| Episode {
+            audio_url: url.to_string(),
+            played,
+            state_changed: Some(1_700_000_000),
+            ..Default::default()
+        };
+        let podcasts = vec![Podcast {
+            title: "Local".to_string(),
+            description: String::new(),
+            url: "https://example.com/local".to_string(),
+            episodes: vec![episode("https://example.com/1.mp3", false), episode("https://example.com/2.mp3", true)],
+            filter: Default::default(),
+            categories: Vec::new(),
+            image_url: None,
+        }];
+
+        let mut state = SyncState::default();
+        let outcome = sync::run(&*backend, &config.device_id, &podcasts, &mut state, 1_800_000_000).unwrap();
+
+        assert_eq!(outcome.subscribe, vec!["https://example.com/remote".to_string()]);
+        assert_eq!(outcome.updates.len(), 1);
+        assert_eq!(outcome.updates[0].position_secs, 90);
+        assert_eq!(outcome.pushed_actions, 1);
+        assert_eq!((state.subscriptions_since, state.actions_since), (21, 23));
+
+        let requests = server.requests.lock().unwrap();
+        let paths: Vec<&str> = requests.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(paths, vec![
+            "/index.php/apps/gpoddersync/subscriptions?since=0",
+            "/index.php/apps/gpoddersync/subscription_change/create",
+            "/index.php/apps/gpoddersync/episode_action?since=0",
+            "/index.php/apps/gpoddersync/episode_action/create",
+        ]);
+        assert!(requests.iter().all(|r| r.authorized));
+        assert!(requests[1].body.contains("https://example.com/local"));
+        assert!(requests[3].body.contains("https://example.com/2.mp3"));
+    }
+}
@@ -258,8 +258,8 @@ impl Theme {
         let s = color_str.trim().to_lowercase();
 
         // Check for hex color (#ff0000 or #f00)
-        if s.starts_with('#') {
-            return Self::parse_hex(&s[1..]).unwrap_or(Color::White);
+        if let Some(hex) = s.strip_prefix('#') {
+            return Self::parse_hex(hex).unwrap_or(Color::White);
         } else if s.len() == 6 || s.len() == 3 {
             // Also try parsing without # prefix
             if let Some(color) = Self::parse_hex(&s) {
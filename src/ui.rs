@@ -1,14 +1,22 @@
-use crate::app::{App, AppScreen, InputMode, PaneFocus};
-use crate::playback::Player;
+use crate::app::{App, AppScreen, BookmarksPopup, ChaptersPopup, InputMode, PaneFocus};
+use crate::ab_loop;
+use crate::artwork::Placement;
+use crate::browse::{BrowseListing, LoadState, Preview};
+use crate::continue_listening;
+use crate::now_playing;
+use crate::stats;
+use crate::playback::Playback;
 use crate::theme::Theme;
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Modifier, Style},
-    widgets::{Block, Borders, Clear, Gauge, List, ListItem, Paragraph, Wrap},
+    text::{Line, Span},
+    widgets::{BarChart, Block, Borders, Clear, Gauge, List, ListItem, Paragraph, Sparkline, Wrap},
     Frame,
 };
 
-pub fn draw_ui(frame: &mut Frame, app: &App, player: &Player, theme: &Theme) {
+/// Draw the whole UI. Returns the artwork to draw over the frame, if any.
+pub fn draw_ui(frame: &mut Frame, app: &App, player: &dyn Playback, theme: &Theme) -> Option<Placement> {
     let main_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -17,12 +25,29 @@ pub fn draw_ui(frame: &mut Frame, app: &App, player: &Player, theme: &Theme) {
         ])
         .split(frame.area());
 
+    let mut artwork = match app.screen {
+        AppScreen::Podcasts => draw_podcasts_screen(frame, app, player, main_layout[0], theme),
+        AppScreen::Browse => draw_browse_screen(frame, app, main_layout[0], theme),
+        AppScreen::NowPlaying => draw_now_playing_screen(frame, app, player, main_layout[0], theme),
+        _ => None,
+    };
+
     match app.screen {
-        AppScreen::Podcasts => {
-            draw_podcasts_screen(frame, app, main_layout[0], theme);
+        AppScreen::Podcasts | AppScreen::Browse | AppScreen::NowPlaying => {}
+        AppScreen::Inbox => {
+            draw_inbox_screen(frame, app, main_layout[0], theme);
+        }
+        AppScreen::ContinueListening => {
+            draw_continue_listening_screen(frame, app, main_layout[0], theme);
+        }
+        AppScreen::History => {
+            draw_history_screen(frame, app, main_layout[0], theme);
         }
-        AppScreen::Browse => {
-            draw_browse_screen(frame, app, main_layout[0], theme);
+        AppScreen::Bookmarks => {
+            draw_bookmarks_screen(frame, app, main_layout[0], theme);
+        }
+        AppScreen::Stats => {
+            draw_stats_screen(frame, app, main_layout[0], theme);
         }
     }
 
@@ -31,27 +56,283 @@ pub fn draw_ui(frame: &mut Frame, app: &App, player: &Player, theme: &Theme) {
     // Draw help popup on top if visible
     if app.show_help {
         draw_help_popup(frame, theme);
+        artwork = None;
     }
 
     // Draw info popup on top if visible
     if app.show_info {
-        draw_info_popup(frame, app, theme);
+        artwork = draw_info_popup(frame, app, theme);
+    }
+
+    // Draw bookmarks popup on top if visible
+    if let Some(popup) = &app.bookmarks_popup {
+        draw_bookmarks_popup(frame, app, popup, theme);
+        artwork = None;
     }
 
     // Draw chapters popup on top if visible
-    if app.show_chapters {
-        draw_chapters_popup(frame, app, theme);
+    if let Some(popup) = &app.chapters_popup {
+        draw_chapters_popup(frame, popup, player, theme);
+        artwork = None;
     }
+
+    artwork
 }
 
-fn draw_podcasts_screen(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
+/// Leave `area` blank for artwork that is drawn after the frame
+fn reserve_artwork(frame: &mut Frame, app: &App, url: Option<&str>, area: Rect) -> Option<Placement> {
+    let url = url.filter(|u| !u.is_empty())?;
+    if !app.artwork.is_enabled() || area.width < 4 || area.height < 2 {
+        return None;
+    }
+    frame.render_widget(Clear, area);
+    Some(Placement { url: url.to_string(), area })
+}
+
+fn draw_podcasts_screen(frame: &mut Frame, app: &App, player: &dyn Playback, area: Rect, theme: &Theme) -> Option<Placement> {
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
         .split(area);
 
-    draw_podcast_list(frame, app, chunks[0], theme);
     draw_episode_list(frame, app, chunks[1], theme);
+
+    // Now-playing panel under the podcast list while something is playing
+    if app.playback.start.is_none() || chunks[0].height < 20 {
+        draw_podcast_list(frame, app, chunks[0], theme);
+        return None;
+    }
+    let left = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(5), Constraint::Length(10)])
+        .split(chunks[0]);
+
+    draw_podcast_list(frame, app, left[0], theme);
+    draw_now_playing_panel(frame, app, player, left[1], theme)
+}
+
+fn draw_now_playing_panel(frame: &mut Frame, app: &App, player: &dyn Playback, area: Rect, theme: &Theme) -> Option<Placement> {
+    let block = Block::default()
+        .title("Now Playing")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border_unfocused_color()));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let position = player.get_position().as_secs_f64();
+    let url = app.playing_artwork_url(position);
+
+    // Square artwork on the left, two cells wide per row
+    let art_width = if url.is_some() && app.artwork.is_enabled() { inner.height * 2 + 1 } else { 0 };
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(art_width), Constraint::Min(1)])
+        .split(inner);
+
+    let mut lines = Vec::new();
+    if let Some(episode) = app.playing_episode() {
+        lines.push(Line::from(Span::styled(
+            episode.title.clone(),
+            Style::default().fg(theme.episode_title_color()).add_modifier(Modifier::BOLD),
+        )));
+    }
+    if let Some(podcast) = app.playing_podcast() {
+        lines.push(Line::raw(podcast.title.clone()));
+    }
+    if let Some((_, chapter)) = app.playback.chapters.as_ref().and_then(|c| c.toc_chapter_at(position)) {
+        lines.push(Line::raw(""));
+        lines.push(Line::from(Span::styled(
+            format!("Chapter: {}", chapter.title),
+            Style::default().fg(theme.episode_published_color()),
+        )));
+    }
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: true })
+            .style(Style::default().fg(theme.text_normal_color())),
+        columns[1],
+    );
+
+    let art_area = Rect { width: columns[0].width.saturating_sub(1), ..columns[0] };
+    reserve_artwork(frame, app, url, art_area)
+}
+
+fn draw_now_playing_screen(frame: &mut Frame, app: &App, player: &dyn Playback, area: Rect, theme: &Theme) -> Option<Placement> {
+    let block = Block::default()
+        .title("Now Playing")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border_focused_color()));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    if app.playback.start.is_none() {
+        frame.render_widget(
+            Paragraph::new("Nothing playing. Press Space on an episode to start listening.")
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(theme.text_played_color())),
+            Rect { y: inner.y + inner.height / 2, height: 1.min(inner.height), ..inner },
+        );
+        return None;
+    }
+
+    let position = player.get_position();
+    let position_secs = position.as_secs_f64();
+    let episode = app.playing_episode();
+    let chapters = app.playback.chapters.as_ref();
+    let current_chapter = chapters.and_then(|c| c.toc_chapter_at(position_secs));
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage(40), // Artwork and details
+            Constraint::Length(5),      // Progress bar, chapter ticks and time
+            Constraint::Min(3),         // Show notes
+        ])
+        .split(inner);
+
+    // Square artwork on the left, two cells wide per row
+    let url = app.playing_artwork_url(position_secs);
+    let art_width = if url.is_some() && app.artwork.is_enabled() { rows[0].height * 2 + 1 } else { 0 };
+    let top = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(art_width), Constraint::Min(1)])
+        .split(rows[0]);
+
+    let label = Style::default().fg(theme.text_played_color());
+    let mut lines = Vec::new();
+    if let Some(episode) = episode {
+        lines.push(Line::from(Span::styled(
+            episode.title.clone(),
+            Style::default().fg(theme.episode_title_color()).add_modifier(Modifier::BOLD),
+        )));
+    }
+    if let Some(podcast) = app.playing_podcast() {
+        lines.push(Line::raw(podcast.title.clone()));
+    }
+    if let Some(published) = episode.map(|e| &e.published).filter(|p| !p.is_empty()) {
+        lines.push(Line::from(Span::styled(published.clone(), Style::default().fg(theme.episode_published_color()))));
+    }
+    lines.push(Line::raw(""));
+
+    if let Some((index, chapter)) = current_chapter {
+        lines.push(Line::from(vec![
+            Span::styled("Chapter:  ", label),
+            Span::raw(format!("{}/{} {}", index + 1, chapters.map(|c| c.toc().len()).unwrap_or(0), chapter.title)),
+        ]));
+        if let Some(location) = &chapter.location {
+            lines.push(Line::from(vec![Span::styled("Location: ", label), Span::raw(location.name.clone())]));
+        }
+    }
+
+    let state = if player.is_paused() { "Paused" } else { "Playing" };
+    lines.push(Line::from(vec![Span::styled("State:    ", label), Span::raw(state)]));
+    lines.push(Line::from(vec![
+        Span::styled("Speed:    ", label),
+        Span::raw(format!("{:.2}x", player.get_speed())),
+    ]));
+    lines.push(Line::from(vec![
+        Span::styled("Volume:   ", label),
+        Span::raw(format!("{:.0}%", player.get_volume() * 100.0)),
+    ]));
+    let sleep = match app.now_playing.sleep_remaining(std::time::Instant::now()) {
+        Some(remaining) => format!("{} left", now_playing::format_time(remaining.as_secs())),
+        None => "Off".to_string(),
+    };
+    lines.push(Line::from(vec![Span::styled("Sleep:    ", label), Span::raw(sleep)]));
+    lines.push(Line::from(vec![
+        Span::styled("Buffer:   ", label),
+        Span::raw(now_playing::describe_buffer(&player.buffer_status(), player.is_buffering())),
+    ]));
+    if let Some((_, start)) = app.clip_start.as_ref().filter(|(url, _)| app.playback.url.as_ref() == Some(url)) {
+        lines.push(Line::from(vec![
+            Span::styled("Clip:     ", label),
+            Span::raw(format!("from {} (press }} to save)", now_playing::format_time(*start as u64))),
+        ]));
+    }
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: true })
+            .style(Style::default().fg(theme.text_normal_color())),
+        top[1],
+    );
+
+    draw_now_playing_progress(frame, app, position.as_secs(), rows[1], theme);
+
+    // Show notes, scrolled with j/k
+    let notes = episode
+        .map(|e| e.description.as_str())
+        .filter(|d| !d.is_empty())
+        .unwrap_or("No show notes for this episode.");
+    frame.render_widget(
+        Paragraph::new(notes)
+            .block(
+                Block::default()
+                    .title("Show Notes")
+                    .borders(Borders::TOP)
+                    .border_style(Style::default().fg(theme.border_unfocused_color())),
+            )
+            .wrap(Wrap { trim: true })
+            .scroll((app.now_playing.notes_scroll, 0))
+            .style(Style::default().fg(theme.text_normal_color())),
+        rows[2],
+    );
+
+    let art_area = Rect { width: top[0].width.saturating_sub(1), ..top[0] };
+    reserve_artwork(frame, app, url, art_area)
+}
+
+/// Large progress bar with a tick under each chapter start and the elapsed/total time
+fn draw_now_playing_progress(frame: &mut Frame, app: &App, position_secs: u64, area: Rect, theme: &Theme) {
+    let duration_secs = app.playback.duration_secs;
+    let parts = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // Spacing
+            Constraint::Length(2), // Progress bar
+            Constraint::Length(1), // Chapter ticks
+            Constraint::Length(1), // Time
+        ])
+        .split(area);
+
+    let ratio = if duration_secs > 0 {
+        (position_secs as f64 / duration_secs as f64).min(1.0)
+    } else {
+        0.0
+    };
+    frame.render_widget(
+        Gauge::default()
+            .gauge_style(Style::default().fg(theme.border_focused_color()))
+            .label("")
+            .ratio(ratio),
+        parts[1],
+    );
+
+    if let Some(chapters) = &app.playback.chapters {
+        let starts: Vec<f64> = chapters.toc().iter().map(|c| c.start_time).collect();
+        frame.render_widget(
+            Paragraph::new(now_playing::chapter_ticks(&starts, duration_secs, parts[2].width))
+                .style(Style::default().fg(theme.episode_published_color())),
+            parts[2],
+        );
+    }
+
+    let total = if duration_secs > 0 { now_playing::format_time(duration_secs) } else { "--:--".to_string() };
+    let remaining = if duration_secs > 0 {
+        format!("-{}", now_playing::format_time(duration_secs.saturating_sub(position_secs)))
+    } else {
+        String::new()
+    };
+    let time_row = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(parts[3]);
+    frame.render_widget(
+        Paragraph::new(format!("{} / {}", now_playing::format_time(position_secs), total)),
+        time_row[0],
+    );
+    frame.render_widget(Paragraph::new(remaining).alignment(Alignment::Right), time_row[1]);
 }
 
 fn draw_podcast_list(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
@@ -108,13 +389,16 @@ fn draw_podcast_list(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
 }
 
 fn draw_episode_list(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
+    let visible = app.visible_episode_indices();
+
     let items: Vec<ListItem> = if let Some(podcast) = app.selected_podcast() {
-        podcast
-            .episodes
+        visible
             .iter()
+            .filter_map(|&idx| podcast.episodes.get(idx))
             .enumerate()
             .map(|(i, episode)| {
                 let marker = if episode.played { "○" } else { "●" };
+                let star = if episode.starred { "★ " } else { "" };
                 let duration_str = if let Some(dur) = episode.duration {
                     let mins = dur.as_secs() / 60;
                     let secs = dur.as_secs() % 60;
@@ -129,7 +413,7 @@ fn draw_episode_list(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
                     String::new()
                 };
 
-                let label = format!("{} {}{}{}", marker, date_str, episode.title, duration_str);
+                let label = format!("{} {}{}{}{}", marker, star, date_str, episode.title, duration_str);
 
                 let style = if i == app.selected_episode_index {
                     Style::default()
@@ -155,17 +439,145 @@ fn draw_episode_list(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
     };
 
     let title = if let Some(podcast) = app.selected_podcast() {
-        format!("Episodes - {}", podcast.title)
+        if podcast.filter.is_active() {
+            format!("Episodes - {} ({}/{})", podcast.title, visible.len(), podcast.episodes.len())
+        } else {
+            format!("Episodes - {} ({})", podcast.title, podcast.episodes.len())
+        }
     } else {
         "Episodes".to_string()
     };
 
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(border_style);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // Filter bar
+            Constraint::Min(1),    // Episode list
+        ])
+        .split(inner);
+
+    draw_filter_bar(frame, app, chunks[0], theme);
+
+    let list = List::new(items)
+        .highlight_symbol("➤ ")
+        .highlight_style(
+            Style::default()
+                .bg(theme.selection_bg_color())
+                .fg(theme.selection_fg_color())
+                .add_modifier(Modifier::BOLD),
+        );
+
+    frame.render_stateful_widget(
+        list,
+        chunks[1],
+        &mut ratatui::widgets::ListState::default().with_selected(Some(app.selected_episode_index)),
+    );
+}
+
+fn draw_filter_bar(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
+    let filter = app.selected_podcast().map(|p| p.filter.clone()).unwrap_or_default();
+
+    let toggle = |label: &'static str, on: bool| {
+        let style = if on {
+            Style::default()
+                .fg(theme.border_focused_color())
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.text_played_color())
+        };
+        Span::styled(label, style)
+    };
+
+    let editing = app.input_mode == InputMode::FilteringEpisodes;
+    let search_text = if editing {
+        format!("/{}█", filter.text)
+    } else if filter.text.is_empty() {
+        "/search".to_string()
+    } else {
+        format!("/{}", filter.text)
+    };
+    let search_style = if editing || !filter.text.is_empty() {
+        Style::default().fg(theme.status_bar_fg_color())
+    } else {
+        Style::default().fg(theme.text_played_color())
+    };
+
+    let line = Line::from(vec![
+        toggle("[u]nplayed", filter.unplayed),
+        Span::raw(" "),
+        toggle("[p]rogress", filter.in_progress),
+        Span::raw(" "),
+        toggle("[o]ffline", filter.downloaded),
+        Span::raw(" "),
+        toggle("[*]starred", filter.starred),
+        Span::raw("  "),
+        Span::styled(search_text, search_style),
+    ]);
+
+    frame.render_widget(Paragraph::new(line), area);
+}
+
+fn draw_inbox_screen(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
+    let items: Vec<ListItem> = app
+        .inbox
+        .entries
+        .iter()
+        .enumerate()
+        .filter_map(|(i, entry)| {
+            let podcast = app.podcasts.get(entry.podcast_index)?;
+            let episode = podcast.episodes.get(entry.episode_index)?;
+
+            let new_marker = if app.inbox.new_episodes.contains(&episode.audio_url) {
+                "NEW "
+            } else {
+                ""
+            };
+            let queued_marker = if app.is_queued(entry.podcast_index, entry.episode_index) {
+                " [queued]"
+            } else {
+                ""
+            };
+            let date_str = entry
+                .published
+                .map(|d| d.format("%Y-%m-%d").to_string())
+                .unwrap_or_else(|| "----------".to_string());
+
+            let label = format!(
+                "● {}{} - {} - {}{}",
+                new_marker, date_str, podcast.title, episode.title, queued_marker
+            );
+
+            let style = if i == app.inbox.selected_index {
+                Style::default()
+                    .fg(theme.selection_fg_color())
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.text_unplayed_color())
+            };
+
+            Some(ListItem::new(label).style(style))
+        })
+        .collect();
+
+    let title = format!(
+        "Inbox - {} unplayed, {} queued",
+        app.inbox.entries.len(),
+        app.queue.len()
+    );
+
     let list = List::new(items)
         .block(
             Block::default()
                 .title(title)
                 .borders(Borders::ALL)
-                .border_style(border_style),
+                .border_style(Style::default().fg(theme.border_focused_color())),
         )
         .highlight_symbol("➤ ")
         .highlight_style(
@@ -178,11 +590,320 @@ fn draw_episode_list(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
     frame.render_stateful_widget(
         list,
         area,
-        &mut ratatui::widgets::ListState::default().with_selected(Some(app.selected_episode_index)),
+        &mut ratatui::widgets::ListState::default().with_selected(Some(app.inbox.selected_index)),
+    );
+}
+
+fn draw_continue_listening_screen(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
+    const BAR_WIDTH: usize = 20;
+    let now = chrono::Utc::now().timestamp();
+
+    let items: Vec<ListItem> = app
+        .continue_listening
+        .entries
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &(p, e))| {
+            let podcast = app.podcasts.get(p)?;
+            let episode = podcast.episodes.get(e)?;
+
+            let pos = episode.position_secs;
+            let (bar, total_str) = match continue_listening::progress_ratio(episode) {
+                Some(ratio) => {
+                    let filled = (ratio * BAR_WIDTH as f64).round() as usize;
+                    let total = episode.duration.map(|d| d.as_secs()).unwrap_or(0);
+                    (
+                        format!("{}{} {:>3}%", "█".repeat(filled), "░".repeat(BAR_WIDTH - filled), (ratio * 100.0) as u64),
+                        format!("{:02}:{:02}", total / 60, total % 60),
+                    )
+                }
+                None => ("░".repeat(BAR_WIDTH) + "   ?%", "--:--".to_string()),
+            };
+            let last = episode
+                .last_played
+                .map(|t| continue_listening::format_ago(t, now))
+                .unwrap_or_else(|| "unknown".to_string());
+
+            let title = format!("▸ {} - {}", podcast.title, episode.title);
+            let progress = format!(
+                "  {} {:02}:{:02} / {}  · last listened {}",
+                bar, pos / 60, pos % 60, total_str, last
+            );
+
+            let style = if i == app.continue_listening.selected_index {
+                Style::default()
+                    .fg(theme.selection_fg_color())
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.text_unplayed_color())
+            };
+
+            Some(ListItem::new(format!("{}\n{}", title, progress)).style(style))
+        })
+        .collect();
+
+    let title = format!("Continue Listening ({})", app.continue_listening.entries.len());
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.border_focused_color())),
+        )
+        .highlight_symbol("➤ ")
+        .highlight_style(
+            Style::default()
+                .bg(theme.selection_bg_color())
+                .fg(theme.selection_fg_color())
+                .add_modifier(Modifier::BOLD),
+        );
+
+    frame.render_stateful_widget(
+        list,
+        area,
+        &mut ratatui::widgets::ListState::default().with_selected(Some(app.continue_listening.selected_index)),
+    );
+}
+
+fn draw_history_screen(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
+    let now = chrono::Utc::now().timestamp();
+
+    let items: Vec<ListItem> = app
+        .history
+        .entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let subscribed = app.find_episode(&entry.episode).is_some();
+            let removed = if subscribed { "" } else { " (removed)" };
+            let pos = entry.position_secs;
+
+            let label = format!(
+                "{:>8}  {:<6} {:02}:{:02}  {} - {}{}",
+                continue_listening::format_ago(entry.last_timestamp, now),
+                entry.last_kind.label(),
+                pos / 60,
+                pos % 60,
+                entry.podcast_title,
+                entry.episode_title,
+                removed
+            );
+
+            let style = if i == app.history.selected_index {
+                Style::default()
+                    .fg(theme.selection_fg_color())
+                    .add_modifier(Modifier::BOLD)
+            } else if !subscribed {
+                Style::default().fg(theme.text_played_color())
+            } else {
+                Style::default().fg(theme.text_normal_color())
+            };
+
+            ListItem::new(label).style(style)
+        })
+        .collect();
+
+    let title = format!("History ({} episodes)", app.history.entries.len());
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.border_focused_color())),
+        )
+        .highlight_symbol("➤ ")
+        .highlight_style(
+            Style::default()
+                .bg(theme.selection_bg_color())
+                .fg(theme.selection_fg_color())
+                .add_modifier(Modifier::BOLD),
+        );
+
+    frame.render_stateful_widget(
+        list,
+        area,
+        &mut ratatui::widgets::ListState::default().with_selected(Some(app.history.selected_index)),
     );
 }
 
-fn draw_browse_screen(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
+fn draw_bookmarks_screen(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
+    let now = chrono::Utc::now().timestamp();
+
+    let items: Vec<ListItem> = app
+        .bookmarks
+        .bookmarks
+        .iter()
+        .enumerate()
+        .map(|(i, bookmark)| {
+            let subscribed = app.find_episode(&bookmark.episode()).is_some();
+            let removed = if subscribed { "" } else { " (removed)" };
+
+            let label = format!(
+                "{:>8}  {:>8}  {} - {}{}{}",
+                continue_listening::format_ago(bookmark.created, now),
+                now_playing::format_time(bookmark.position_secs),
+                bookmark.podcast_title,
+                bookmark.episode_title,
+                removed,
+                if bookmark.note.is_empty() { String::new() } else { format!("  \"{}\"", bookmark.note) },
+            );
+
+            let style = if i == app.bookmarks.selected_index {
+                Style::default()
+                    .fg(theme.selection_fg_color())
+                    .add_modifier(Modifier::BOLD)
+            } else if !subscribed {
+                Style::default().fg(theme.text_played_color())
+            } else {
+                Style::default().fg(theme.text_normal_color())
+            };
+
+            ListItem::new(label).style(style)
+        })
+        .collect();
+
+    let title = format!(
+        "Bookmarks ({}) - Enter to jump, d to delete, E to export",
+        app.bookmarks.bookmarks.len()
+    );
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.border_focused_color())),
+        )
+        .highlight_symbol("➤ ")
+        .highlight_style(
+            Style::default()
+                .bg(theme.selection_bg_color())
+                .fg(theme.selection_fg_color())
+                .add_modifier(Modifier::BOLD),
+        );
+
+    frame.render_stateful_widget(
+        list,
+        area,
+        &mut ratatui::widgets::ListState::default().with_selected(Some(app.bookmarks.selected_index)),
+    );
+}
+
+fn draw_stats_screen(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
+    let s = &app.stats;
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(4),  // Summary
+            Constraint::Length(10), // Last 14 days
+            Constraint::Length(5),  // Last 90 days
+            Constraint::Min(3),     // Per-podcast breakdown
+        ])
+        .split(area);
+
+    let days = |n: u32| if n == 1 { "day" } else { "days" };
+    let summary = format!(
+        "Listened: {}   Audio covered: {}   Saved by speed-up: {}\nFinished: {} episodes   Current streak: {} {}   Longest streak: {} {}",
+        stats::format_hours(s.total_listened_secs),
+        stats::format_hours(s.content_secs),
+        stats::format_hours(s.time_saved_secs),
+        s.episodes_finished,
+        s.current_streak_days,
+        days(s.current_streak_days),
+        s.longest_streak_days,
+        days(s.longest_streak_days),
+    );
+    frame.render_widget(
+        Paragraph::new(summary)
+            .style(Style::default().fg(theme.text_normal_color()))
+            .block(
+                Block::default()
+                    .title("Listening Stats (export with `shellcast export-stats`)")
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.border_focused_color())),
+            ),
+        chunks[0],
+    );
+
+    // Minutes per day, most recent 14 days
+    let recent: Vec<(String, u64)> = s
+        .per_day
+        .iter()
+        .rev()
+        .take(14)
+        .rev()
+        .map(|d| (d.date[8..].to_string(), d.listened_secs / 60))
+        .collect();
+    let bars: Vec<(&str, u64)> = recent.iter().map(|(label, mins)| (label.as_str(), *mins)).collect();
+    frame.render_widget(
+        BarChart::default()
+            .block(
+                Block::default()
+                    .title("Minutes per day (last 14 days)")
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.border_unfocused_color())),
+            )
+            .data(bars.as_slice())
+            .bar_width(4)
+            .bar_gap(1)
+            .bar_style(Style::default().fg(theme.border_focused_color()))
+            .value_style(
+                Style::default()
+                    .bg(theme.border_focused_color())
+                    .fg(theme.selection_fg_color()),
+            ),
+        chunks[1],
+    );
+
+    let trend: Vec<u64> = s
+        .per_day
+        .iter()
+        .rev()
+        .take(90)
+        .rev()
+        .map(|d| d.listened_secs / 60)
+        .collect();
+    frame.render_widget(
+        Sparkline::default()
+            .block(
+                Block::default()
+                    .title("Last 90 days")
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.border_unfocused_color())),
+            )
+            .data(&trend)
+            .style(Style::default().fg(theme.border_focused_color())),
+        chunks[2],
+    );
+
+    let items: Vec<ListItem> = s
+        .per_podcast
+        .iter()
+        .map(|p| {
+            ListItem::new(format!(
+                "{:>8}  {:>4} finished  {}",
+                stats::format_hours(p.listened_secs),
+                p.episodes_finished,
+                p.podcast_title
+            ))
+            .style(Style::default().fg(theme.text_normal_color()))
+        })
+        .collect();
+    frame.render_widget(
+        List::new(items).block(
+            Block::default()
+                .title("By podcast")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.border_unfocused_color())),
+        ),
+        chunks[3],
+    );
+}
+
+fn draw_browse_screen(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) -> Option<Placement> {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -193,20 +914,37 @@ fn draw_browse_screen(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
 
     // Search box
     let search_block = Block::default()
-        .title("Search Podcasts (gpodder.net)")
+        .title(format!("Search Podcasts ({})", app.directory.provider_names().join(" + ")))
         .borders(Borders::ALL)
         .border_style(Style::default().fg(theme.border_focused_color()));
 
     let search_text = if app.browse.is_searching {
         format!("Search: {}█", app.browse.search_query)
     } else {
-        format!("Search: {} (Press '/' to search)", app.browse.search_query)
+        format!("Search: {} (Press '/' to search, 't' for suggestions)", app.browse.search_query)
     };
 
     let search_para = Paragraph::new(search_text)
         .block(search_block);
     frame.render_widget(search_para, chunks[0]);
 
+    let panes = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(25), Constraint::Percentage(75)])
+        .split(chunks[1]);
+
+    draw_browse_categories(frame, app, panes[0], theme);
+
+    if let Some(preview) = &app.browse.preview {
+        return draw_browse_preview(frame, app, preview, panes[1], theme);
+    }
+
+    let results_border = if app.browse.focus == PaneFocus::Right {
+        theme.border_focused_color()
+    } else {
+        theme.border_unfocused_color()
+    };
+
     // Results list
     let items: Vec<ListItem> = app.browse.search_results
         .iter()
@@ -216,25 +954,83 @@ fn draw_browse_screen(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
             } else {
                 String::new()
             };
-            let title = format!("▸ {}{}", result.title, subs_text);
+            let subscribed = app.is_subscribed(&result.feed_url);
+            let marker = if subscribed { "✓" } else { "▸" };
+            let title = format!("{} {}{}", marker, result.title, subs_text);
             let author = format!("  by {}", result.author);
             let content = format!("{}\n{}", title, author);
-            ListItem::new(content)
+            let style = if subscribed {
+                Style::default().fg(theme.text_played_color())
+            } else {
+                Style::default()
+            };
+            ListItem::new(content).style(style)
         })
         .collect();
 
-    let title = if app.browse.showing_defaults {
-        format!("Featured Podcasts ({})", app.browse.search_results.len())
+    let paging = if app.browse.has_more && app.browse.page > 0 {
+        " (n: next, N: previous)"
+    } else if app.browse.has_more {
+        " (n: next)"
+    } else if app.browse.page > 0 {
+        " (N: previous)"
     } else {
-        format!("Results ({})", app.browse.search_results.len())
+        ""
+    };
+
+    let title = match &app.browse.listing {
+        BrowseListing::Featured => match &app.browse.toplist {
+            LoadState::NotLoaded | LoadState::Loading => "Featured Podcasts (loading...)".to_string(),
+            LoadState::Failed(_) => "Featured Podcasts (failed)".to_string(),
+            LoadState::Loaded => format!("Featured Podcasts ({})", app.browse.search_results.len()),
+        },
+        _ if app.browse.search_pending => "Results (loading...)".to_string(),
+        BrowseListing::Suggested => format!("Suggested — page {}{}", app.browse.page + 1, paging),
+        BrowseListing::Search(query) => {
+            format!("Search: {} — page {}{}", query, app.browse.page + 1, paging)
+        }
+        BrowseListing::Category(category) => {
+            format!("Category: {} — page {}{}", category.title, app.browse.page + 1, paging)
+        }
     };
 
+    // Placeholder while the featured list is loading, after it failed,
+    // or when there is nothing to suggest yet
+    let placeholder = match &app.browse.listing {
+        BrowseListing::Featured if items.is_empty() => Some(match &app.browse.toplist {
+            LoadState::Failed(e) => format!(
+                "Could not load featured podcasts:\n{}\n\nPress 'r' to retry or '/' to search",
+                e
+            ),
+            LoadState::Loaded => "No featured podcasts. Press '/' to search".to_string(),
+            _ => "Loading featured podcasts...".to_string(),
+        }),
+        BrowseListing::Suggested if items.is_empty() && !app.browse.search_pending => Some(
+            "No suggestions yet. Subscribe to a few podcasts (and refresh them so their \
+             categories are known) or configure a gpodder.net account."
+                .to_string(),
+        ),
+        _ => None,
+    };
+    if let Some(message) = placeholder {
+        let placeholder = Paragraph::new(message)
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::default()
+                    .title(title)
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(results_border)),
+            );
+        frame.render_widget(placeholder, panes[1]);
+        return None;
+    }
+
     let results_list = List::new(items)
         .block(
             Block::default()
                 .title(title)
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(theme.border_focused_color())),
+                .border_style(Style::default().fg(results_border)),
         )
         .highlight_symbol("➤ ")
         .highlight_style(
@@ -244,23 +1040,280 @@ fn draw_browse_screen(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
                 .add_modifier(Modifier::BOLD),
         );
 
+    let result_panes = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(panes[1]);
+
     frame.render_stateful_widget(
         results_list,
-        chunks[1],
+        result_panes[0],
         &mut ratatui::widgets::ListState::default().with_selected(Some(app.browse.selected_index)),
     );
+
+    draw_browse_details(frame, app, result_panes[1], theme)
 }
 
-fn draw_footer(frame: &mut Frame, app: &App, player: &Player, area: Rect, theme: &Theme) {
+/// Details of the selected result, with its artwork on top
+fn draw_browse_details(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) -> Option<Placement> {
     let block = Block::default()
-        .borders(Borders::TOP)
+        .title("Details")
+        .borders(Borders::ALL)
         .border_style(Style::default().fg(theme.border_unfocused_color()));
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
+    let result = app.browse.selected_result()?;
+
+    let art_height = if result.artwork_url.is_some() && app.artwork.is_enabled() && inner.height >= 20 {
+        inner.height / 3
+    } else {
+        0
+    };
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(art_height), Constraint::Min(1)])
+        .split(inner);
+
+    let label = Style::default().fg(theme.episode_published_color());
+    let field = |name: &'static str, value: String| {
+        Line::from(vec![Span::styled(format!("{}: ", name), label), Span::raw(value)])
+    };
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            result.title.clone(),
+            Style::default().fg(theme.episode_title_color()).add_modifier(Modifier::BOLD),
+        )),
+        field("Author", result.author.clone()),
+    ];
+    if result.subscribers > 0 {
+        lines.push(field("Subscribers", result.subscribers.to_string()));
+    }
+    if let Some(website) = &result.website {
+        lines.push(field("Website", website.clone()));
+    }
+    lines.push(field("Feed", result.feed_url.clone()));
+    if app.is_subscribed(&result.feed_url) {
+        lines.push(Line::from(Span::styled(
+            "✓ Subscribed",
+            Style::default().fg(theme.border_focused_color()).add_modifier(Modifier::BOLD),
+        )));
+    }
+    lines.push(Line::raw(""));
+    lines.push(Line::raw(if result.description.is_empty() {
+        "No description available.".to_string()
+    } else {
+        result.description.clone()
+    }));
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: true })
+            .style(Style::default().fg(theme.text_normal_color())),
+        rows[1],
+    );
+
+    let art_area = Rect { height: rows[0].height.saturating_sub(1), ..rows[0] };
+    reserve_artwork(frame, app, result.artwork_url.as_deref(), art_area)
+}
+
+fn draw_browse_preview(frame: &mut Frame, app: &App, preview: &Preview, area: Rect, theme: &Theme) -> Option<Placement> {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage(40), // Podcast description
+            Constraint::Min(3),         // Recent episodes
+        ])
+        .split(area);
+
+    let title = preview
+        .podcast
+        .as_ref()
+        .map(|p| p.title.clone())
+        .unwrap_or_else(|| preview.result.title.clone());
+    let description = match (&preview.state, &preview.podcast) {
+        (_, Some(podcast)) if !podcast.description.is_empty() => podcast.description.clone(),
+        (LoadState::Failed(e), _) => format!("Could not load feed: {}", e),
+        _ if !preview.result.description.is_empty() => preview.result.description.clone(),
+        _ => "No description available.".to_string(),
+    };
+    let subscribed = if app.is_subscribed(&preview.result.feed_url) { " (subscribed)" } else { "" };
+    let mut info = format!("{}{}\nby {}", title, subscribed, preview.result.author);
+    if preview.result.subscribers > 0 {
+        info.push_str(&format!("\n{} subscribers", preview.result.subscribers));
+    }
+    if let Some(website) = &preview.result.website {
+        info.push_str(&format!("\n{}", website));
+    }
+    info.push_str(&format!("\n\n{}", description));
+
+    let info_block = Block::default()
+        .title(" Preview - Enter: subscribe, Space: play, Esc: back ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border_focused_color()));
+    let inner = info_block.inner(chunks[0]);
+    frame.render_widget(info_block, chunks[0]);
+
+    // Cover art on the left of the description
+    let art_url = preview
+        .podcast
+        .as_ref()
+        .and_then(|p| p.image_url.as_deref())
+        .or(preview.result.artwork_url.as_deref());
+    let art_width = if art_url.is_some() && app.artwork.is_enabled() { inner.height * 2 + 1 } else { 0 };
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(art_width), Constraint::Min(1)])
+        .split(inner);
+
+    frame.render_widget(
+        Paragraph::new(info)
+            .wrap(Wrap { trim: true })
+            .style(Style::default().fg(theme.text_normal_color())),
+        columns[1],
+    );
+    let art_area = Rect { width: columns[0].width.saturating_sub(1), ..columns[0] };
+    let artwork = reserve_artwork(frame, app, art_url, art_area);
+
+    let episodes_block = Block::default()
+        .title(format!("Recent Episodes ({})", preview.episodes().len()))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border_focused_color()));
+
+    if preview.state == LoadState::Loading {
+        frame.render_widget(Paragraph::new("Loading feed...").block(episodes_block), chunks[1]);
+        return artwork;
+    }
+
+    let items: Vec<ListItem> = preview
+        .episodes()
+        .iter()
+        .map(|episode| {
+            let marker = if app.playback.url.as_ref() == Some(&episode.audio_url) { "▶" } else { "●" };
+            let duration_str = episode
+                .duration
+                .map(|d| format!(" [{:02}:{:02}]", d.as_secs() / 60, d.as_secs() % 60))
+                .unwrap_or_default();
+            let date_str = if !episode.published.is_empty() {
+                format!("{} - ", episode.published)
+            } else {
+                String::new()
+            };
+            ListItem::new(format!("{} {}{}{}", marker, date_str, episode.title, duration_str))
+                .style(Style::default().fg(theme.text_unplayed_color()))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(episodes_block)
+        .highlight_symbol("➤ ")
+        .highlight_style(
+            Style::default()
+                .bg(theme.selection_bg_color())
+                .fg(theme.selection_fg_color())
+                .add_modifier(Modifier::BOLD),
+        );
+
+    frame.render_stateful_widget(
+        list,
+        chunks[1],
+        &mut ratatui::widgets::ListState::default().with_selected(Some(preview.selected_episode)),
+    );
+
+    artwork
+}
+
+fn draw_browse_categories(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
+    let focused = app.browse.focus == PaneFocus::Left;
+    let block = Block::default()
+        .title("Categories")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(if focused {
+            theme.border_focused_color()
+        } else {
+            theme.border_unfocused_color()
+        }));
+
+    if app.browse.categories.is_empty() {
+        let message = match &app.browse.categories_state {
+            LoadState::Failed(_) => "Could not load categories. Press 'r' to retry",
+            LoadState::Loaded => "No categories",
+            _ => "Loading...",
+        };
+        frame.render_widget(
+            Paragraph::new(message).wrap(Wrap { trim: true }).block(block),
+            area,
+        );
+        return;
+    }
+
+    let current = match &app.browse.listing {
+        BrowseListing::Category(category) => Some(category.title.as_str()),
+        _ => None,
+    };
+
+    let items: Vec<ListItem> = app.browse.categories
+        .iter()
+        .map(|category| {
+            let style = if current == Some(category.title.as_str()) {
+                Style::default().fg(theme.episode_title_color()).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.text_normal_color())
+            };
+            ListItem::new(category.title.clone()).style(style)
+        })
+        .collect();
+
+    let mut highlight = Style::default().add_modifier(Modifier::BOLD);
+    if focused {
+        highlight = highlight.bg(theme.selection_bg_color()).fg(theme.selection_fg_color());
+    }
+
+    frame.render_stateful_widget(
+        List::new(items).block(block).highlight_style(highlight),
+        area,
+        &mut ratatui::widgets::ListState::default().with_selected(Some(app.browse.selected_category)),
+    );
+}
+
+/// Shade the A-B loop over the progress bar, in the chapter color
+fn draw_loop_region(frame: &mut Frame, app: &App, area: Rect, theme: &Theme) {
+    let Some(ab_loop) = app.ab_loop.as_ref().filter(|l| app.playback.url.as_ref() == Some(&l.audio_url)) else {
+        return;
+    };
+    let Some((a, b)) = ab_loop::loop_columns(ab_loop, app.playback.duration_secs, area.width) else {
+        return;
+    };
+
+    let buf = frame.buffer_mut();
+    for col in a..=b {
+        let cell = &mut buf[(area.x + col, area.y)];
+        // Unplayed cells are blank, so give them a visible shade
+        if cell.symbol() == " " {
+            cell.set_symbol("░");
+        }
+        cell.set_fg(theme.episode_published_color());
+    }
+}
+
+fn draw_footer(frame: &mut Frame, app: &App, player: &dyn Playback, area: Rect, theme: &Theme) {
+    let block = Block::default()
+        .borders(Borders::TOP)
+        .border_style(Style::default().fg(theme.border_unfocused_color()))
+        .style(Style::default().bg(theme.status_bar_bg_color()));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
     // If in input mode, just show the input prompt
-    if app.input_mode == InputMode::AddingFeed {
-        let text = format!("Add Feed: {}", app.input_buffer);
+    let prompt = match app.input_mode {
+        InputMode::AddingFeed => Some("Add Feed"),
+        InputMode::MarkingOlderThan => Some("Mark played if older than (days)"),
+        InputMode::BookmarkNote => Some("Bookmark note (Enter to save, Esc to cancel)"),
+        _ => None,
+    };
+    if let Some(prompt) = prompt {
+        let text = format!("{}: {}", prompt, app.input_buffer);
         frame.render_widget(
             Paragraph::new(text).style(Style::default().fg(theme.status_bar_fg_color())),
             inner
@@ -295,6 +1348,7 @@ fn draw_footer(frame: &mut Frame, app: &App, player: &Player, area: Rect, theme:
             .gauge_style(Style::default().fg(theme.border_focused_color()))
             .ratio(ratio.min(1.0));
         frame.render_widget(gauge, footer_layout[0]);
+        draw_loop_region(frame, app, footer_layout[0], theme);
 
         // Time display or status message
         if let Some(msg) = &app.status_message {
@@ -322,7 +1376,21 @@ fn draw_footer(frame: &mut Frame, app: &App, player: &Player, area: Rect, theme:
                     elapsed_secs % 60
                 )
             };
-            frame.render_widget(Paragraph::new(time_text), footer_layout[1]);
+            // Current chapter after the time, e.g. "12:34 / 56:00 | 3/7 Interview"
+            let chapter_text = app.playback.chapters.as_ref().and_then(|list| {
+                let (index, chapter) = list.toc_chapter_at(elapsed.as_secs_f64())?;
+                Some(format!(" | {}/{} {}", index + 1, list.toc().len(), chapter.title))
+            });
+            frame.render_widget(
+                Paragraph::new(Line::from(vec![
+                    Span::raw(time_text),
+                    Span::styled(
+                        chapter_text.unwrap_or_default(),
+                        Style::default().fg(theme.episode_published_color()),
+                    ),
+                ])),
+                footer_layout[1],
+            );
         }
 
         // Keybindings
@@ -393,24 +1461,82 @@ Navigation:
 
 Screen Switching:
   1              Switch to Podcasts view
+  2              Switch to Inbox (unplayed episodes from all podcasts)
+  3              Switch to Continue Listening (in-progress episodes)
+  4              Switch to History (recently played episodes)
   5              Switch to Browse/Search view
+  6              Switch to listening Stats
+  7              Switch to Now Playing (j/k scroll the show notes)
+  8              Switch to Bookmarks
 
 Browse Mode:
   /              Start searching (when in Browse mode)
-  Enter          Subscribe to selected search result
+  Enter          Preview selected result / open selected category
+  Enter          Subscribe (in preview)
+  Space          Play selected episode (in preview)
+  Esc            Close preview
+  Tab            Switch between categories and results
+  n / PgDn       Next page of search or category results
+  N / PgUp       Previous page
+  t              Switch between Featured and Suggested
+  r              Reload featured podcasts (retry after an error)
 
 Playback:
   Space          Play/pause selected episode
   s              Stop playback
   h or ←         Seek backward 30 seconds
   l or →         Seek forward 30 seconds
+  . / ,          Next chapter / restart or previous chapter
+  [ / ]          Decrease/increase playback speed
+  - / +          Decrease/increase volume
+  z              Sleep timer (15, 30, 45, 60, 90 min, off)
+  U              Undo automatic chapter skip
+  { / }          Mark clip start / save clip up to here
+  A              Set A-B loop start, then end, then clear it
+  L              Loop speed (100%, 90%, 75%, 50% inside the loop)
+
+Inbox:
+  Space          Play selected episode
+  m              Mark episode as played
+  e              Add episode to the queue
+  M              Mark all episodes older than N days as played
+  r              Refresh all podcasts
+
+Continue Listening:
+  Space          Resume selected episode
+  m              Mark episode as played
+  e              Add episode to the queue
+
+History:
+  Space          Play selected episode (if still subscribed)
+  e              Add episode to the queue
+
+Bookmarks:
+  b              Bookmark the playing position with a note
+  B              Bookmarks of the selected or playing episode
+  Enter/Space    Jump to the selected bookmark (Bookmarks screen)
+  d              Delete the selected bookmark (Bookmarks screen)
+  E              Export all bookmarks to Markdown
+
+Filtering (episode list, remembered per podcast):
+  /              Search episode titles and descriptions
+  u              Show only unplayed episodes
+  p              Show only in-progress episodes
+  o              Show only downloaded episodes
+  *              Show only starred episodes
+  F              Clear all filters
 
 Management:
   m              Mark episode as played/unplayed
+  f              Star/unstar episode
+  e              Add episode to the queue (plays after current episode)
+  r              Refresh selected podcast
+  R              Refresh all podcasts in the background
+  S              Sync with gpodder/Nextcloud (see [sync] in config)
   a              Add new podcast feed (enter URL)
   d              Delete selected podcast
   i              Show episode info/description
-  c              Show episode chapters (if available)
+  c              Show chapters of the selected or playing episode
 
 Help & Exit:
   ?              Toggle this help screen
@@ -438,12 +1564,12 @@ Help & Exit:
     frame.render_widget(paragraph, area);
 }
 
-fn draw_info_popup(frame: &mut Frame, app: &App, theme: &Theme) {
+fn draw_info_popup(frame: &mut Frame, app: &App, theme: &Theme) -> Option<Placement> {
     let area = centered_rect(70, 70, frame.area());
 
     // Get the currently selected episode's info
-    let (title, published, description, duration) = if let Some(podcast) = app.selected_podcast() {
-        if let Some(episode) = podcast.episodes.get(app.selected_episode_index) {
+    let (title, published, description, duration) = if app.selected_podcast().is_some() {
+        if let Some(episode) = app.selected_episode() {
             let duration_str = if let Some(dur) = episode.duration {
                 let mins = dur.as_secs() / 60;
                 let secs = dur.as_secs() % 60;
@@ -484,59 +1610,131 @@ fn draw_info_popup(frame: &mut Frame, app: &App, theme: &Theme) {
         .style(Style::default()
             .bg(theme.popup_bg_color())
             .fg(theme.popup_fg_color()));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    // Episode artwork, or the podcast cover, above the text
+    let art_url = app
+        .selected_episode()
+        .and_then(|e| e.image_url.as_deref())
+        .or_else(|| app.selected_podcast()?.image_url.as_deref());
+    let art_height = if art_url.is_some() && app.artwork.is_enabled() { (inner.height / 2).min(12) } else { 0 };
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(art_height), Constraint::Min(1)])
+        .split(inner);
 
     let paragraph = Paragraph::new(info_text)
-        .block(block)
         .wrap(Wrap { trim: true })
         .alignment(Alignment::Left);
 
-    frame.render_widget(paragraph, area);
+    frame.render_widget(paragraph, rows[1]);
+
+    let art_area = Rect { height: rows[0].height.saturating_sub(1), ..rows[0] };
+    reserve_artwork(frame, app, art_url, art_area)
 }
 
-fn draw_chapters_popup(frame: &mut Frame, app: &App, theme: &Theme) {
+fn draw_bookmarks_popup(frame: &mut Frame, app: &App, popup: &BookmarksPopup, theme: &Theme) {
     let area = centered_rect(70, 70, frame.area());
+    let bookmarks = app.popup_bookmarks();
+
+    let mut lines = vec![
+        Line::raw(format!("Episode: {}", popup.episode_title)),
+        Line::raw(""),
+    ];
+    if bookmarks.is_empty() {
+        lines.push(Line::raw("No bookmarks yet. Press b while playing to add one."));
+    } else {
+        for (idx, bookmark) in bookmarks.iter().enumerate() {
+            let prefix = if idx == popup.selected { "▶ " } else { "  " };
+            lines.push(Line::raw(format!("{}{}", prefix, bookmark.label())));
+        }
+        lines.push(Line::raw(""));
+        lines.push(Line::raw("Navigation: j/k to move, Enter to jump, d to delete, B or Esc to close"));
+    }
 
-    let content = if let Some(chapter_list) = &app.cached_chapters {
-        if chapter_list.chapters.is_empty() {
-            "No chapters available for this episode.".to_string()
-        } else {
-            let episode = app.selected_podcast()
-                .and_then(|p| p.episodes.get(app.selected_episode_index));
+    frame.render_widget(Clear, area);
 
-            // Build chapter list with selection
+    let block = Block::default()
+        .title(" Bookmarks - Press B or Esc to close ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.popup_border_color()))
+        .style(Style::default()
+            .bg(theme.popup_bg_color())
+            .fg(theme.popup_fg_color()));
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .alignment(Alignment::Left),
+        area,
+    );
+}
+
+fn draw_chapters_popup(frame: &mut Frame, popup: &ChaptersPopup, player: &dyn Playback, theme: &Theme) {
+    let area = centered_rect(70, 70, frame.area());
+    let toc = popup.toc();
+
+    let content: Vec<Line> = match &popup.chapters {
+        None => vec![Line::raw("Loading chapters…")],
+        Some(Ok(list)) if !toc.is_empty() => {
+            // Only the playing episode has a chapter to highlight
+            let active = popup
+                .position_secs
+                .and_then(|_| list.toc_chapter_at(player.get_position().as_secs_f64()))
+                .map(|(i, _)| i);
+
+            // Build chapter list with selection, highlighting the chapter that is playing
             let mut lines = vec![
-                format!("Episode: {}", episode.map(|e| e.title.as_str()).unwrap_or("Unknown")),
-                String::new(),
-                "Chapters:".to_string(),
-                String::new(),
+                Line::raw(format!("Episode: {}", popup.episode_title)),
+                Line::raw(""),
+                Line::raw("Chapters:"),
+                Line::raw(""),
             ];
 
-            for (idx, chapter) in chapter_list.chapters.iter().enumerate() {
+            for (idx, chapter) in toc.iter().enumerate() {
                 let mins = (chapter.start_time / 60.0) as u64;
                 let secs = (chapter.start_time % 60.0) as u64;
-                let prefix = if idx == app.selected_chapter_index {
+                let prefix = if idx == popup.selected {
                     "▶ "
                 } else {
                     "  "
                 };
-                lines.push(format!("{}{:02}:{:02} - {}", prefix, mins, secs, chapter.title));
+                let mut text = format!("{}{:02}:{:02} - {}", prefix, mins, secs, chapter.title);
+                if let Some(location) = &chapter.location {
+                    text.push_str(&format!(" ({})", location.name));
+                }
+                if Some(idx) == active {
+                    lines.push(Line::from(Span::styled(
+                        format!("{}  ♪", text),
+                        Style::default().fg(theme.border_focused_color()).add_modifier(Modifier::BOLD),
+                    )));
+                } else {
+                    lines.push(Line::raw(text));
+                }
             }
 
-            lines.push(String::new());
-            lines.push("Navigation: j/k to move, Enter to jump, c or Esc to close".to_string());
+            lines.push(Line::raw(""));
+            lines.push(Line::raw("Navigation: j/k to move, Enter to jump, c or Esc to close"));
 
-            lines.join("\n")
+            lines
         }
-    } else {
-        "No chapters available for this episode.".to_string()
+        _ => vec![Line::raw("No chapters available for this episode.")],
     };
 
     // Clear the area
     frame.render_widget(Clear, area);
 
-    // Draw the chapters popup
+    // Draw the chapters popup, naming where the chapters came from
+    let source = popup
+        .chapters
+        .as_ref()
+        .and_then(|result| result.as_ref().ok()?.source)
+        .map(|source| format!(" (from {})", source.label()))
+        .unwrap_or_default();
     let block = Block::default()
-        .title(" Episode Chapters - Press c or Esc to close ")
+        .title(format!(" Episode Chapters{} - Press c or Esc to close ", source))
         .borders(Borders::ALL)
         .border_style(Style::default().fg(theme.popup_border_color()))
         .style(Style::default()